@@ -0,0 +1,208 @@
+// Game actions: systems ask for an action instead of a key, the bindings map each action
+// to any number of keys, gamepad buttons and stick directions.
+// Actions can also be pressed from code, so the gameplay runs without a keyboard.
+
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use crate::GameKeys;
+use crate::gamepad::PadInput;
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(ActionBindings::from_keys(&GameKeys::default()))
+        .insert_resource(ActionInput::default())
+        .add_system_to_stage(CoreStage::PreUpdate, resolve_actions.after("ReadGamepadsSystem"));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameAction {
+    ThrustLeft,
+    ThrustRight,
+    ThrustUp,
+    FireCannon,
+    FireSpecial,
+    Pause,
+    Back,
+    Confirm,
+    MenuUp,
+    MenuDown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhysicalInput {
+    Key(KeyCode),
+    PadButton(GamepadButtonType),
+    StickLeft,
+    StickRight,
+    StickUp,
+    StickDown,
+}
+
+pub struct ActionBindings {
+    map: HashMap<GameAction, Vec<PhysicalInput>>,
+}
+
+impl ActionBindings {
+    // redefinable keys and buttons come from GameKeys, the rest is fixed
+    pub fn from_keys(keys: &GameKeys) -> Self {
+        let mut bindings = ActionBindings { map: HashMap::default() };
+
+        bindings.bind(GameAction::ThrustLeft, PhysicalInput::Key(keys.left));
+        bindings.bind(GameAction::ThrustLeft, PhysicalInput::PadButton(keys.pad_left));
+        bindings.bind(GameAction::ThrustLeft, PhysicalInput::StickLeft);
+        bindings.bind(GameAction::ThrustRight, PhysicalInput::Key(keys.right));
+        bindings.bind(GameAction::ThrustRight, PhysicalInput::PadButton(keys.pad_right));
+        bindings.bind(GameAction::ThrustRight, PhysicalInput::StickRight);
+        bindings.bind(GameAction::ThrustUp, PhysicalInput::Key(keys.up));
+        bindings.bind(GameAction::ThrustUp, PhysicalInput::PadButton(keys.pad_up));
+        bindings.bind(GameAction::ThrustUp, PhysicalInput::StickUp);
+        bindings.bind(GameAction::FireCannon, PhysicalInput::Key(keys.fire));
+        bindings.bind(GameAction::FireCannon, PhysicalInput::PadButton(keys.pad_fire));
+        bindings.bind(GameAction::FireSpecial, PhysicalInput::Key(keys.down));
+        bindings.bind(GameAction::FireSpecial, PhysicalInput::PadButton(keys.pad_down));
+        bindings.bind(GameAction::Pause, PhysicalInput::Key(keys.pause));
+        bindings.bind(GameAction::Pause, PhysicalInput::PadButton(keys.pad_pause));
+
+        // East is a gameplay button by default, the pad goes back with Select
+        bindings.bind(GameAction::Back, PhysicalInput::Key(KeyCode::Escape));
+        bindings.bind(GameAction::Back, PhysicalInput::PadButton(GamepadButtonType::Select));
+        bindings.bind(GameAction::Confirm, PhysicalInput::Key(KeyCode::Return));
+        bindings.bind(GameAction::Confirm, PhysicalInput::Key(KeyCode::NumpadEnter));
+        bindings.bind(GameAction::Confirm, PhysicalInput::Key(KeyCode::Space));
+        bindings.bind(GameAction::Confirm, PhysicalInput::PadButton(GamepadButtonType::South));
+        bindings.bind(GameAction::MenuUp, PhysicalInput::Key(KeyCode::Up));
+        bindings.bind(GameAction::MenuUp, PhysicalInput::PadButton(GamepadButtonType::DPadUp));
+        bindings.bind(GameAction::MenuUp, PhysicalInput::StickUp);
+        bindings.bind(GameAction::MenuDown, PhysicalInput::Key(KeyCode::Down));
+        bindings.bind(GameAction::MenuDown, PhysicalInput::PadButton(GamepadButtonType::DPadDown));
+        bindings.bind(GameAction::MenuDown, PhysicalInput::StickDown);
+
+        bindings
+    }
+
+    pub fn bind(&mut self, action: GameAction, input: PhysicalInput) {
+        let inputs = self.map.entry(action).or_insert_with(Vec::new);
+        if !inputs.contains(&input) {
+            inputs.push(input);
+        }
+    }
+
+    pub fn inputs(&self, action: GameAction) -> &[PhysicalInput] {
+        match self.map.get(&action) {
+            Some(inputs) => inputs,
+            None => &[],
+        }
+    }
+}
+
+// actions in the current frame, filled in PreUpdate
+#[derive(Default)]
+pub struct ActionInput {
+    pressed: HashSet<GameAction>,
+    just_pressed: HashSet<GameAction>,
+    // pressed from code, held until released
+    injected: HashSet<GameAction>,
+    // pressed in the previous resolve, just_pressed is the difference
+    previous: HashSet<GameAction>,
+    // any key or gamepad button, for "press any key" screens
+    any_just_pressed: bool,
+    // just pressed since the last gameplay step, a press is kept through frames without a step
+    step_just_pressed: HashSet<GameAction>,
+}
+
+impl ActionInput {
+    pub fn pressed(&self, action: GameAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: GameAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    // for the systems of the fixed gameplay step
+    pub fn step_just_pressed(&self, action: GameAction) -> bool {
+        self.step_just_pressed.contains(&action)
+    }
+
+    pub fn any_just_pressed(&self) -> bool {
+        self.any_just_pressed || !self.just_pressed.is_empty()
+    }
+
+    // holds the action like a key would be held, it is just pressed at once
+    pub fn press(&mut self, action: GameAction) {
+        self.injected.insert(action);
+        if self.pressed.insert(action) {
+            self.just_pressed.insert(action);
+            self.step_just_pressed.insert(action);
+        }
+    }
+
+    pub fn release(&mut self, action: GameAction) {
+        self.injected.remove(&action);
+        self.pressed.remove(&action);
+        self.just_pressed.remove(&action);
+        self.step_just_pressed.remove(&action);
+    }
+
+    // the frame's presses are handled, systems running after this one do not see them
+    pub fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.step_just_pressed.clear();
+        self.any_just_pressed = false;
+    }
+
+    // a gameplay step has seen the presses
+    pub fn end_step(&mut self) {
+        self.step_just_pressed.clear();
+    }
+}
+
+fn input_pressed(
+    input: &PhysicalInput,
+    keyboard: &Input<KeyCode>,
+    gamepads: &Gamepads,
+    buttons: &Input<GamepadButton>,
+    pad: &PadInput) -> bool
+{
+    match input {
+        PhysicalInput::Key(key) => keyboard.pressed(*key),
+        PhysicalInput::PadButton(button_type) => gamepads.iter().any(|gamepad| buttons.pressed(GamepadButton(*gamepad, *button_type))),
+        PhysicalInput::StickLeft => pad.stick_left(),
+        PhysicalInput::StickRight => pad.stick_right(),
+        PhysicalInput::StickUp => pad.stick_up(),
+        PhysicalInput::StickDown => pad.stick_down(),
+    }
+}
+
+fn resolve_actions(
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    pad: Res<PadInput>,
+    game_keys: Res<GameKeys>,
+    mut bindings: ResMut<ActionBindings>,
+    mut actions: ResMut<ActionInput>)
+{
+    if game_keys.is_changed() {
+        *bindings = ActionBindings::from_keys(&game_keys);
+    }
+
+    let mut pressed = actions.injected.clone();
+    for (action, inputs) in bindings.map.iter() {
+        if inputs.iter().any(|input| input_pressed(input, &keyboard, &gamepads, &buttons, &pad)) {
+            pressed.insert(*action);
+        }
+    }
+
+    actions.just_pressed = pressed.difference(&actions.previous).copied().collect();
+    let just_pressed = actions.just_pressed.clone();
+    actions.step_just_pressed.extend(just_pressed);
+    actions.previous = pressed.clone();
+    actions.pressed = pressed;
+    actions.any_just_pressed = keyboard.get_just_pressed().len() > 0 || pad.any_button.is_some();
+}
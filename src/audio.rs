@@ -1,6 +1,8 @@
 use bevy_kira_audio::prelude::*;
 use bevy::prelude::{App, Plugin, StartupStage, Handle, Commands, ResMut, AssetServer};
 
+use crate::loading::LoadingAssets;
+
 pub struct GameAudioPlugin;
 
 impl Plugin for GameAudioPlugin {
@@ -63,29 +65,29 @@ pub struct Shooting09Channel;
 
 pub struct DeathSoundChannel;
 
-fn load_audio(mut commands: Commands, asset_server: ResMut<AssetServer>) {
+fn load_audio(mut commands: Commands, asset_server: ResMut<AssetServer>, mut loading: ResMut<LoadingAssets>) {
     println!("Load sounds");
     commands.insert_resource(Sounds {
-        cannon_shot: asset_server.load("sounds/ship_cannon_shot.wav"),
-        boom: asset_server.load("sounds/boom.wav"),
-        boom_base: asset_server.load("sounds/boom_base.wav"),
-        damage: asset_server.load("sounds/damage.wav"),
-        enemy_damage: asset_server.load("sounds/enemy_damage.wav"),
-        enemy_01_shot: asset_server.load("sounds/enemy_01_shot.wav"),
-        enemy_02_shot: asset_server.load("sounds/enemy_02_shot.wav"),
-        enemy_03_13_shot: asset_server.load("sounds/enemy_03_13_shot.wav"),
-        enemy_05_shot: asset_server.load("sounds/enemy_05_shot.wav"),
-        enemy_06_shot: asset_server.load("sounds/enemy_06_shot.wav"),
-        enemy_07_shot: asset_server.load("sounds/enemy_07_shot.wav"),
-        enemy_08_shot: asset_server.load("sounds/enemy_08_shot.wav"),
-        enemy_09_launch: asset_server.load("sounds/enemy_09_launch.wav"),
-        enemy_09_shot: asset_server.load("sounds/enemy_09_shot.wav"),
-        enemy_10: asset_server.load("sounds/enemy_10.wav"),
-        special_launch: asset_server.load("sounds/special_launch.wav"),
-        ball_bounce: asset_server.load("sounds/ball_bounce.wav"),
-        get_item: asset_server.load("sounds/get_item.wav"),
-        ship_damage: asset_server.load("sounds/ship_damage.wav"),
-        death: asset_server.load("sounds/death.wav"),
+        cannon_shot: loading.track(asset_server.load("sounds/ship_cannon_shot.wav")),
+        boom: loading.track(asset_server.load("sounds/boom.wav")),
+        boom_base: loading.track(asset_server.load("sounds/boom_base.wav")),
+        damage: loading.track(asset_server.load("sounds/damage.wav")),
+        enemy_damage: loading.track(asset_server.load("sounds/enemy_damage.wav")),
+        enemy_01_shot: loading.track(asset_server.load("sounds/enemy_01_shot.wav")),
+        enemy_02_shot: loading.track(asset_server.load("sounds/enemy_02_shot.wav")),
+        enemy_03_13_shot: loading.track(asset_server.load("sounds/enemy_03_13_shot.wav")),
+        enemy_05_shot: loading.track(asset_server.load("sounds/enemy_05_shot.wav")),
+        enemy_06_shot: loading.track(asset_server.load("sounds/enemy_06_shot.wav")),
+        enemy_07_shot: loading.track(asset_server.load("sounds/enemy_07_shot.wav")),
+        enemy_08_shot: loading.track(asset_server.load("sounds/enemy_08_shot.wav")),
+        enemy_09_launch: loading.track(asset_server.load("sounds/enemy_09_launch.wav")),
+        enemy_09_shot: loading.track(asset_server.load("sounds/enemy_09_shot.wav")),
+        enemy_10: loading.track(asset_server.load("sounds/enemy_10.wav")),
+        special_launch: loading.track(asset_server.load("sounds/special_launch.wav")),
+        ball_bounce: loading.track(asset_server.load("sounds/ball_bounce.wav")),
+        get_item: loading.track(asset_server.load("sounds/get_item.wav")),
+        ship_damage: loading.track(asset_server.load("sounds/ship_damage.wav")),
+        death: loading.track(asset_server.load("sounds/death.wav")),
         
         enemy_01_shot_counter: 0,
         enemy_05_shot_counter: 0,
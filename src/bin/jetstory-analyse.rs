@@ -0,0 +1,330 @@
+// Finds out which rooms of the maze the ship can get into.
+//
+// For every room the free positions of the ship centre are worked out on a 1 px grid,
+// with the collision rule and the wall boxes of the game (boxes_overlap and wall_box in mazefile.rs).
+// Free areas touching the screen edges are linked to the free areas of the neighbouring rooms,
+// following the maze edge policy, and the room graph is walked from the start position.
+// A base is reached when the ship can touch it from a free position it can get to,
+// so a base walled off inside a room that can be entered is reported too.
+//
+// Reports unreachable rooms, one-way links between rooms and bases that can not be reached.
+// Exits with status 1 when a room or a base can not be reached.
+//
+// usage: jetstory-analyse [maze_file] [tileset_file] [enemies_file] [-v]
+
+#[allow(dead_code)]
+#[path = "../mazefile.rs"]
+mod mazefile;
+
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use mazefile::{MazeData, MazeGrid, RoomData, FellowData, TilesetData, EnemyDefsData,
+    PLAYER_W, PLAYER_H, LEFT_EDGE, RIGHT_EDGE, TOP_EDGE, BOTTOM_EDGE, START_ROOM, PLAYER_START_X, PLAYER_START_Y,
+    CARRIER_TYPE, boxes_overlap, wall_box, enemy_start_centre};
+
+const BASE_TYPE: usize = 0;
+
+// ship centre positions, 1 px apart, from one step behind the left/bottom edge to one step behind the right/top edge
+// (game coordinates, y goes up)
+const GRID_X0: f32 = LEFT_EDGE - 1.0;
+const GRID_Y0: f32 = BOTTOM_EDGE - 1.0;
+const GRID_W: usize = (RIGHT_EDGE - LEFT_EDGE) as usize + 3;
+const GRID_H: usize = (TOP_EDGE - BOTTOM_EDGE) as usize + 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+const SIDES: [Side; 4] = [Side::Left, Side::Right, Side::Up, Side::Down];
+
+impl Side {
+    fn name(&self) -> &'static str {
+        match self {
+            Side::Left => "left",
+            Side::Right => "right",
+            Side::Up => "up",
+            Side::Down => "down",
+        }
+    }
+
+    fn direction(&self) -> (isize, isize) {
+        match self {
+            Side::Left => (0, -1),
+            Side::Right => (0, 1),
+            Side::Up => (-1, 0),
+            Side::Down => (1, 0),
+        }
+    }
+
+    // grid cells beyond the edge, the ship leaves the room there (see player_movement)
+    fn exit_cell(&self, n: usize) -> (usize, usize) {
+        match self {
+            Side::Left => (0, n),
+            Side::Right => (GRID_W - 1, n),
+            Side::Up => (n, GRID_H - 1),
+            Side::Down => (n, 0),
+        }
+    }
+
+    // grid cell where the ship appears in the next room after leaving through this side
+    fn entry_cell(&self, n: usize) -> (usize, usize) {
+        match self {
+            Side::Left => (GRID_W - 3, n),
+            Side::Right => (2, n),
+            Side::Up => (n, 0),
+            Side::Down => (n, GRID_H - 1),
+        }
+    }
+
+    fn cells(&self) -> usize {
+        match self {
+            Side::Left | Side::Right => GRID_H,
+            Side::Up | Side::Down => GRID_W,
+        }
+    }
+}
+
+// free areas of one room, area of every free grid cell
+struct RoomAreas {
+    area: Vec<Option<usize>>,
+    count: usize,
+}
+
+impl RoomAreas {
+    fn get(&self, (x, y): (usize, usize)) -> Option<usize> {
+        self.area[(y * GRID_W) + x]
+    }
+}
+
+fn cell_position(x: usize, y: usize) -> (f32, f32) {
+    (GRID_X0 + x as f32, GRID_Y0 + y as f32)
+}
+
+// grid cells where the ship box can overlap a box at centre with size, clipped to the grid
+fn cells_around(centre: (f32, f32), size: (f32, f32)) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+    let reach_x = (PLAYER_W + size.0) / 2.0 + 1.0;
+    let reach_y = (PLAYER_H + size.1) / 2.0 + 1.0;
+    let range = |from: f32, to: f32, len: usize| {
+        (from.floor().max(0.0) as usize).min(len)..(to.ceil().max(0.0) as usize + 1).min(len)
+    };
+    (
+        range(centre.0 - reach_x - GRID_X0, centre.0 + reach_x - GRID_X0, GRID_W),
+        range(centre.1 - reach_y - GRID_Y0, centre.1 + reach_y - GRID_Y0, GRID_H),
+    )
+}
+
+fn analyse_room(room: &RoomData, tileset: &TilesetData) -> RoomAreas {
+    let mut blocked = vec![false; GRID_W * GRID_H];
+
+    for wall in room.walls.iter() {
+        let collision = match tileset.tile(wall.id) {
+            Some(tile) if tile.solid => tile.collision,
+            _ => continue,
+        };
+        let (wall_position, wall_size) = wall_box(wall.x, wall.y, &collision);
+        let (xs, ys) = cells_around(wall_position, wall_size);
+
+        for y in ys {
+            for x in xs.clone() {
+                if boxes_overlap(cell_position(x, y), (PLAYER_W, PLAYER_H), wall_position, wall_size) {
+                    blocked[(y * GRID_W) + x] = true;
+                }
+            }
+        }
+    }
+
+    let mut areas = RoomAreas { area: vec![None; GRID_W * GRID_H], count: 0 };
+    let mut queue = VecDeque::new();
+
+    for start in 0..(GRID_W * GRID_H) {
+        if blocked[start] || areas.area[start].is_some() {
+            continue;
+        }
+
+        areas.area[start] = Some(areas.count);
+        queue.push_back(start);
+        while let Some(cell) = queue.pop_front() {
+            let x = cell % GRID_W;
+            let y = cell / GRID_W;
+            let mut neighbours = Vec::with_capacity(4);
+            if x > 0 { neighbours.push(cell - 1); }
+            if x + 1 < GRID_W { neighbours.push(cell + 1); }
+            if y > 0 { neighbours.push(cell - GRID_W); }
+            if y + 1 < GRID_H { neighbours.push(cell + GRID_W); }
+
+            for next in neighbours {
+                if !blocked[next] && areas.area[next].is_none() {
+                    areas.area[next] = Some(areas.count);
+                    queue.push_back(next);
+                }
+            }
+        }
+        areas.count += 1;
+    }
+
+    areas
+}
+
+// base at its start position, game coordinates
+struct Base {
+    enemy_seq: usize,
+    centre: (f32, f32),
+    hitbox: (f32, f32),
+}
+
+// a carried base is one carrier height above the carrier
+fn room_bases(room: &RoomData, enemy_defs: &EnemyDefsData) -> Vec<Base> {
+    let hitbox = |enemy_type: usize| enemy_defs.enemy(enemy_type).map(|def| (def.hitbox.w, def.hitbox.h));
+    let mut bases = Vec::new();
+
+    for (seq, enemy) in room.enemies.iter().enumerate() {
+        let centre = enemy_start_centre(enemy.x, enemy.y);
+        if enemy.enemy_type == BASE_TYPE {
+            if let Some(size) = hitbox(BASE_TYPE) {
+                bases.push(Base { enemy_seq: seq, centre, hitbox: size });
+            }
+        }
+        else if enemy.enemy_type == CARRIER_TYPE && matches!(enemy.fellow, Some(FellowData::Enemy { enemy_type: BASE_TYPE, .. })) {
+            if let (Some(size), Some(carrier)) = (hitbox(BASE_TYPE), enemy_defs.enemy(CARRIER_TYPE)) {
+                bases.push(Base { enemy_seq: seq, centre: (centre.0, centre.1 + carrier.h), hitbox: size });
+            }
+        }
+    }
+
+    bases
+}
+
+// the ship touches the box from a free position of an area it gets to
+fn box_reached(room_index: usize, areas: &RoomAreas, reached: &HashSet<(usize, usize)>, centre: (f32, f32), size: (f32, f32)) -> bool {
+    let (xs, ys) = cells_around(centre, size);
+    ys.into_iter().any(|y| xs.clone().any(|x| {
+        boxes_overlap(cell_position(x, y), (PLAYER_W, PLAYER_H), centre, size)
+            && areas.get((x, y)).is_some_and(|area| reached.contains(&(room_index, area)))
+    }))
+}
+
+fn exit_with_error(message: String) -> ! {
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().filter(|arg| arg != "-v").collect();
+    let verbose = std::env::args().any(|arg| arg == "-v");
+
+    let maze_file = args.get(1).map(|s| s.as_str()).unwrap_or(mazefile::MAZE_FILE);
+    let tileset_file = args.get(2).map(|s| s.as_str()).unwrap_or(mazefile::TILESET_FILE);
+    let enemies_file = args.get(3).map(|s| s.as_str()).unwrap_or(mazefile::ENEMIES_FILE);
+
+    let maze: MazeData = mazefile::read_maze(maze_file).unwrap_or_else(|e| exit_with_error(e.to_string()));
+    let grid: MazeGrid = maze.grid(maze_file).unwrap_or_else(|e| exit_with_error(e.to_string()));
+
+    let tileset: TilesetData = mazefile::read_tileset(tileset_file).unwrap_or_else(|e| exit_with_error(e.to_string()));
+    tileset.check_maze(&maze, maze_file).unwrap_or_else(|e| exit_with_error(e.to_string()));
+    let enemy_defs: EnemyDefsData = mazefile::read_enemy_defs(enemies_file).unwrap_or_else(|e| exit_with_error(e.to_string()));
+
+    // rooms in grid order
+    let mut rooms: Vec<&RoomData> = Vec::new();
+    for row in 0..grid.rows {
+        for col in 0..grid.cols {
+            match maze.rooms.iter().find(|room| room.row == row && room.col == col) {
+                Some(room) => rooms.push(room),
+                None => exit_with_error(format!("{}: room {},{} missing", maze_file, row, col)),
+            }
+        }
+    }
+
+    let areas: Vec<RoomAreas> = rooms.iter().map(|room| analyse_room(room, &tileset)).collect();
+
+    // links between free areas, node = (room index, area)
+    let mut links: BTreeSet<((usize, usize), (usize, usize))> = BTreeSet::new();
+
+    for (index, room) in rooms.iter().enumerate() {
+        let mut open_sides = Vec::new();
+
+        for side in SIDES.iter() {
+            let (d_row, d_col) = side.direction();
+            let next_index = grid.next_room(room.row, room.col, d_row, d_col)
+                .and_then(|(row, col)| grid.room_index(row, col));
+
+            let mut open = false;
+            for n in 0..side.cells() {
+                if let Some(from_area) = areas[index].get(side.exit_cell(n)) {
+                    open = true;
+                    if let Some(next_index) = next_index {
+                        if let Some(to_area) = areas[next_index].get(side.entry_cell(n)) {
+                            links.insert(((index, from_area), (next_index, to_area)));
+                        }
+                    }
+                }
+            }
+            if open {
+                open_sides.push(side.name());
+            }
+        }
+
+        if verbose {
+            println!("room {},{}: areas {}, open edges: {}", room.row, room.col, areas[index].count, open_sides.join(", "));
+        }
+    }
+
+    // walk the graph from the start position
+    let start_index = grid.room_index(START_ROOM.0, START_ROOM.1)
+        .unwrap_or_else(|| exit_with_error(String::from("start room outside the maze")));
+    let start_cell = ((PLAYER_START_X - GRID_X0).round() as usize, (PLAYER_START_Y - GRID_Y0).round() as usize);
+    let start_area = areas[start_index].get(start_cell)
+        .unwrap_or_else(|| exit_with_error(String::from("player start position is inside a wall")));
+
+    let mut reached: HashSet<(usize, usize)> = HashSet::new();
+    let mut queue = VecDeque::new();
+    reached.insert((start_index, start_area));
+    queue.push_back((start_index, start_area));
+    while let Some(node) = queue.pop_front() {
+        for (_, to) in links.range((node, (0, 0))..).take_while(|(from, _)| *from == node) {
+            if reached.insert(*to) {
+                queue.push_back(*to);
+            }
+        }
+    }
+
+    let mut unreachable_rooms = 0;
+    let mut unreachable_bases = 0;
+
+    for (index, room) in rooms.iter().enumerate() {
+        if !reached.iter().any(|(room_index, _)| *room_index == index) {
+            println!("room {},{}: unreachable", room.row, room.col);
+            unreachable_rooms += 1;
+        }
+
+        for base in room_bases(room, &enemy_defs) {
+            if !box_reached(index, &areas[index], &reached, base.centre, base.hitbox) {
+                println!("room {},{}: base (enemy {}) unreachable", room.row, room.col, base.enemy_seq);
+                unreachable_bases += 1;
+            }
+        }
+    }
+
+    let room_links: BTreeSet<(usize, usize)> = links.iter()
+        .filter(|((from, _), (to, _))| from != to)
+        .map(|((from, _), (to, _))| (*from, *to))
+        .collect();
+
+    let mut one_way_links = 0;
+    for (from, to) in room_links.iter() {
+        if !room_links.contains(&(*to, *from)) {
+            println!("room {},{} -> room {},{}: one-way link", rooms[*from].row, rooms[*from].col, rooms[*to].row, rooms[*to].col);
+            one_way_links += 1;
+        }
+    }
+
+    println!("Rooms: {}, unreachable: {}, one-way links: {}, unreachable bases: {}",
+        rooms.len(), unreachable_rooms, one_way_links, unreachable_bases);
+
+    if unreachable_rooms > 0 || unreachable_bases > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,32 @@
+// Converts the legacy per-room text files (rooms/, enemies/, items/) into a single maze file.
+//
+// usage: jetstory-convert [legacy_dir] [output_file] [rows] [cols]
+
+#[allow(dead_code)]
+#[path = "../mazefile.rs"]
+mod mazefile;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let dir = args.get(1).map(|s| s.as_str()).unwrap_or("assets/data");
+    let output = args.get(2).map(|s| s.as_str()).unwrap_or(mazefile::MAZE_FILE);
+    let rows = args.get(3).map(|s| s.parse::<usize>().expect("Invalid rows!")).unwrap_or(8);
+    let cols = args.get(4).map(|s| s.parse::<usize>().expect("Invalid cols!")).unwrap_or(16);
+
+    println!("Convert {} ({}x{}) -> {}", dir, rows, cols, output);
+
+    let maze = match mazefile::read_legacy_maze(dir, rows, cols) {
+        Ok(maze) => maze,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = mazefile::write_maze(output, &maze) {
+        eprintln!("Error: {}: {}", output, e);
+        std::process::exit(1);
+    }
+
+    println!("Rooms written: {}", maze.rooms.len());
+}
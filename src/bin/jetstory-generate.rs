@@ -0,0 +1,395 @@
+// Generates a random maze from a seed, in the same format as the shipped maze file.
+//
+// usage: jetstory-generate <seed> [output_file] [rows] [cols]
+//
+// The default output file is generated.ron, play it with: rth_bevy_jet-story --maze generated.ron
+// Rooms are joined by a random spanning tree with a few extra links, so every room can be reached.
+// Every room keeps a free cross through its centre to the openings in its border, obstacles only go
+// to the corners. Enemies get harder and more frequent, pickups rarer, with the distance from the start room.
+
+#[allow(dead_code)]
+#[path = "../mazefile.rs"]
+mod mazefile;
+
+use std::collections::VecDeque;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use mazefile::{MazeData, RoomData, WallData, EnemyData, FellowData, ItemData, EdgePolicy, ITEM_TYPE_MAX,
+    PLAY_AREA_W, PLAY_AREA_H, PLAYER_W, PLAYER_H, START_ROOM, PLAYER_START_X, PLAYER_START_Y, CARRIER_TYPE, CARRIED_ENEMY_TYPES};
+
+// player start in maze coordinates
+const PLAYER_START: (f32, f32) = (PLAYER_START_X, PLAY_AREA_H - PLAYER_START_Y);
+
+// border tiles of the tileset: 100x25 for top and bottom, 25x100 for left and right
+const BORDER_H_ID: usize = 1;
+const BORDER_V_ID: usize = 9;
+const BORDER: f32 = 25.0;
+const TILE: f32 = 100.0;
+// openings in the border (maze coordinates)
+const GAP_X: (f32, f32) = (300.0, 500.0);
+const GAP_Y: (f32, f32) = (200.0, 300.0);
+// free cross through the room centre, no obstacles there
+const CROSS_X: (f32, f32) = (275.0, 525.0);
+const CROSS_Y: (f32, f32) = (175.0, 325.0);
+
+const OUTPUT_FILE: &str = "generated.ron";
+
+// corner obstacles: id, width, height (collision rectangles of the tileset)
+const OBSTACLES: [(usize, f32, f32); 10] = [
+    (0, 100.0, 75.0), (2, 100.0, 100.0), (3, 100.0, 100.0), (5, 100.0, 100.0), (11, 25.0, 50.0),
+    (13, 100.0, 50.0), (14, 100.0, 25.0), (16, 100.0, 100.0), (19, 100.0, 100.0), (24, 100.0, 100.0),
+];
+
+const BASE_TYPE: usize = 0;
+const SPAWNER_TYPE: usize = 10;
+// base of the farthest room when there is no other free place, inside the free cross
+const CENTRE_BASE: (f32, f32) = (375.0, 225.0);
+const EASY_ENEMIES: [usize; 7] = [4, 8, 9, 11, 12, 14, 17];
+const MEDIUM_ENEMIES: [usize; 6] = [2, 7, 15, 16, 18, 19];
+const HARD_ENEMIES: [usize; 5] = [1, 3, 5, 6, 13];
+// item types, fuel and ammo are more common
+const ITEM_TYPES: [usize; 11] = [0, 0, 1, 2, 2, 3, 4, 5, 6, 7, 2];
+
+#[derive(Clone, Copy)]
+struct Rect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl Rect {
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.x < other.x + other.w && self.x + self.w > other.x && self.y < other.y + other.h && self.y + self.h > other.y
+    }
+}
+
+// open sides of a room: left, right, up, down
+type Openings = [bool; 4];
+
+fn wall_size(id: usize) -> (f32, f32) {
+    match id {
+        BORDER_H_ID => (TILE, BORDER),
+        BORDER_V_ID => (BORDER, TILE),
+        _ => OBSTACLES.iter().find(|(obstacle_id, _, _)| *obstacle_id == id).map(|(_, w, h)| (*w, *h)).unwrap_or((0.0, 0.0)),
+    }
+}
+
+fn enemy_rect(enemy_type: usize, x: f32, y: f32) -> Rect {
+    // spawn_room puts the enemy centre to x + 25, y + 25
+    let (w, h) = match enemy_type {
+        7 => (50.0, 46.0),
+        18 => (100.0, 100.0),
+        19 => (94.0, 50.0),
+        _ => (50.0, 50.0),
+    };
+    Rect { x: x + 25.0 - w / 2.0, y: y + 25.0 - h / 2.0, w, h }
+}
+
+fn item_rect(x: f32, y: f32) -> Rect {
+    Rect { x, y: y + 6.0, w: 49.0, h: 43.0 }
+}
+
+fn connect_rooms(rng: &mut StdRng, rows: usize, cols: usize) -> Vec<Openings> {
+    let mut openings = vec![[false; 4]; rows * cols];
+    let mut visited = vec![false; rows * cols];
+    let mut stack = vec![(START_ROOM.0 * cols) + START_ROOM.1];
+    visited[stack[0]] = true;
+
+    let neighbours = |index: usize| -> Vec<(usize, usize, usize)> {
+        // (side, neighbour index, side seen from the neighbour)
+        let (row, col) = (index / cols, index % cols);
+        let mut list = Vec::new();
+        if col > 0 { list.push((0, index - 1, 1)); }
+        if col + 1 < cols { list.push((1, index + 1, 0)); }
+        if row > 0 { list.push((2, index - cols, 3)); }
+        if row + 1 < rows { list.push((3, index + cols, 2)); }
+        list
+    };
+
+    // random depth first spanning tree
+    while let Some(&index) = stack.last() {
+        let unvisited: Vec<(usize, usize, usize)> = neighbours(index).into_iter().filter(|(_, next, _)| !visited[*next]).collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (side, next, back) = unvisited[rng.gen_range(0..unvisited.len())];
+        openings[index][side] = true;
+        openings[next][back] = true;
+        visited[next] = true;
+        stack.push(next);
+    }
+
+    // a few loops
+    for index in 0..(rows * cols) {
+        for (side, next, back) in neighbours(index) {
+            if next > index && rng.gen_bool(0.15) {
+                openings[index][side] = true;
+                openings[next][back] = true;
+            }
+        }
+    }
+
+    openings
+}
+
+fn room_distances(openings: &[Openings], rows: usize, cols: usize) -> Vec<usize> {
+    let mut distances = vec![usize::MAX; rows * cols];
+    let start = (START_ROOM.0 * cols) + START_ROOM.1;
+    let mut queue = VecDeque::new();
+    distances[start] = 0;
+    queue.push_back(start);
+
+    while let Some(index) = queue.pop_front() {
+        let steps = [(0, index.wrapping_sub(1)), (1, index + 1), (2, index.wrapping_sub(cols)), (3, index + cols)];
+        for (side, next) in steps.iter() {
+            if openings[index][*side] && distances[*next] == usize::MAX {
+                distances[*next] = distances[index] + 1;
+                queue.push_back(*next);
+            }
+        }
+    }
+
+    distances
+}
+
+fn generate_walls(rng: &mut StdRng, open: &Openings, difficulty: f32) -> Vec<WallData> {
+    let mut walls = Vec::new();
+
+    // top and bottom border
+    let mut x = 0.0;
+    while x < PLAY_AREA_W {
+        let in_gap = x >= GAP_X.0 && x < GAP_X.1;
+        if !(in_gap && open[2]) {
+            walls.push(WallData { id: BORDER_H_ID, x, y: 0.0 });
+        }
+        if !(in_gap && open[3]) {
+            walls.push(WallData { id: BORDER_H_ID, x, y: PLAY_AREA_H - BORDER });
+        }
+        x += TILE;
+    }
+
+    // left and right border
+    let mut y = 0.0;
+    while y < PLAY_AREA_H {
+        let in_gap = y >= GAP_Y.0 && y < GAP_Y.1;
+        if !(in_gap && open[0]) {
+            walls.push(WallData { id: BORDER_V_ID, x: 0.0, y });
+        }
+        if !(in_gap && open[1]) {
+            walls.push(WallData { id: BORDER_V_ID, x: PLAY_AREA_W - BORDER, y });
+        }
+        y += TILE;
+    }
+
+    // obstacles in the corners, outside the free cross
+    let corners = [
+        (BORDER, CROSS_X.0, BORDER, CROSS_Y.0),
+        (CROSS_X.1, PLAY_AREA_W - BORDER, BORDER, CROSS_Y.0),
+        (BORDER, CROSS_X.0, CROSS_Y.1, PLAY_AREA_H - BORDER),
+        (CROSS_X.1, PLAY_AREA_W - BORDER, CROSS_Y.1, PLAY_AREA_H - BORDER),
+    ];
+    let mut placed: Vec<Rect> = Vec::new();
+    for (x0, x1, y0, y1) in corners.iter() {
+        let count = rng.gen_range(0..=(1 + (difficulty * 2.0) as usize));
+        for _ in 0..count {
+            let (id, w, h) = OBSTACLES[rng.gen_range(0..OBSTACLES.len())];
+            let steps_x = ((x1 - x0 - w) / BORDER) as usize;
+            let steps_y = ((y1 - y0 - h) / BORDER) as usize;
+            let rect = Rect {
+                x: x0 + (rng.gen_range(0..=steps_x) as f32) * BORDER,
+                y: y0 + (rng.gen_range(0..=steps_y) as f32) * BORDER,
+                w,
+                h,
+            };
+            if !placed.iter().any(|other| other.overlaps(&rect)) {
+                placed.push(rect);
+                walls.push(WallData { id, x: rect.x, y: rect.y });
+            }
+        }
+    }
+
+    walls
+}
+
+struct RoomBuilder<'a> {
+    rng: &'a mut StdRng,
+    occupied: Vec<Rect>,
+}
+
+impl<'a> RoomBuilder<'a> {
+    // random free position on the 25 px grid, None if there is no room left
+    fn place(&mut self, rect_at: &dyn Fn(f32, f32) -> Rect, area: Rect) -> Option<(f32, f32)> {
+        for _ in 0..50 {
+            let x = area.x + (self.rng.gen_range(0..=((area.w / BORDER) as usize)) as f32) * BORDER;
+            let y = area.y + (self.rng.gen_range(0..=((area.h / BORDER) as usize)) as f32) * BORDER;
+            let rect = rect_at(x, y);
+            let inside = rect.x >= BORDER && rect.y >= BORDER
+                && rect.x + rect.w <= PLAY_AREA_W - BORDER && rect.y + rect.h <= PLAY_AREA_H - BORDER;
+            if inside && !self.occupied.iter().any(|other| other.overlaps(&rect)) {
+                self.occupied.push(rect);
+                return Some((x, y));
+            }
+        }
+        None
+    }
+
+    fn pick_enemy(&mut self, difficulty: f32) -> usize {
+        let roll: f32 = self.rng.gen();
+        if roll < difficulty * 0.6 {
+            HARD_ENEMIES[self.rng.gen_range(0..HARD_ENEMIES.len())]
+        }
+        else if roll < 0.3 + difficulty * 0.5 {
+            MEDIUM_ENEMIES[self.rng.gen_range(0..MEDIUM_ENEMIES.len())]
+        }
+        else {
+            EASY_ENEMIES[self.rng.gen_range(0..EASY_ENEMIES.len())]
+        }
+    }
+
+    fn subtype(&mut self, enemy_type: usize) -> usize {
+        match enemy_type {
+            1 | 8 | CARRIER_TYPE => self.rng.gen_range(0..2),
+            _ => 0,
+        }
+    }
+
+    fn add_enemy(&mut self, enemies: &mut Vec<EnemyData>, enemy_type: usize, difficulty: f32) -> bool {
+        let area = Rect { x: BORDER, y: BORDER, w: PLAY_AREA_W - 150.0, h: PLAY_AREA_H - 100.0 };
+        if let Some((x, y)) = self.place(&|x, y| enemy_rect(enemy_type, x, y), area) {
+            let mut fellow = None;
+            if enemy_type == CARRIER_TYPE {
+                if self.rng.gen_bool(0.6) {
+                    fellow = Some(FellowData::Item { x, y: y - 50.0, item_type: self.rng.gen_range(0..=ITEM_TYPE_MAX) });
+                }
+                else {
+                    let mut fellow_type = self.pick_enemy(difficulty);
+                    if !CARRIED_ENEMY_TYPES.contains(&fellow_type) {
+                        fellow_type = CARRIED_ENEMY_TYPES[self.rng.gen_range(1..CARRIED_ENEMY_TYPES.len())];
+                    }
+                    let subtype = self.subtype(fellow_type);
+                    fellow = Some(FellowData::Enemy { x, y: y - 50.0, enemy_type: fellow_type, subtype });
+                }
+            }
+            let subtype = self.subtype(enemy_type);
+            enemies.push(EnemyData { x, y, enemy_type, subtype, fellow });
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    fn add_item(&mut self, items: &mut Vec<ItemData>) {
+        // pickups stay in the free cross, so they can always be reached
+        let area = if self.rng.gen_bool(0.5) {
+            Rect { x: BORDER, y: CROSS_Y.0, w: PLAY_AREA_W - 100.0, h: CROSS_Y.1 - CROSS_Y.0 - 50.0 }
+        }
+        else {
+            Rect { x: CROSS_X.0, y: BORDER, w: CROSS_X.1 - CROSS_X.0 - 50.0, h: PLAY_AREA_H - 100.0 }
+        };
+        if let Some((x, y)) = self.place(&item_rect, area) {
+            items.push(ItemData { x, y, item_type: ITEM_TYPES[self.rng.gen_range(0..ITEM_TYPES.len())] });
+        }
+    }
+}
+
+fn generate_maze(seed: u64, rows: usize, cols: usize) -> MazeData {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let openings = connect_rooms(&mut rng, rows, cols);
+    let distances = room_distances(&openings, rows, cols);
+    let max_distance = distances.iter().copied().max().unwrap_or(0).max(1);
+    let farthest = distances.iter().position(|d| *d == max_distance).unwrap_or(0);
+
+    let mut rooms = Vec::new();
+    let mut bases = 0;
+
+    for index in 0..(rows * cols) {
+        let (row, col) = (index / cols, index % cols);
+        let difficulty = distances[index] as f32 / max_distance as f32;
+        let walls = generate_walls(&mut rng, &openings[index], difficulty);
+
+        let mut builder = RoomBuilder { rng: &mut rng, occupied: Vec::new() };
+        for wall in walls.iter() {
+            let (w, h) = wall_size(wall.id);
+            builder.occupied.push(Rect { x: wall.x, y: wall.y, w, h });
+        }
+        let mut enemies = Vec::new();
+        let mut items = Vec::new();
+
+        if (row, col) == START_ROOM {
+            // keep the ship start position free
+            builder.occupied.push(Rect {
+                x: PLAYER_START.0 - PLAYER_W, y: PLAYER_START.1 - PLAYER_H, w: PLAYER_W * 2.0, h: PLAYER_H * 2.0 });
+            builder.add_item(&mut items);
+        }
+        else {
+            // the farthest room always has a base, so the maze can be won
+            let base = builder.rng.gen_bool((0.25 + 0.2 * difficulty) as f64) || index == farthest;
+            if base && !builder.add_enemy(&mut enemies, BASE_TYPE, difficulty) && index == farthest {
+                let (x, y) = CENTRE_BASE;
+                builder.occupied.push(enemy_rect(BASE_TYPE, x, y));
+                enemies.push(EnemyData { x, y, enemy_type: BASE_TYPE, subtype: 0, fellow: None });
+            }
+            if builder.rng.gen_bool((0.1 + 0.3 * difficulty) as f64) {
+                builder.add_enemy(&mut enemies, SPAWNER_TYPE, difficulty);
+            }
+            if builder.rng.gen_bool((0.1 + 0.1 * difficulty) as f64) {
+                builder.add_enemy(&mut enemies, CARRIER_TYPE, difficulty);
+            }
+            let count = 1 + (difficulty * 3.0) as usize + builder.rng.gen_range(0..=1);
+            for _ in 0..count {
+                let enemy_type = builder.pick_enemy(difficulty);
+                builder.add_enemy(&mut enemies, enemy_type, difficulty);
+            }
+            if builder.rng.gen_bool((0.6 - 0.3 * difficulty) as f64) {
+                builder.add_item(&mut items);
+            }
+        }
+
+        bases += enemies.iter().filter(|enemy| enemy.enemy_type == BASE_TYPE
+            || matches!(enemy.fellow, Some(FellowData::Enemy { enemy_type: BASE_TYPE, .. }))).count();
+
+        rooms.push(RoomData { row, col, name: None, walls, enemies, items });
+    }
+
+    println!("Bases: {}", bases);
+
+    MazeData { rows, cols, edges: EdgePolicy::Solid, rooms }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let seed = match args.get(1).map(|s| s.parse::<u64>()) {
+        Some(Ok(seed)) => seed,
+        _ => {
+            eprintln!("usage: jetstory-generate <seed> [output_file] [rows] [cols]");
+            eprintln!("default output_file: {}, played with: rth_bevy_jet-story --maze <output_file>", OUTPUT_FILE);
+            std::process::exit(1);
+        }
+    };
+    let output = args.get(2).map(|s| s.as_str()).unwrap_or(OUTPUT_FILE);
+    let rows = args.get(3).map(|s| s.parse::<usize>().expect("Invalid rows!")).unwrap_or(8);
+    let cols = args.get(4).map(|s| s.parse::<usize>().expect("Invalid cols!")).unwrap_or(16);
+    // the bases can not be in the start room, a second room is needed
+    if rows == 0 || cols == 0 || rows * cols < 2 {
+        eprintln!("Error: the maze needs at least 2 rooms, got {}x{}", rows, cols);
+        std::process::exit(1);
+    }
+
+    println!("Generate maze {}x{}, seed {} -> {}", rows, cols, seed, output);
+
+    let maze = generate_maze(seed, rows, cols);
+    if let Err(e) = mazefile::write_maze(output, &maze) {
+        eprintln!("Error: {}: {}", output, e);
+        std::process::exit(1);
+    }
+
+    println!("Rooms written: {}", maze.rooms.len());
+    println!("Play it with: rth_bevy_jet-story --maze {}", output);
+}
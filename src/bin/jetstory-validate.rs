@@ -0,0 +1,191 @@
+// Checks the maze file without starting the game and reports every problem found.
+// Exits with status 1 when the maze has problems, so it can be used in the content pipeline.
+//
+// usage: jetstory-validate [maze_file] [tileset_file] [enemies_file]
+//
+// Enemy types and item types out of range are rejected already when the file is read.
+
+#[allow(dead_code)]
+#[path = "../mazefile.rs"]
+mod mazefile;
+
+use mazefile::{MazeData, RoomData, FellowData, TilesetData, EnemyDefsData, read_png_size,
+    PLAY_AREA_W, PLAY_AREA_H, ITEM_W, ITEM_H, START_ROOM, PLAYER_START_X, PLAYER_START_Y, CARRIER_TYPE, CARRIED_ENEMY_TYPES};
+
+// player start in maze coordinates
+const PLAYER_START: (f32, f32) = (PLAYER_START_X, PLAY_AREA_H - PLAYER_START_Y);
+
+struct Validator {
+    tileset: TilesetData,
+    enemy_defs: EnemyDefsData,
+    problems: Vec<String>,
+}
+
+impl Validator {
+    fn report(&mut self, room: &RoomData, problem: String) {
+        self.problems.push(format!("room {},{}: {}", room.row, room.col, problem));
+    }
+
+    fn check_position(&mut self, room: &RoomData, what: &str, x: f32, y: f32) {
+        if !(0.0..PLAY_AREA_W).contains(&x) || !(0.0..PLAY_AREA_H).contains(&y) {
+            self.report(room, format!("{} at {}, {} is outside the {}x{} play area", what, x, y, PLAY_AREA_W, PLAY_AREA_H));
+        }
+    }
+
+    fn check_walls(&mut self, room: &RoomData) {
+        for (seq, wall) in room.walls.iter().enumerate() {
+            self.check_position(room, format!("wall {}", seq).as_str(), wall.x, wall.y);
+            if self.tileset.tile(wall.id).is_none() {
+                self.report(room, format!("wall {}: id {} is not in the tileset", seq, wall.id));
+            }
+        }
+    }
+
+    fn check_enemies(&mut self, room: &RoomData) {
+        for (seq, enemy) in room.enemies.iter().enumerate() {
+            self.check_position(room, format!("enemy {}", seq).as_str(), enemy.x, enemy.y);
+            if self.enemy_defs.enemy(enemy.enemy_type).is_none() {
+                self.report(room, format!("enemy {}: enemy type {} has no definition", seq, enemy.enemy_type));
+            }
+
+            match (&enemy.fellow, enemy.enemy_type == CARRIER_TYPE) {
+                (None, true) => {
+                    self.report(room, format!("enemy {}: carrier has no fellow", seq));
+                },
+                (Some(_), false) => {
+                    self.report(room, format!("enemy {}: fellow on enemy type {}, only carrier ({}) can have one", seq, enemy.enemy_type, CARRIER_TYPE));
+                },
+                (Some(FellowData::Enemy { enemy_type, .. }), true) if !CARRIED_ENEMY_TYPES.contains(enemy_type) => {
+                    self.report(room, format!("enemy {}: carrier can not carry enemy type {}", seq, enemy_type));
+                },
+                (Some(FellowData::Enemy { enemy_type, .. }), true) if self.enemy_defs.enemy(*enemy_type).is_none() => {
+                    self.report(room, format!("enemy {}: fellow enemy type {} has no definition", seq, enemy_type));
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn check_items(&mut self, room: &RoomData) {
+        for (seq, item) in room.items.iter().enumerate() {
+            self.check_position(room, format!("item {}", seq).as_str(), item.x, item.y);
+        }
+    }
+
+    fn check_spawn_points(&mut self, room: &RoomData) {
+        let mut spawn_points: Vec<(String, f32, f32)> = Vec::new();
+
+        for (seq, enemy) in room.enemies.iter().enumerate() {
+            // undefined types are reported by check_enemies
+            let (w, h) = match self.enemy_defs.enemy(enemy.enemy_type) {
+                Some(def) => (def.w, def.h),
+                None => continue,
+            };
+            spawn_points.push((format!("enemy {}", seq), enemy.x + w / 2.0, enemy.y + h / 2.0));
+        }
+        for (seq, item) in room.items.iter().enumerate() {
+            spawn_points.push((format!("item {}", seq), item.x + ITEM_W / 2.0, item.y + ITEM_H / 2.0));
+        }
+        if (room.row, room.col) == START_ROOM {
+            spawn_points.push((String::from("player start"), PLAYER_START.0, PLAYER_START.1));
+        }
+
+        for (seq, wall) in room.walls.iter().enumerate() {
+            let collision = match self.tileset.tile(wall.id) {
+                Some(tile) if tile.solid => tile.collision,
+                _ => continue,
+            };
+            let (wall_x, wall_y) = (wall.x + collision.x, wall.y + collision.y);
+            for (what, x, y) in spawn_points.iter() {
+                if *x > wall_x && *x < wall_x + collision.w && *y > wall_y && *y < wall_y + collision.h {
+                    self.report(room, format!("wall {} (id {}) overlaps {} spawn point", seq, wall.id, what));
+                }
+            }
+        }
+    }
+
+    fn check_grid(&mut self, maze: &MazeData) {
+        for row in 0..maze.rows {
+            for col in 0..maze.cols {
+                let count = maze.rooms.iter().filter(|room| room.row == row && room.col == col).count();
+                if count != 1 {
+                    self.problems.push(format!("room {},{}: defined {} times, expected once", row, col, count));
+                }
+            }
+        }
+        for room in maze.rooms.iter() {
+            if room.row >= maze.rows || room.col >= maze.cols {
+                self.report(room, format!("outside the {}x{} maze", maze.rows, maze.cols));
+            }
+        }
+        if let Err(e) = maze.grid(mazefile::MAZE_FILE) {
+            self.problems.push(format!("{}: expected {}", e.field, e.expected));
+        }
+    }
+
+    fn check_tileset(&mut self, tileset_file: &str) {
+        let mut problems = Vec::new();
+        for tile in self.tileset.tiles.iter() {
+            let file_name = format!("assets/{}", tile.image);
+            match read_png_size(&file_name) {
+                None => problems.push(format!("{}: tile {}: {} is not a readable PNG image", tileset_file, tile.id, file_name)),
+                Some((w, h)) if w != tile.w || h != tile.h => {
+                    problems.push(format!("{}: tile {}: size {}x{}, image is {}x{}", tileset_file, tile.id, tile.w, tile.h, w, h));
+                },
+                _ => {}
+            }
+        }
+        self.problems.append(&mut problems);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let maze_file = args.get(1).map(|s| s.as_str()).unwrap_or(mazefile::MAZE_FILE);
+    let tileset_file = args.get(2).map(|s| s.as_str()).unwrap_or(mazefile::TILESET_FILE);
+    let enemies_file = args.get(3).map(|s| s.as_str()).unwrap_or(mazefile::ENEMIES_FILE);
+
+    let maze = match mazefile::read_maze(maze_file) {
+        Ok(maze) => maze,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let tileset = match mazefile::read_tileset(tileset_file) {
+        Ok(tileset) => tileset,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let enemy_defs = match mazefile::read_enemy_defs(enemies_file) {
+        Ok(enemy_defs) => enemy_defs,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut validator = Validator { tileset, enemy_defs, problems: Vec::new() };
+
+    validator.check_tileset(tileset_file);
+    validator.check_grid(&maze);
+    for room in maze.rooms.iter() {
+        validator.check_walls(room);
+        validator.check_enemies(room);
+        validator.check_items(room);
+        validator.check_spawn_points(room);
+    }
+
+    for problem in validator.problems.iter() {
+        println!("{}: {}", maze_file, problem);
+    }
+    println!("Rooms checked: {}, problems: {}", maze.rooms.len(), validator.problems.len());
+
+    if !validator.problems.is_empty() {
+        std::process::exit(1);
+    }
+}
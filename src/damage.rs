@@ -0,0 +1,254 @@
+// Damage pipeline: the ship and its weapons send DamageEvent for every enemy part they hit,
+// apply_damage takes the health and sends EnemyKilledEvent for every enemy that dies.
+// Score, bases, explosions and sounds of the kills are handled by the subscribers below,
+// so a new weapon only has to find what it hit and send the damage.
+
+use bevy::prelude::*;
+use bevy_kira_audio::{AudioControl, AudioChannel};
+
+use crate::collision_check;
+use crate::gamestep::GAME_STEP;
+use crate::enemies::{EnemyDefs, EnemyComponent};
+use crate::maze::Maze;
+use crate::items::{ITEM_W, ITEM_H};
+use crate::explosions::ExplosionsImages;
+use crate::infobar::{ScoreChange, BaseCountChange};
+use crate::audio::{Sounds, SfxChannel};
+use crate::random::GameRng;
+
+pub const BASE_ENEMY_TYPE: usize = 0;
+
+// more than any enemy has, for weapons that destroy whatever they touch
+pub const INSTANT_KILL: i16 = i16::MAX;
+
+pub struct DamagePlugin;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<DamageEvent>()
+        .add_event::<EnemyKilledEvent>()
+        .add_system_set_to_stage(GAME_STEP, SystemSet::new()
+            .with_system(apply_damage.label("ApplyDamageSystem").after("DamageSourceSystem"))
+            .with_system(score_kills.after("ApplyDamageSystem"))
+            .with_system(count_bases.after("ApplyDamageSystem"))
+            .with_system(kill_explosions.after("ApplyDamageSystem"))
+            .with_system(kill_sounds.after("ApplyDamageSystem"))
+        );
+    }
+}
+
+// part of the enemy that was hit, a carrier takes its fellow along in collisions
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HitPart {
+    Enemy,
+    FellowEnemy,
+    // only the ship picks it up, weapons pass through
+    FellowItem,
+}
+
+pub struct DamageEvent {
+    // entity with the EnemyComponent, the carrier for its fellow
+    pub entity: Entity,
+    pub part: HitPart,
+    pub amount: i16,
+    // enemy damage sound when the enemy survives, kills are heard through EnemyKilledEvent
+    pub hit_sound: bool,
+}
+
+pub struct EnemyKilledEvent {
+    pub enemy_type: usize,
+    pub position: Vec3,
+    // false when it only went down with its carrier or fellow
+    pub scored: bool,
+}
+
+// parts of the enemy overlapped by the box, the fellow first
+pub fn enemy_hits(
+    position: Vec3,
+    size: Vec2,
+    enemy_component: &EnemyComponent,
+    enemy_position: Vec3,
+    maze: &Maze,
+    enemy_defs: &EnemyDefs) -> Vec<HitPart>
+{
+    let mut hits = Vec::new();
+
+    if !enemy_component.is_from_10 {
+        let enemy = &maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
+        // fellow is one carrier height above the carrier
+        let fellow_position = enemy_position + Vec3::new(0.0, enemy_defs.size(enemy_component.enemy_type).y, 0.0);
+
+        if let Some(fellow) = &enemy.fellow_enemy {
+            if fellow.health > 0 && collision_check(position, size, fellow_position, enemy_defs.hitbox(fellow.enemy_type)) {
+                hits.push(HitPart::FellowEnemy);
+            }
+        }
+        else if let Some(fellow) = &enemy.fellow_item {
+            if !fellow.collected && collision_check(position, size, fellow_position, Vec2::new(ITEM_W, ITEM_H)) {
+                hits.push(HitPart::FellowItem);
+            }
+        }
+    }
+
+    if collision_check(position, size, enemy_position, enemy_defs.hitbox(enemy_component.enemy_type)) {
+        hits.push(HitPart::Enemy);
+    }
+
+    hits
+}
+
+fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut killed_events: EventWriter<EnemyKilledEvent>,
+    enemies_query: Query<(&EnemyComponent, &Transform)>,
+    mut maze: ResMut<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+    sounds: Res<Sounds>)
+{
+    for damage in damage_events.iter() {
+        let (enemy_component, enemy_transform) = match enemies_query.get(damage.entity) {
+            Ok(enemy) => enemy,
+            Err(_) => continue,
+        };
+        let position = enemy_transform.translation;
+        let fellow_position = position + Vec3::new(0.0, enemy_defs.size(enemy_component.enemy_type).y, 0.0);
+
+        // from 10
+        if enemy_component.is_from_10 {
+            let room = &mut maze.rooms[enemy_component.room_seq];
+            // not found - killed by an earlier hit
+            let idx = match room.enemies_from_10.iter().position(|enemy| enemy.enemy_seq == enemy_component.enemy_seq) {
+                Some(idx) => idx,
+                None => continue,
+            };
+            let enemy = &mut room.enemies_from_10[idx];
+            enemy.health = enemy.health.saturating_sub(damage.amount);
+            if enemy.health > 0 {
+                if damage.hit_sound {
+                    sfx.play(sounds.enemy_damage.clone());
+                }
+                continue;
+            }
+            room.enemies_from_10.remove(idx);
+            commands.entity(damage.entity).despawn_recursive();
+            killed_events.send(EnemyKilledEvent { enemy_type: enemy_component.enemy_type, position, scored: true });
+            continue;
+        }
+
+        let enemy = &mut maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
+        if enemy.health <= 0 {
+            // killed by an earlier hit
+            continue;
+        }
+
+        match damage.part {
+            HitPart::Enemy => {
+                enemy.health = enemy.health.saturating_sub(damage.amount);
+                if enemy.health > 0 {
+                    if damage.hit_sound {
+                        sfx.play(sounds.enemy_damage.clone());
+                    }
+                    continue;
+                }
+                killed_events.send(EnemyKilledEvent { enemy_type: enemy.enemy_type, position, scored: true });
+
+                // destroy also fellow
+                if let Some(fellow) = enemy.fellow_enemy.as_mut() {
+                    if fellow.health > 0 {
+                        fellow.health = 0;
+                        killed_events.send(EnemyKilledEvent { enemy_type: fellow.enemy_type, position: fellow_position, scored: false });
+                    }
+                }
+            },
+            HitPart::FellowEnemy => {
+                let fellow = match enemy.fellow_enemy.as_mut() {
+                    Some(fellow) if fellow.health > 0 => fellow,
+                    _ => continue,
+                };
+                fellow.health = fellow.health.saturating_sub(damage.amount);
+                if fellow.health > 0 {
+                    if damage.hit_sound {
+                        sfx.play(sounds.enemy_damage.clone());
+                    }
+                    continue;
+                }
+                killed_events.send(EnemyKilledEvent { enemy_type: fellow.enemy_type, position: fellow_position, scored: true });
+
+                // destroy also carrier
+                enemy.health = 0;
+                killed_events.send(EnemyKilledEvent { enemy_type: enemy.enemy_type, position, scored: false });
+            },
+            HitPart::FellowItem => {
+                // item was picked up, the carrier goes with it
+                enemy.health = 0;
+                killed_events.send(EnemyKilledEvent { enemy_type: enemy.enemy_type, position, scored: false });
+            },
+        }
+
+        // fellow is a child of the carrier
+        commands.entity(damage.entity).despawn_recursive();
+    }
+}
+
+fn score_kills(
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    mut maze: ResMut<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    mut change_score_event: EventWriter<ScoreChange>)
+{
+    let mut changed = false;
+    for killed in killed_events.iter().filter(|killed| killed.scored) {
+        maze.score += enemy_defs.def(killed.enemy_type).score;
+        changed = true;
+    }
+    if changed {
+        change_score_event.send(ScoreChange(maze.score));
+    }
+}
+
+fn count_bases(
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    mut maze: ResMut<Maze>,
+    mut change_base_count_event: EventWriter<BaseCountChange>)
+{
+    let mut changed = false;
+    for _ in killed_events.iter().filter(|killed| killed.enemy_type == BASE_ENEMY_TYPE) {
+        maze.bases -= 1;
+        changed = true;
+    }
+    if changed {
+        change_base_count_event.send(BaseCountChange(maze.bases));
+    }
+}
+
+fn kill_explosions(
+    mut commands: Commands,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    explosions_images: Res<ExplosionsImages>,
+    mut rng: ResMut<GameRng>)
+{
+    for killed in killed_events.iter() {
+        crate::explosions::spawn_boom(&mut commands, killed.position, &explosions_images, &mut rng.effects);
+        if killed.enemy_type == BASE_ENEMY_TYPE {
+            crate::explosions::spawn_flash(&mut commands);
+        }
+    }
+}
+
+fn kill_sounds(
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+    sounds: Res<Sounds>)
+{
+    for killed in killed_events.iter() {
+        if killed.enemy_type == BASE_ENEMY_TYPE {
+            sfx.play(sounds.boom_base.clone());
+        }
+        else {
+            sfx.play(sounds.boom.clone());
+        }
+    }
+}
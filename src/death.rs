@@ -2,11 +2,16 @@ use bevy::prelude::*;
 use bevy_kira_audio::{AudioControl, AudioChannel};
 use rand::Rng;
 
-use crate::GameState;
+use crate::{GameState, WINDOW_W2, WINDOW_H2};
+use crate::actions::{ActionInput, GameAction};
 use crate::audio::{Sounds, DamageChannel, Shooting01Channel, Shooting05Channel, Shooting06Channel, Shooting08Channel, Shooting09Channel, DeathSoundChannel};
 use crate::explosions::ExplosionsImages;
 use crate::items::ITEM_COLORS;
+use crate::mainmenu::UiAssets;
+use crate::maze::Maze;
 use crate::player::{Player, PlayerComponent};
+use crate::random::GameRng;
+use crate::savegame::Checkpoint;
 
 pub struct DeathScreenPlugin;
 
@@ -19,6 +24,10 @@ impl Plugin for DeathScreenPlugin {
         .add_system_set(SystemSet::on_update(GameState::Death)
             .with_system(check_death_timer).after("StartDeathSystem")
             .with_system(spawn_death_boom).after("StartDeathSystem")
+            .with_system(death_input).after("StartDeathSystem")
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Death)
+            .with_system(despawn_death_text)
         );
     }
 }
@@ -29,6 +38,10 @@ pub struct DeathTimer(pub Timer);
 #[derive(Component, Deref, DerefMut)]
 pub struct DeathBoomsTimer(pub Timer);
 
+// restart from checkpoint offered after the explosion, see death_input
+#[derive(Component)]
+pub struct DeathText;
+
 pub fn start_death(
     mut commands: Commands,
     sfx_dmg: Res<AudioChannel<DamageChannel>>,
@@ -63,32 +76,89 @@ pub fn check_death_timer(
     time: Res<Time>,
     mut death_timer_query: Query<&mut DeathTimer>,
     death_booms_timer_query: Query<Entity, With<DeathBoomsTimer>>,
+    ui_assets: Res<UiAssets>,
+    checkpoint: Res<Checkpoint>,
     mut state: ResMut<State<GameState>>,)
 {
     for mut timer in death_timer_query.iter_mut() {
         timer.tick(time.delta());
         if timer.just_finished() {
-            player.is_dead = true;
             for entity in death_booms_timer_query.iter() {
                 commands.entity(entity).despawn_recursive();
             }
-            state.pop().expect("DEATH: Failed to pop Death state!");
+            if checkpoint.exists() {
+                commands.spawn_bundle(Text2dBundle {
+                    text: Text::from_section(
+                        "ENTER / pad South - restart from checkpoint, any other key - continue", 
+                        TextStyle {
+                            font: ui_assets.font.clone(),
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        }).with_alignment(TextAlignment::CENTER),
+                    transform: Transform::from_xyz(WINDOW_W2, WINDOW_H2, 900.0),
+                    ..Default::default()
+                }).insert(DeathText);
+            }
+            else {
+                player.is_dead = true;
+                state.pop().expect("DEATH: Failed to pop Death state!");
+            }
         }
     }
 }
 
+// the checkpoint replaces the game, any other key goes on as without it: a life lost or game over
+fn death_input(
+    mut actions: ResMut<ActionInput>,
+    mut state: ResMut<State<GameState>>,
+    mut maze: ResMut<Maze>,
+    mut player: ResMut<Player>,
+    checkpoint: Res<Checkpoint>,
+    text_query: Query<Entity, With<DeathText>>)
+{
+    if text_query.is_empty() {
+        return;
+    }
+
+    if actions.just_pressed(GameAction::Confirm) {
+        actions.clear();
+        match checkpoint.restore(&mut maze, &mut player) {
+            Ok(()) => {
+                state.replace(GameState::Game).expect("DEATH: Failed to change state!");
+            },
+            Err(error) => {
+                println!("Failed to restore checkpoint: {}", error);
+                maze.loaded = false;
+                maze.load_error = Some(error);
+                state.replace(GameState::MazeError).expect("DEATH: Failed to change state!");
+            }
+        }
+    }
+    else if actions.any_just_pressed() {
+        actions.clear();
+        player.is_dead = true;
+        state.pop().expect("DEATH: Failed to pop Death state!");
+    }
+}
+
+fn despawn_death_text(mut commands: Commands, text_query: Query<Entity, With<DeathText>>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 pub fn spawn_death_boom(
     mut commands: Commands,
     time: Res<Time>,
     mut death_booms_timer_query: Query<&mut DeathBoomsTimer>,
     mut player_query: Query<(&Transform, &mut Sprite), With<PlayerComponent>>,
     mut player: ResMut<Player>,
-    explosions_images: Res<ExplosionsImages>)
+    explosions_images: Res<ExplosionsImages>,
+    mut rng: ResMut<GameRng>)
 {
     for mut timer in death_booms_timer_query.iter_mut() {
         timer.tick(time.delta());
         if timer.just_finished() {
-            let mut rng = rand::thread_rng();
             let (player_transform, mut player_sprite) = player_query.single_mut();
 
             player_sprite.color = ITEM_COLORS[player.color_index];
@@ -98,12 +168,12 @@ pub fn spawn_death_boom(
             }
 
             let boom_pos = Vec3::new(
-                player_transform.translation.x + rng.gen_range(-60.0..=60.0),
-                player_transform.translation.y + rng.gen_range(-30.0..=30.0),
+                player_transform.translation.x + rng.effects.gen_range(-60.0..=60.0),
+                player_transform.translation.y + rng.effects.gen_range(-30.0..=30.0),
                 player_transform.translation.z
             );
 
-            crate::explosions::spawn_boom(&mut commands, boom_pos, &explosions_images);
+            crate::explosions::spawn_boom(&mut commands, boom_pos, &explosions_images, &mut rng.effects);
         }
     }
 }
@@ -0,0 +1,624 @@
+use bevy::prelude::*;
+use bevy::input::mouse::MouseWheel;
+
+use crate::{GameState, WINDOW_H, mainmenu::UiAssets};
+use crate::enemies::{EnemyDefs, EnemyComponent};
+use crate::items::{ItemsImages, ItemComponent, ITEM_W, ITEM_H};
+use crate::maze::{Maze, MazeFile, Tileset, WallSprite, START_ROOM, load_room, spawn_room};
+use crate::actions::{ActionInput, GameAction};
+use crate::random::GameRng;
+use crate::mazefile::{MazeData, RoomData, WallData, EnemyData, FellowData, ItemData, EdgePolicy,
+    read_maze, write_maze, ENEMY_TYPE_MAX, ITEM_TYPE_MAX, PLAY_AREA_H, CARRIER_TYPE, CARRIED_ENEMY_TYPES};
+
+const SNAP: f32 = 25.0;
+
+// carried fellow is drawn on top of the carrier
+const FELLOW_OFFSET_Y: f32 = 50.0;
+// wall or enemy missing in the tileset or in the enemy definitions
+const UNKNOWN_SIZE: Vec2 = Vec2::splat(50.0);
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_startup_system_to_stage(StartupStage::PreStartup, create_editor_resource)
+        .add_system_set(SystemSet::on_enter(GameState::Editor)
+            .with_system(start_editor)
+        )
+        .add_system_set(SystemSet::on_update(GameState::Editor)
+            .with_system(editor_keyboard_input).label("EditorInputSystem")
+            .with_system(editor_mouse_input).label("EditorInputSystem")
+            .with_system(update_editor_room).after("EditorInputSystem")
+            .with_system(update_editor_cursor).after("EditorInputSystem")
+            .with_system(update_editor_text).after("EditorInputSystem")
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Editor)
+            .with_system(despawn_editor)
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditorTool {
+    Walls,
+    Enemies,
+    Items,
+}
+
+pub struct Editor {
+    maze: MazeData,
+    room: (usize, usize),
+    tool: EditorTool,
+    wall_id: usize,
+    enemy_type: usize,
+    enemy_subtype: usize,
+    // index to fellow_choices()
+    fellow: usize,
+    item_type: usize,
+    // dragged object of the current tool and the cursor offset from its position
+    dragging: Option<(usize, Vec2)>,
+    changed: bool,
+    confirm_exit: bool,
+    redraw: bool,
+    message: String,
+}
+
+#[derive(Component)]
+pub struct EditorText;
+
+#[derive(Component)]
+pub struct EditorCursor;
+
+fn create_editor_resource(mut commands: Commands) {
+    let editor = Editor {
+        maze: MazeData { rows: 0, cols: 0, edges: EdgePolicy::Solid, rooms: Vec::new() },
+        room: START_ROOM,
+        tool: EditorTool::Walls,
+        wall_id: 0,
+        enemy_type: 0,
+        enemy_subtype: 0,
+        fellow: 0,
+        item_type: 0,
+        dragging: None,
+        changed: false,
+        confirm_exit: false,
+        redraw: false,
+        message: String::new(),
+    };
+    commands.insert_resource(editor);
+}
+
+// everything a carrier can carry: enemies first, then items
+fn fellow_choices() -> Vec<FellowData> {
+    let mut choices = Vec::new();
+    for enemy_type in CARRIED_ENEMY_TYPES.iter() {
+        choices.push(FellowData::Enemy { x: 0.0, y: 0.0, enemy_type: *enemy_type, subtype: 0 });
+    }
+    for item_type in 0..=ITEM_TYPE_MAX {
+        choices.push(FellowData::Item { x: 0.0, y: 0.0, item_type });
+    }
+    choices
+}
+
+fn fellow_name(fellow: &FellowData) -> String {
+    match fellow {
+        FellowData::Enemy { enemy_type, .. } => format!("enemy {}", enemy_type),
+        FellowData::Item { item_type, .. } => format!("item {}", item_type),
+    }
+}
+
+fn fellow_at(fellow: &FellowData, x: f32, y: f32) -> FellowData {
+    match fellow {
+        FellowData::Enemy { enemy_type, subtype, .. } =>
+            FellowData::Enemy { x, y: y - FELLOW_OFFSET_Y, enemy_type: *enemy_type, subtype: *subtype },
+        FellowData::Item { item_type, .. } =>
+            FellowData::Item { x, y: y - FELLOW_OFFSET_Y, item_type: *item_type },
+    }
+}
+
+fn subtype_count(enemy_type: usize) -> usize {
+    match enemy_type {
+        1 | 8 | 20 => 2,
+        _ => 1,
+    }
+}
+
+fn snap(value: f32, precise: bool) -> f32 {
+    if precise {
+        value.round()
+    }
+    else {
+        (value / SNAP).round() * SNAP
+    }
+}
+
+fn wall_size(wall_id: usize, tileset: &Tileset) -> Vec2 {
+    match tileset.data.tile(wall_id) {
+        Some(tile) => Vec2::new(tile.w, tile.h),
+        None => UNKNOWN_SIZE,
+    }
+}
+
+fn enemy_size(enemy_type: usize, enemy_defs: &EnemyDefs) -> Vec2 {
+    match enemy_defs.get(enemy_type) {
+        Some(def) => Vec2::new(def.w, def.h),
+        None => UNKNOWN_SIZE,
+    }
+}
+
+// rectangle (top left corner, size) in maze coordinates of an object placed at x,y, as spawn_room places it
+fn object_rect(tool: EditorTool, id: usize, x: f32, y: f32, tileset: &Tileset, enemy_defs: &EnemyDefs) -> (Vec2, Vec2) {
+    match tool {
+        EditorTool::Walls => (Vec2::new(x, y), wall_size(id, tileset)),
+        EditorTool::Enemies => {
+            let size = enemy_size(id, enemy_defs);
+            (Vec2::new(x + 25.0 - size.x / 2.0, y + 25.0 - size.y / 2.0), size)
+        },
+        EditorTool::Items => (Vec2::new(x, y + 6.0), Vec2::new(ITEM_W, ITEM_H)),
+    }
+}
+
+impl Editor {
+    fn room_index(&self) -> Option<usize> {
+        let (row, col) = self.room;
+        self.maze.rooms.iter().position(|room| room.row == row && room.col == col)
+    }
+
+    // a room missing from the maze file is empty
+    fn room_data(&self) -> Option<&RoomData> {
+        self.room_index().map(|index| &self.maze.rooms[index])
+    }
+
+    // only editing adds a missing room to the maze
+    fn room_data_mut(&mut self) -> &mut RoomData {
+        let (row, col) = self.room;
+        let index = match self.room_index() {
+            Some(index) => index,
+            None => {
+                self.maze.rooms.push(RoomData { row, col, name: None, walls: Vec::new(), enemies: Vec::new(), items: Vec::new() });
+                self.maze.rooms.len() - 1
+            }
+        };
+        &mut self.maze.rooms[index]
+    }
+
+    fn current_id(&self) -> usize {
+        match self.tool {
+            EditorTool::Walls => self.wall_id,
+            EditorTool::Enemies => self.enemy_type,
+            EditorTool::Items => self.item_type,
+        }
+    }
+
+    fn change_id(&mut self, step: isize, tileset: &Tileset) {
+        match self.tool {
+            EditorTool::Walls => {
+                // wall ids go in the tileset order, there can be gaps
+                let tiles = &tileset.data.tiles;
+                if !tiles.is_empty() {
+                    let index = tiles.iter().position(|tile| tile.id == self.wall_id).unwrap_or(0);
+                    self.wall_id = tiles[(index as isize + step).rem_euclid(tiles.len() as isize) as usize].id;
+                }
+            },
+            EditorTool::Enemies => {
+                self.enemy_type = (self.enemy_type as isize + step).rem_euclid(ENEMY_TYPE_MAX as isize + 1) as usize;
+            },
+            EditorTool::Items => {
+                self.item_type = (self.item_type as isize + step).rem_euclid(ITEM_TYPE_MAX as isize + 1) as usize;
+            },
+        }
+        self.enemy_subtype = 0;
+    }
+
+    // positions of the objects of the current tool in the current room
+    fn positions(&self) -> Vec<(usize, f32, f32)> {
+        let room = match self.room_data() {
+            Some(room) => room,
+            None => return Vec::new(),
+        };
+        match self.tool {
+            EditorTool::Walls => room.walls.iter().map(|wall| (wall.id, wall.x, wall.y)).collect(),
+            EditorTool::Enemies => room.enemies.iter().map(|enemy| (enemy.enemy_type, enemy.x, enemy.y)).collect(),
+            EditorTool::Items => room.items.iter().map(|item| (item.item_type, item.x, item.y)).collect(),
+        }
+    }
+
+    fn object_at(&self, point: Vec2, tileset: &Tileset, enemy_defs: &EnemyDefs) -> Option<usize> {
+        let tool = self.tool;
+        // last spawned is drawn on top
+        self.positions().iter().enumerate().rev()
+            .find(|(_, (id, x, y))| {
+                let (min, size) = object_rect(tool, *id, *x, *y, tileset, enemy_defs);
+                point.x >= min.x && point.x < min.x + size.x && point.y >= min.y && point.y < min.y + size.y
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn add_object(&mut self, x: f32, y: f32) {
+        let tool = self.tool;
+        let wall_id = self.wall_id;
+        let enemy_type = self.enemy_type;
+        let enemy_subtype = self.enemy_subtype;
+        let item_type = self.item_type;
+        let fellow = if enemy_type == CARRIER_TYPE {
+            Some(fellow_at(&fellow_choices()[self.fellow], x, y))
+        }
+        else {
+            None
+        };
+
+        let room = self.room_data_mut();
+        match tool {
+            EditorTool::Walls => room.walls.push(WallData { id: wall_id, x, y }),
+            EditorTool::Enemies => room.enemies.push(EnemyData { x, y, enemy_type, subtype: enemy_subtype, fellow }),
+            EditorTool::Items => room.items.push(ItemData { x, y, item_type }),
+        }
+    }
+
+    fn move_object(&mut self, index: usize, x: f32, y: f32) -> bool {
+        let tool = self.tool;
+        let room = self.room_data_mut();
+        let position = match tool {
+            EditorTool::Walls => room.walls.get_mut(index).map(|wall| (&mut wall.x, &mut wall.y)),
+            EditorTool::Enemies => room.enemies.get_mut(index).map(|enemy| {
+                if let Some(fellow) = enemy.fellow.take() {
+                    enemy.fellow = Some(fellow_at(&fellow, x, y));
+                }
+                (&mut enemy.x, &mut enemy.y)
+            }),
+            EditorTool::Items => room.items.get_mut(index).map(|item| (&mut item.x, &mut item.y)),
+        };
+
+        match position {
+            Some((posx, posy)) if *posx != x || *posy != y => {
+                *posx = x;
+                *posy = y;
+                true
+            },
+            _ => false
+        }
+    }
+
+    fn delete_object(&mut self, index: usize) {
+        let tool = self.tool;
+        let room = self.room_data_mut();
+        match tool {
+            EditorTool::Walls => { room.walls.remove(index); },
+            EditorTool::Enemies => { room.enemies.remove(index); },
+            EditorTool::Items => { room.items.remove(index); },
+        }
+    }
+
+    fn mark_changed(&mut self) {
+        self.changed = true;
+        self.confirm_exit = false;
+        self.redraw = true;
+    }
+}
+
+fn start_editor(
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    mut maze: ResMut<Maze>,
+    maze_file: Res<MazeFile>,
+    mut state: ResMut<State<GameState>>,
+    ui_assets: Res<UiAssets>,
+    tileset: Res<Tileset>)
+{
+    let maze_data = match &tileset.load_error {
+        Some(error) => Err(error.clone()),
+        None => read_maze(&maze_file.0).and_then(|maze_data| tileset.data.check_maze(&maze_data, &maze_file.0).map(|_| maze_data)),
+    };
+    match maze_data {
+        Ok(maze_data) => {
+            editor.maze = maze_data;
+        },
+        Err(error) => {
+            println!("Maze load error: {}", error);
+            maze.load_error = Some(error);
+            state.set(GameState::MazeError).expect("EDITOR: Failed to change state!");
+            return;
+        }
+    }
+
+    if editor.room.0 >= editor.maze.rows || editor.room.1 >= editor.maze.cols {
+        editor.room = START_ROOM;
+    }
+    if tileset.data.tile(editor.wall_id).is_none() {
+        editor.wall_id = tileset.data.tiles.first().map(|tile| tile.id).unwrap_or(0);
+    }
+    editor.dragging = None;
+    editor.changed = false;
+    editor.confirm_exit = false;
+    editor.redraw = true;
+    editor.message = String::new();
+
+    commands.spawn_bundle(Text2dBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: ui_assets.font.clone(),
+                font_size: 18.0,
+                color: Color::WHITE,
+            }).with_alignment(TextAlignment { vertical: VerticalAlign::Top, horizontal: HorizontalAlign::Left }),
+        transform: Transform::from_xyz(10.0, WINDOW_H - 10.0, 200.0),
+        ..Default::default()
+    }).insert(EditorText);
+
+    commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: Color::rgba(1.0, 1.0, 1.0, 0.3),
+            custom_size: Some(UNKNOWN_SIZE),
+            ..Default::default()
+        },
+        transform: Transform::from_xyz(0.0, 0.0, 150.0),
+        visibility: Visibility { is_visible: false },
+        ..Default::default()
+    }).insert(EditorCursor);
+}
+
+fn editor_keyboard_input(
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mut actions: ResMut<ActionInput>,
+    mut editor: ResMut<Editor>,
+    mut maze: ResMut<Maze>,
+    maze_file: Res<MazeFile>,
+    mut state: ResMut<State<GameState>>,
+    tileset: Res<Tileset>)
+{
+    let ctrl = keyboard.pressed(KeyCode::LControl) || keyboard.pressed(KeyCode::RControl);
+
+    if actions.just_pressed(GameAction::Back) {
+        if editor.changed && !editor.confirm_exit {
+            editor.confirm_exit = true;
+            editor.message = String::from("Unsaved changes! Escape = leave, Ctrl+S = save");
+        }
+        else {
+            keyboard.clear();
+            actions.clear();
+            state.set(GameState::Menu).expect("EDITOR: Failed to change state!");
+        }
+        return;
+    }
+
+    if ctrl && keyboard.just_pressed(KeyCode::S) {
+        match write_maze(&maze_file.0, &editor.maze) {
+            Ok(()) => {
+                // the game reads the saved maze again, a game in progress cannot be resumed
+                maze.loaded = false;
+                editor.changed = false;
+                editor.confirm_exit = false;
+                editor.message = format!("Saved to {}", maze_file.0);
+            },
+            Err(e) => {
+                editor.message = format!("Save failed: {}", e);
+            }
+        }
+        return;
+    }
+
+    // change room
+    let (row, col) = editor.room;
+    let mut room = editor.room;
+    if keyboard.just_pressed(KeyCode::Left) && col > 0 {
+        room.1 -= 1;
+    }
+    if keyboard.just_pressed(KeyCode::Right) && col + 1 < editor.maze.cols {
+        room.1 += 1;
+    }
+    if keyboard.just_pressed(KeyCode::Up) && row > 0 {
+        room.0 -= 1;
+    }
+    if keyboard.just_pressed(KeyCode::Down) && row + 1 < editor.maze.rows {
+        room.0 += 1;
+    }
+    if room != editor.room {
+        editor.room = room;
+        editor.dragging = None;
+        editor.redraw = true;
+    }
+
+    // tool and object type
+    if keyboard.just_pressed(KeyCode::Key1) {
+        editor.tool = EditorTool::Walls;
+    }
+    if keyboard.just_pressed(KeyCode::Key2) {
+        editor.tool = EditorTool::Enemies;
+    }
+    if keyboard.just_pressed(KeyCode::Key3) {
+        editor.tool = EditorTool::Items;
+    }
+    if keyboard.just_pressed(KeyCode::Q) {
+        editor.change_id(-1, &tileset);
+    }
+    if keyboard.just_pressed(KeyCode::E) {
+        editor.change_id(1, &tileset);
+    }
+    if keyboard.just_pressed(KeyCode::S) && !ctrl {
+        editor.enemy_subtype = (editor.enemy_subtype + 1) % subtype_count(editor.enemy_type);
+    }
+    if keyboard.just_pressed(KeyCode::F) {
+        editor.fellow = (editor.fellow + 1) % fellow_choices().len();
+    }
+}
+
+fn editor_mouse_input(
+    mouse: Res<Input<MouseButton>>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    keyboard: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    mut editor: ResMut<Editor>,
+    tileset: Res<Tileset>,
+    enemy_defs: Res<EnemyDefs>)
+{
+    for event in mouse_wheel.iter() {
+        if event.y > 0.0 {
+            editor.change_id(1, &tileset);
+        }
+        else if event.y < 0.0 {
+            editor.change_id(-1, &tileset);
+        }
+    }
+
+    if mouse.just_released(MouseButton::Left) {
+        editor.dragging = None;
+    }
+
+    let cursor = match windows.get_primary().and_then(|window| window.cursor_position()) {
+        Some(cursor) if cursor.y < PLAY_AREA_H => cursor,
+        _ => return,
+    };
+    // maze coordinates (y goes down from the top of the play area)
+    let point = Vec2::new(cursor.x, PLAY_AREA_H - cursor.y);
+    let precise = keyboard.pressed(KeyCode::LShift) || keyboard.pressed(KeyCode::RShift);
+
+    if mouse.just_pressed(MouseButton::Left) {
+        match editor.object_at(point, &tileset, &enemy_defs) {
+            Some(index) => {
+                let (_, x, y) = editor.positions()[index];
+                editor.dragging = Some((index, point - Vec2::new(x, y)));
+            },
+            None => {
+                editor.add_object(snap(point.x, precise), snap(point.y, precise));
+                editor.mark_changed();
+            }
+        }
+    }
+    else if let Some((index, offset)) = editor.dragging {
+        if mouse.pressed(MouseButton::Left) {
+            let target = point - offset;
+            if editor.move_object(index, snap(target.x, precise), snap(target.y, precise)) {
+                editor.mark_changed();
+            }
+        }
+    }
+
+    if mouse.just_pressed(MouseButton::Right) {
+        if let Some(index) = editor.object_at(point, &tileset, &enemy_defs) {
+            editor.dragging = None;
+            editor.delete_object(index);
+            editor.mark_changed();
+        }
+    }
+}
+
+fn update_editor_room(
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    maze_file: Res<MazeFile>,
+    walls_query: Query<Entity, With<WallSprite>>,
+    enemies_query: Query<Entity, With<EnemyComponent>>,
+    items_query: Query<Entity, With<ItemComponent>>,
+    tileset: Res<Tileset>,
+    enemy_defs: Res<EnemyDefs>,
+    items_images: Res<ItemsImages>,
+    mut rng: ResMut<GameRng>)
+{
+    if !editor.redraw {
+        return;
+    }
+    editor.redraw = false;
+
+    for entity in walls_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in enemies_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in items_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let mut base_count: u8 = 0;
+    // the preview does not take from the gameplay stream
+    let loaded = editor.room_data()
+        .map(|room_data| load_room(&maze_file.0, room_data, 0, &mut base_count, &enemy_defs, &mut rng.effects));
+    match loaded {
+        Some(Ok(room)) => {
+            spawn_room(&mut commands, &room.walls, &tileset, &room.enemies, &room.enemies_from_10,
+                &enemy_defs, &room.items, &items_images);
+        },
+        Some(Err(error)) => {
+            editor.message = error.to_string();
+        },
+        None => {}
+    }
+}
+
+fn update_editor_cursor(
+    mut cursor_query: Query<(&mut Sprite, &mut Transform, &mut Visibility), With<EditorCursor>>,
+    windows: Res<Windows>,
+    keyboard: Res<Input<KeyCode>>,
+    editor: Res<Editor>,
+    tileset: Res<Tileset>,
+    enemy_defs: Res<EnemyDefs>)
+{
+    let (mut sprite, mut transform, mut visibility) = match cursor_query.get_single_mut() {
+        Ok(cursor) => cursor,
+        Err(_) => return,
+    };
+
+    let cursor = match windows.get_primary().and_then(|window| window.cursor_position()) {
+        Some(cursor) if cursor.y < PLAY_AREA_H && editor.dragging.is_none() => cursor,
+        _ => {
+            visibility.is_visible = false;
+            return;
+        }
+    };
+
+    let precise = keyboard.pressed(KeyCode::LShift) || keyboard.pressed(KeyCode::RShift);
+    let x = snap(cursor.x, precise);
+    let y = snap(PLAY_AREA_H - cursor.y, precise);
+    let (min, size) = object_rect(editor.tool, editor.current_id(), x, y, &tileset, &enemy_defs);
+
+    sprite.custom_size = Some(size);
+    transform.translation.x = min.x + size.x / 2.0;
+    transform.translation.y = PLAY_AREA_H - min.y - size.y / 2.0;
+    visibility.is_visible = true;
+}
+
+fn update_editor_text(mut text_query: Query<&mut Text, With<EditorText>>, editor: Res<Editor>) {
+    let object = match editor.tool {
+        EditorTool::Walls => format!("wall {}", editor.wall_id),
+        EditorTool::Enemies => {
+            let mut object = format!("enemy {} subtype {}", editor.enemy_type, editor.enemy_subtype);
+            if editor.enemy_type == CARRIER_TYPE {
+                object += format!(" carrying {}", fellow_name(&fellow_choices()[editor.fellow])).as_str();
+            }
+            object
+        },
+        EditorTool::Items => format!("item {}", editor.item_type),
+    };
+
+    let status = format!(
+        "EDITOR  room {},{}  {}{}\n{}\n1/2/3 tool, Q/E type, S subtype, F fellow, LMB place/drag, RMB delete, arrows room, Ctrl+S save",
+        editor.room.0, editor.room.1, object, if editor.changed { "  *" } else { "" }, editor.message);
+
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != status {
+            text.sections[0].value = status.clone();
+        }
+    }
+}
+
+fn despawn_editor(
+    mut commands: Commands,
+    editor_query: Query<Entity, Or<(With<EditorText>, With<EditorCursor>)>>,
+    walls_query: Query<Entity, With<WallSprite>>,
+    enemies_query: Query<Entity, With<EnemyComponent>>,
+    items_query: Query<Entity, With<ItemComponent>>,)
+{
+    for entity in editor_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in walls_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in enemies_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in items_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,74 +1,77 @@
 use bevy::prelude::*;
 use rand::Rng;
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 use bevy_kira_audio::{AudioControl, AudioChannel};
+use serde::{Deserialize, Serialize};
 
 use crate::items::{FellowItem, ITEM_H};
 use crate::{WINDOW_W, WINDOW_H, INFO_BAR_H, H_PADDING, V_PADDING, GameState, GameDirection, load_atlas, collision_check};
 use crate::maze::{Maze, WallComponent, AnimationTimer};
 use crate::player::{Player, PLAYER_W, PLAYER_H, PlayerComponent};
 use crate::explosions::ExplosionsImages;
+use crate::loading::LoadingAssets;
+use crate::gamestep::{GAME_STEP, TIME_STEP};
+use crate::random::GameRng;
 use crate::audio::{Sounds, SfxChannel, Shooting01Channel, Shooting05Channel, Shooting06Channel, Shooting08Channel, Shooting09Channel};
+use crate::mazefile::{ENEMIES_FILE, MazeLoadError, EnemyDefsData, EnemyDefData, EnemyMovement, Facing, FacePlayer, ShootingData, ShotAim, read_enemy_defs};
 
 pub struct EnemiesPlugin;
 
 impl Plugin for EnemiesPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_startup_system_to_stage(StartupStage::PreStartup, load_enemies_images)
+        .add_startup_system_to_stage(StartupStage::PreStartup, load_enemy_defs)
         .add_startup_system_to_stage(StartupStage::PreStartup, load_enemies_shot_images)
         .add_system_set(SystemSet::on_update(GameState::Game)
             .with_system(animate_sprite)
-            .with_system(enemy_01_movement)
-            .with_system(enemy_02_movement)
-            .with_system(enemy_03_movement)
-            .with_system(enemy_05_movement)
-            .with_system(enemy_06_movement)
-            .with_system(enemy_07_movement)
-            .with_system(enemy_08_movement)
-            .with_system(enemy_09_movement)
-            .with_system(enemy_10_movement)
-            .with_system(enemy_11_movement)
-            .with_system(enemy_12_movement)
-            .with_system(enemy_13_movement)
-            .with_system(enemy_14_movement)
-            .with_system(enemy_15_movement)
-            .with_system(enemy_16_movement)
-            .with_system(enemy_17_movement)
-            .with_system(enemy_18_movement)
-            .with_system(enemy_19_movement)
-            .with_system(enemy_20_movement)
+        )
+        // the systems taking from the gameplay random stream run in a fixed order,
+        // so that a seed gives the same game
+        .add_system_set_to_stage(GAME_STEP, SystemSet::new()
+            .with_system(patrol_movement.label("PatrolMovementSystem").after("ItemsPickupSystem"))
+            .with_system(follow_player_movement.label("FollowMovementSystem").after("PatrolMovementSystem"))
+            .with_system(bounce_movement)
+            .with_system(carrier_movement.label("CarrierMovementSystem").after("FollowMovementSystem"))
+            .with_system(face_player.label("FacePlayerSystem"))
+            .with_system(enemy_shooting.label("EnemyShootingSystem").after("FacePlayerSystem").after("CarrierMovementSystem"))
+            .with_system(enemy_spawning.after("EnemyShootingSystem"))
             .with_system(enemy_shot_movement)
         );
     }
 }
 
-pub struct EnemiesImages {
-    pub enemy_00: Handle<TextureAtlas>, // base
-    pub enemy_01: Handle<TextureAtlas>, // dish
-    pub enemy_02: Handle<TextureAtlas>, // rod
-    pub enemy_03: Handle<TextureAtlas>, // tank
-    pub enemy_04: Handle<TextureAtlas>, // barrier
-    pub enemy_05: Handle<TextureAtlas>, // missile launcher (up)
-    pub enemy_06: Handle<TextureAtlas>, // bomb launcher (down)
-    pub enemy_07: Handle<TextureAtlas>, // 
-    pub enemy_08: Handle<TextureAtlas>, // missile
-    pub enemy_09: Handle<TextureAtlas>, // missile launcher (horizontal)
-    pub enemy_10: Handle<TextureAtlas>, // spawner
-    pub enemy_11: Handle<TextureAtlas>, // 
-    pub enemy_12: Handle<TextureAtlas>, // bolt drone
-    pub enemy_13_left: Handle<TextureAtlas>, // shooting drone
-    pub enemy_13_right: Handle<TextureAtlas>, // shooting drone
-    pub enemy_14_left: Handle<TextureAtlas>, // 
-    pub enemy_14_right: Handle<TextureAtlas>, // 
-    pub enemy_15: Handle<TextureAtlas>, // 
-    pub enemy_16: Handle<TextureAtlas>, // small ball
-    pub enemy_17_left: Handle<TextureAtlas>, // 
-    pub enemy_17_right: Handle<TextureAtlas>, // 
-    pub enemy_18: Handle<TextureAtlas>, // big ball
-    pub enemy_19: Handle<TextureAtlas>, // big hoovercraft
-    pub enemy_20_v1: Handle<TextureAtlas>, // carrier
-    pub enemy_20_v2: Handle<TextureAtlas>, // carrier
+// enemies.ron with the sprite sheets of every enemy type
+pub struct EnemyDefs {
+    pub data: EnemyDefsData,
+    // same order as data.enemies, one atlas per image
+    pub atlases: Vec<Vec<Handle<TextureAtlas>>>,
+    pub load_error: Option<MazeLoadError>,
+}
+
+impl EnemyDefs {
+    pub fn get(&self, enemy_type: usize) -> Option<&EnemyDefData> {
+        self.data.enemy(enemy_type)
+    }
+
+    // enemy types of the maze are checked when it is loaded, so the definition exists
+    pub fn def(&self, enemy_type: usize) -> &EnemyDefData {
+        self.get(enemy_type).expect("ENEMIES: Unexpected enemy type!")
+    }
+
+    pub fn atlas(&self, enemy_type: usize, image: usize) -> Handle<TextureAtlas> {
+        let index = self.data.enemies.iter().position(|enemy| enemy.enemy_type == enemy_type).expect("ENEMIES: Unexpected enemy type!");
+        self.atlases[index][image].clone()
+    }
+
+    pub fn size(&self, enemy_type: usize) -> Vec2 {
+        let def = self.def(enemy_type);
+        Vec2::new(def.w, def.h)
+    }
+
+    pub fn hitbox(&self, enemy_type: usize) -> Vec2 {
+        let def = self.def(enemy_type);
+        Vec2::new(def.hitbox.w, def.hitbox.h)
+    }
 }
 
 pub struct EnemiesShotImages {
@@ -110,6 +113,7 @@ pub const ENEMY_COLORS: [Color; 23] = [
     Color::YELLOW,
     Color::YELLOW_GREEN ];
 
+#[derive(Serialize, Deserialize)]
 pub struct Enemy {
     pub health: i16,
     pub enemy_seq: usize,
@@ -121,14 +125,15 @@ pub struct Enemy {
     pub posy: f32,
     pub color: Color,
     pub velocity: Vec2,
-    pub shooting_cooldown: u16,
-    pub shooting_cooldown_max: u16,
+    pub shooting_cooldown: f32,
+    pub shooting_cooldown_max: f32,
     pub direction: GameDirection,
     pub is_from_10: bool,
     pub fellow_enemy: Option<FellowEnemy>,
     pub fellow_item: Option<FellowItem>,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct FellowEnemy {
     pub health: i16,
     pub enemy_type: usize,
@@ -137,13 +142,14 @@ pub struct FellowEnemy {
     pub posx: f32,
     pub posy: f32,
     pub color: Color,
-    pub shooting_cooldown: u16,
-    pub shooting_cooldown_max: u16,
+    pub shooting_cooldown: f32,
+    pub shooting_cooldown_max: f32,
     pub direction: GameDirection,
 }
 
 #[derive(Component)]
 pub struct EnemyComponent {
+    pub enemy_type: usize,
     pub enemy_seq: usize,
     pub room_seq: usize,
     pub is_from_10: bool,
@@ -151,111 +157,156 @@ pub struct EnemyComponent {
 
 #[derive(Component)]
 pub struct EnemyShotComponent {
+    // enemy that fired the shot, its damage is taken from the ship
     pub enemy_type: usize,
+    // one of ENEMY_SHOT_TYPES
+    pub shot: usize,
     pub size: Vec2,
     pub velocity: Vec2,
 }
 
-#[derive(Component)]
-pub struct EnemyType01;
-
-#[derive(Component)]
-pub struct EnemyType02;
+// behaviour components, inserted from the enemy definition by insert_behaviours
 
+// stays where the maze puts it
 #[derive(Component)]
-pub struct EnemyType03;
+pub struct FixedTurret;
 
+// random walk, acceleration in pixels per second squared
 #[derive(Component)]
-pub struct EnemyType03Fellow;
-
-#[derive(Component)]
-pub struct EnemyType05;
-
-#[derive(Component)]
-pub struct EnemyType06;
-
-#[derive(Component)]
-pub struct EnemyType07;
-
-#[derive(Component)]
-pub struct EnemyType08;
+pub struct Patrol {
+    pub acceleration: f32,
+}
 
+// random walk pulled to the height of the ship
 #[derive(Component)]
-pub struct EnemyType09;
+pub struct FollowPlayer {
+    pub acceleration: f32,
+    pub pull: f32,
+}
 
+// keeps the speed of its start, bounces off walls and screen edges
 #[derive(Component)]
-pub struct EnemyType10;
+pub struct Bounce;
 
+// random walk with its fellow enemy or item on top
 #[derive(Component)]
-pub struct EnemyType10Fellow;
+pub struct Carrier {
+    pub acceleration: f32,
+}
 
+// spawns one of the enemy types at random at every cooldown
 #[derive(Component)]
-pub struct EnemyType11;
+pub struct Spawner {
+    pub spawns: Vec<usize>,
+    // spawned enemies alive in the room at most
+    pub limit: usize,
+}
 
 #[derive(Component)]
-pub struct EnemyType12;
+pub struct Shooting(pub ShootingData);
 
 #[derive(Component)]
-pub struct EnemyType13;
+pub struct FacesPlayer(pub FacePlayer);
 
+// fellow enemy drawn on a carrier, its state is fellow_enemy of the carrier
 #[derive(Component)]
-pub struct EnemyType14;
+pub struct CarriedEnemy {
+    pub enemy_type: usize,
+    pub room_seq: usize,
+    pub enemy_seq: usize,
+}
 
-#[derive(Component)]
-pub struct EnemyType15;
+pub fn load_enemy_defs(
+    mut commands: Commands, 
+    assets: Res<AssetServer>, 
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>, 
+    mut loading: ResMut<LoadingAssets>)
+{
+    println!("Load enemy definitions: {}", ENEMIES_FILE);
+    let mut enemy_defs = EnemyDefs {
+        data: EnemyDefsData { enemies: Vec::new() },
+        atlases: Vec::new(),
+        load_error: None,
+    };
+
+    match read_enemy_defs(ENEMIES_FILE) {
+        Ok(data) => {
+            for enemy in data.enemies.iter() {
+                let size = Vec2::new(enemy.w, enemy.h);
+                let padding = if enemy.frames > 1 { Some(H_PADDING) } else { None };
+                enemy_defs.atlases.push(enemy.images.iter()
+                    .map(|image| load_atlas(&assets, &mut texture_atlases, &mut loading, image, size, 1, enemy.frames, padding))
+                    .collect());
+            }
+            enemy_defs.data = data;
+        },
+        Err(error) => {
+            println!("Enemy definitions load error: {}", error);
+            enemy_defs.load_error = Some(error);
+        }
+    }
 
-#[derive(Component)]
-pub struct EnemyType16;
+    commands.insert_resource(enemy_defs);
+}
 
-#[derive(Component)]
-pub struct EnemyType17;
+// direction of a new enemy, the movement of some enemies turns them to the ship
+pub fn start_direction(def: &EnemyDefData, subtype: usize) -> GameDirection {
+    match def.subtype(subtype).facing {
+        Some(Facing::Left) => GameDirection::Left,
+        Some(Facing::Right) => GameDirection::Right,
+        None => GameDirection::None,
+    }
+}
 
-#[derive(Component)]
-pub struct EnemyType18;
+// bouncing enemies keep the speed of their start in a random diagonal direction,
+// the others get their velocity in their movement system
+pub fn start_velocity(def: &EnemyDefData, rng: &mut StdRng) -> Vec2 {
+    match def.movement {
+        EnemyMovement::Bounce { speed } => {
+            let mut x = speed;
+            let mut y = speed;
+            if rng.gen_bool(1.0/2.0) {
+                x *= -1.0;
+            }
+            if rng.gen_bool(1.0/2.0) {
+                y *= -1.0;
+            }
+            Vec2::new(x, y)
+        },
+        _ => Vec2::splat(0.0),
+    }
+}
 
-#[derive(Component)]
-pub struct EnemyType19;
+// random part of the velocity change of walking enemies
+fn random_acceleration(rng: &mut StdRng, acceleration: f32, dt: f32) -> Vec2 {
+    Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)) * acceleration * dt
+}
 
-#[derive(Component)]
-pub struct EnemyType20;
-
-pub const ENEMY_NN_SIZE: Vec2 = Vec2::splat(50.0);
-pub const ENEMY_07_SIZE: Vec2 = Vec2::new(50.0, 46.0);
-pub const ENEMY_18_SIZE: Vec2 = Vec2::splat(100.0);
-pub const ENEMY_19_SIZE: Vec2 = Vec2::new(94.0, 50.0);
-
-pub fn load_enemies_images(mut commands: Commands, assets: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>) {
-    println!("Load enemies images");
-    commands.insert_resource(EnemiesImages {
-        enemy_00: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_00.png", ENEMY_NN_SIZE, 1, 1, None),
-        enemy_01: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_01.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_02: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_02.png", ENEMY_NN_SIZE, 1, 1, None),
-        enemy_03: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_03.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_04: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_04.png", ENEMY_NN_SIZE, 1, 1, None),
-        enemy_05: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_05.png", ENEMY_NN_SIZE, 1, 1, None),
-        enemy_06: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_06.png", ENEMY_NN_SIZE, 1, 1, None),
-        enemy_07: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_07.png", ENEMY_07_SIZE, 1, 4, Some(H_PADDING)),
-        enemy_08: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_08.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_09: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_09.png", ENEMY_NN_SIZE, 1, 8, Some(H_PADDING)),
-        enemy_10: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_10.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_11: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_11.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_12: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_12.png", ENEMY_NN_SIZE, 1, 4, Some(H_PADDING)),
-        enemy_13_left: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_13_left.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_13_right: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_13_right.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_14_left: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_14_left.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_14_right: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_14_right.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_15: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_15.png", ENEMY_NN_SIZE, 1, 4, Some(H_PADDING)),
-        enemy_16: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_16.png", ENEMY_NN_SIZE, 1, 4, Some(H_PADDING)),
-        enemy_17_left: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_17_left.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_17_right: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_17_right.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_18: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_18.png", ENEMY_18_SIZE, 1, 1, Some(H_PADDING)),
-        enemy_19: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_19.png", ENEMY_19_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_20_v1: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_20_v1.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_20_v2: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_20_v2.png", ENEMY_NN_SIZE, 1, 2, Some(H_PADDING)),
-    });
+// behaviour components of the enemy as its definition says, a carried enemy moves with its carrier
+pub fn insert_behaviours(commands: &mut Commands, entity: Entity, def: &EnemyDefData, carried: bool) {
+    let mut entity_commands = commands.entity(entity);
+
+    if !carried {
+        match def.movement {
+            EnemyMovement::FixedTurret => entity_commands.insert(FixedTurret),
+            EnemyMovement::Patrol { acceleration } => entity_commands.insert(Patrol { acceleration }),
+            EnemyMovement::FollowPlayer { acceleration, pull } => entity_commands.insert(FollowPlayer { acceleration, pull }),
+            EnemyMovement::Bounce { .. } => entity_commands.insert(Bounce),
+            EnemyMovement::Carrier { acceleration } => entity_commands.insert(Carrier { acceleration }),
+        };
+    }
+    if let Some(face_player) = def.face_player {
+        entity_commands.insert(FacesPlayer(face_player));
+    }
+    if let Some(shooting) = def.shooting {
+        entity_commands.insert(Shooting(shooting));
+    }
+    if def.spawner() {
+        entity_commands.insert(Spawner { spawns: def.spawns.clone(), limit: def.spawn_limit });
+    }
 }
 
-pub const SHOT_01_SIZE: Vec2 = ENEMY_NN_SIZE;
+pub const SHOT_01_SIZE: Vec2 = Vec2::splat(50.0);
 pub const SHOT_02_SIZE: Vec2 = Vec2::splat(18.0);
 pub const SHOT_03_SIZE: Vec2 = Vec2::new(17.0, 6.0);
 pub const SHOT_05_SIZE: Vec2 = Vec2::new(40.0, 50.0);
@@ -266,21 +317,26 @@ pub const SHOT_08_SIZE: Vec2 = Vec2::splat(50.0);
 pub const SHOT_09_SIZE: Vec2 = Vec2::new(50.0, 35.0);
 pub const SHOT_13_SIZE: Vec2 = Vec2::new(17.0, 6.0);
 
-pub fn load_enemies_shot_images(mut commands: Commands, assets: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>) {
+pub fn load_enemies_shot_images(
+    mut commands: Commands, 
+    assets: Res<AssetServer>, 
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>, 
+    mut loading: ResMut<LoadingAssets>)
+{
     println!("Load enemies shot images");
     commands.insert_resource(EnemiesShotImages {
-        enemy_01_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_01_shot.png", SHOT_01_SIZE, 1, 4, Some(H_PADDING)),
-        enemy_02_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_02_shot.png", SHOT_02_SIZE, 1, 1, None),
-        enemy_03_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_03_shot.png", SHOT_03_SIZE, 1, 1, None),
-        enemy_05_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_05_shot.png", SHOT_05_SIZE, 1, 4, Some(H_PADDING)),
-        enemy_06_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_06_shot.png", SHOT_06_SIZE, 4, 1, Some(V_PADDING)),
-        // enemy_07_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_07_shot.png", SHOT_07_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_07_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_07_shot.png", SHOT_07_SIZE, 1, 4, Some(H_PADDING)),
-        enemy_08_shot_left: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_08_shot_left.png", SHOT_08_SIZE, 1, 1, None),
-        enemy_08_shot_right: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_08_shot_right.png", SHOT_08_SIZE, 1, 1, None),
-        enemy_09_shot_left: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_09_shot_left.png", SHOT_09_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_09_shot_right: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_09_shot_right.png", SHOT_09_SIZE, 1, 2, Some(H_PADDING)),
-        enemy_13_shot: load_atlas(&assets, &mut texture_atlases, "images/enemies/enemy_13_shot.png", SHOT_13_SIZE, 1, 1, None),
+        enemy_01_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_01_shot.png", SHOT_01_SIZE, 1, 4, Some(H_PADDING)),
+        enemy_02_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_02_shot.png", SHOT_02_SIZE, 1, 1, None),
+        enemy_03_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_03_shot.png", SHOT_03_SIZE, 1, 1, None),
+        enemy_05_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_05_shot.png", SHOT_05_SIZE, 1, 4, Some(H_PADDING)),
+        enemy_06_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_06_shot.png", SHOT_06_SIZE, 4, 1, Some(V_PADDING)),
+        // enemy_07_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_07_shot.png", SHOT_07_SIZE, 1, 2, Some(H_PADDING)),
+        enemy_07_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_07_shot.png", SHOT_07_SIZE, 1, 4, Some(H_PADDING)),
+        enemy_08_shot_left: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_08_shot_left.png", SHOT_08_SIZE, 1, 1, None),
+        enemy_08_shot_right: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_08_shot_right.png", SHOT_08_SIZE, 1, 1, None),
+        enemy_09_shot_left: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_09_shot_left.png", SHOT_09_SIZE, 1, 2, Some(H_PADDING)),
+        enemy_09_shot_right: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_09_shot_right.png", SHOT_09_SIZE, 1, 2, Some(H_PADDING)),
+        enemy_13_shot: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/enemies/enemy_13_shot.png", SHOT_13_SIZE, 1, 1, None),
     });
 }
 
@@ -298,13 +354,69 @@ fn animate_sprite(
     }
 }
 
-fn get_enemy_from_10(enemies: &mut Vec<Enemy>, seq: usize) -> Option<&mut Enemy> {
-    for enemy in enemies.iter_mut() {
-        if enemy.enemy_seq == seq {
-            return Some(enemy);
-        }
+// enemy of the room the component points to, None = spawned enemy already destroyed
+fn room_enemy<'a>(maze: &'a mut Maze, component: &EnemyComponent) -> Option<&'a mut Enemy> {
+    let room = &mut maze.rooms[component.room_seq];
+    if component.is_from_10 {
+        room.enemies_from_10.iter_mut().find(|enemy| enemy.enemy_seq == component.enemy_seq)
+    }
+    else {
+        room.enemies.get_mut(component.enemy_seq)
+    }
+}
+
+// what the facing, shooting and spawning read and change,
+// the Enemy of the room or, for a carried enemy, fellow_enemy of its carrier
+struct EnemyState<'a> {
+    enemy_type: usize,
+    room_seq: usize,
+    // middle of the enemy on the screen
+    position: Vec2,
+    // despawned when the enemy is launched, a carried enemy takes its carrier with it
+    entity: Entity,
+    health: &'a mut i16,
+    shooting_cooldown: &'a mut f32,
+    shooting_cooldown_max: f32,
+    direction: &'a mut GameDirection,
+}
+
+fn enemy_state<'a>(
+    maze: &'a mut Maze,
+    entity: Entity,
+    transform: &Transform,
+    component: Option<&EnemyComponent>,
+    carried: Option<(&CarriedEnemy, &Parent)>,
+    carrier_query: &Query<&Transform, With<Carrier>>) -> Option<EnemyState<'a>>
+{
+    if let Some(component) = component {
+        let enemy = room_enemy(maze, component)?;
+        return Some(EnemyState {
+            enemy_type: enemy.enemy_type,
+            room_seq: component.room_seq,
+            position: transform.translation.truncate(),
+            entity,
+            health: &mut enemy.health,
+            shooting_cooldown: &mut enemy.shooting_cooldown,
+            shooting_cooldown_max: enemy.shooting_cooldown_max,
+            direction: &mut enemy.direction,
+        });
     }
-    None
+
+    // fellow translation is relative to the carrier
+    let (carried, parent) = carried?;
+    let carrier_transform = carrier_query.get(parent.get()).ok()?;
+    let carrier = maze.rooms[carried.room_seq].enemies.get_mut(carried.enemy_seq)?;
+    let fellow = carrier.fellow_enemy.as_mut()?;
+    Some(EnemyState {
+        enemy_type: carried.enemy_type,
+        room_seq: carried.room_seq,
+        position: (carrier_transform.translation + transform.translation).truncate(),
+        entity: parent.get(),
+        health: &mut carrier.health,
+        shooting_cooldown: &mut fellow.shooting_cooldown,
+        shooting_cooldown_max: fellow.shooting_cooldown_max,
+        direction: &mut fellow.direction,
+    })
 }
 
 fn process_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transform, size: Vec2, walls_query: &Query<&WallComponent>) {
@@ -362,1114 +474,345 @@ fn process_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transform, size:
     enemy.first = false;
 }
 
-fn enemy_01_shooting(
-    commands: &mut Commands, 
-    enemy_cooldown: &mut u16,
-    enemy_cooldown_max: u16,
-    enemy_direction: GameDirection,
-    enemy_posx: f32, enemy_posy: f32,
-    player_posy: f32,
-    enemies_shot_images: &Res<EnemiesShotImages>,) -> bool
+fn patrol_movement(
+    mut query: Query<(&mut Transform, &EnemyComponent, &Patrol)>,
+    walls_query: Query<&WallComponent>,
+    mut maze: ResMut<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    mut rng: ResMut<GameRng>,) 
 {
-    if player_posy > enemy_posy - 25.0 && player_posy < enemy_posy + 25.0
-    {
-        if *enemy_cooldown > 0 {
-            *enemy_cooldown -= 1;
-            return false;
-        }
+    for (mut transform, component, patrol) in query.iter_mut() {
+        let enemy = match room_enemy(&mut maze, component) {
+            Some(enemy) => enemy,
+            None => continue,
+        };
 
-        let velocity: Vec2;
-        let start_x: f32;
-        match enemy_direction {
-            GameDirection::Left => {
-                velocity = Vec2::new(-300.0, 0.0);
-                start_x = enemy_posx - 10.0;
-            },
-            GameDirection::Right => {
-                velocity = Vec2::new(300.0, 0.0);
-                start_x = enemy_posx + 10.0;
-            },
-            GameDirection::None => panic!("Unexpected direction!")
-        }
+        enemy.velocity += random_acceleration(&mut rng.game, patrol.acceleration, TIME_STEP);
 
-        commands.spawn_bundle(SpriteSheetBundle {
-            texture_atlas: enemies_shot_images.enemy_01_shot.clone(),
-            sprite: TextureAtlasSprite {
-                color: Color::WHITE,
-                index: 0,
-                custom_size: Some(SHOT_01_SIZE),
-                ..Default::default()
-            },
-            transform: Transform { 
-                translation: Vec3::new(start_x, enemy_posy, 100.0),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(EnemyShotComponent {
-            enemy_type: 1,
-            size: SHOT_01_SIZE,
-            velocity,
-        })
-        .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-
-        *enemy_cooldown = enemy_cooldown_max;
-        return true;
+        process_movement(TIME_STEP, enemy, &mut transform, enemy_defs.size(component.enemy_type), &walls_query);
     }
-
-    false
 }
 
-fn enemy_01_movement(
-    mut commands: Commands,
+fn follow_player_movement(
     player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&Transform, &EnemyComponent), With<EnemyType01>>,
+    mut query: Query<(&mut Transform, &EnemyComponent, &FollowPlayer), Without<PlayerComponent>>,
+    walls_query: Query<&WallComponent>,
     mut maze: ResMut<Maze>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx01: Res<AudioChannel<Shooting01Channel>>,
-    mut sounds: ResMut<Sounds>,) 
+    enemy_defs: Res<EnemyDefs>,
+    mut rng: ResMut<GameRng>,) 
 {
     let player_transform = player_query.single();
 
-    for (enemy_transfrom, component) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
+    for (mut transform, component, follow) in query.iter_mut() {
+        let enemy = match room_enemy(&mut maze, component) {
+            Some(enemy) => enemy,
+            None => continue,
+        };
 
-        if enemy_01_shooting(&mut commands, &mut enemy.shooting_cooldown, enemy.shooting_cooldown_max, enemy.direction,
-            enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            player_transform.translation.y, &enemies_shot_images)
-        {
-            if !sfx01.is_playing_sound() {
-                sfx01.play(sounds.enemy_01_shot.clone()).looped();
-            }
-            sounds.enemy_01_shot_counter += 1;
-        }
-    }
-}
+        enemy.velocity += random_acceleration(&mut rng.game, follow.acceleration, TIME_STEP);
+        enemy.velocity += Vec2::new(0.0, transform.translation.y - player_transform.translation.y) * follow.pull * TIME_STEP * -1.0;
 
-fn enemy_02_shooting(
-    commands: &mut Commands, 
-    enemy_cooldown: &mut u16,
-    enemy_cooldown_max: u16,
-    enemy_posx: f32, enemy_posy: f32,
-    player_posx: f32, player_posy: f32,
-    enemies_shot_images: &Res<EnemiesShotImages>,) -> bool
-{
-    if *enemy_cooldown > 0 {
-        *enemy_cooldown -= 1;
-        return false;
+        process_movement(TIME_STEP, enemy, &mut transform, enemy_defs.size(component.enemy_type), &walls_query);
     }
-
-    let mut velocity = Vec2::new(
-        player_posx - enemy_posx, 
-        player_posy - enemy_posy).normalize();
-    velocity *= 200.0;
-
-    commands.spawn_bundle(SpriteSheetBundle {
-        texture_atlas: enemies_shot_images.enemy_02_shot.clone(),
-        sprite: TextureAtlasSprite {
-            color: Color::WHITE,
-            index: 0,
-            custom_size: Some(SHOT_02_SIZE),
-            ..Default::default()
-        },
-        transform: Transform { 
-            translation: Vec3::new(enemy_posx, enemy_posy + 10.0, 100.0),
-            ..Default::default()
-        },
-        ..Default::default()
-    })
-    .insert(EnemyShotComponent {
-        enemy_type: 2,
-        size: SHOT_02_SIZE,
-        velocity,
-    });
-
-    *enemy_cooldown = enemy_cooldown_max;
-    true
 }
 
-fn enemy_02_movement(
-    mut commands: Commands,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&Transform, &EnemyComponent), With<EnemyType02>>,
+fn bounce_movement(
+    mut query: Query<(&mut Transform, &EnemyComponent), With<Bounce>>,
+    walls_query: Query<&WallComponent>,
     mut maze: ResMut<Maze>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx: Res<AudioChannel<SfxChannel>>,
-    sounds: Res<Sounds>,) 
+    enemy_defs: Res<EnemyDefs>,) 
 {
-    let player_transform = player_query.single();
-
-    for (enemy_transfrom, component) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-
-        if enemy_02_shooting(&mut commands, &mut enemy.shooting_cooldown, enemy.shooting_cooldown_max, 
-            enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            player_transform.translation.x, player_transform.translation.y, &enemies_shot_images)
-        {
-            sfx.play(sounds.enemy_02_shot.clone());
-        }
-    }
-}
-
-fn enemy_03_shooting(
-    commands: &mut Commands, 
-    enemy_cooldown: &mut u16,
-    enemy_cooldown_max: u16,
-    enemy_direction: GameDirection,
-    enemy_posx: f32, enemy_posy: f32,
-    player_posy: f32,
-    enemies_shot_images: &Res<EnemiesShotImages>,) -> bool
-{
-    if player_posy > enemy_posy - 25.0 &&
-       player_posy < enemy_posy + 25.0
-    {
-        if *enemy_cooldown > 0 {
-            *enemy_cooldown -= 1;
-            return false;
-        }
-
-        let velocity: Vec2;
-        let start_x: f32;
-        match enemy_direction {
-            GameDirection::Left => {
-                velocity = Vec2::new(-300.0, 0.0);
-                start_x = enemy_posx - 15.0;
-            },
-            GameDirection::Right => {
-                velocity = Vec2::new(300.0, 0.0);
-                start_x = enemy_posx + 15.0;
-            },
-            GameDirection::None => panic!("Unexpected direction!")
-        }
-
-        commands.spawn_bundle(SpriteSheetBundle {
-            texture_atlas: enemies_shot_images.enemy_03_shot.clone(),
-            sprite: TextureAtlasSprite {
-                color: Color::WHITE,
-                index: 0,
-                custom_size: Some(SHOT_03_SIZE),
-                ..Default::default()
-            },
-            transform: Transform { 
-                translation: Vec3::new(start_x, enemy_posy + 4.0, 100.0),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(EnemyShotComponent {
-            enemy_type: 3,
-            size: SHOT_03_SIZE,
-            velocity,
-        });
+    for (mut transform, component) in query.iter_mut() {
+        let enemy = match room_enemy(&mut maze, component) {
+            Some(enemy) => enemy,
+            None => continue,
+        };
 
-        *enemy_cooldown = enemy_cooldown_max;
-        return true;
+        process_movement(TIME_STEP, enemy, &mut transform, enemy_defs.size(component.enemy_type), &walls_query);
     }
-    
-    false
 }
 
-fn enemy_03_movement(
-    mut commands: Commands,
+fn face_player(
     player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&Transform, &EnemyComponent, &mut TextureAtlasSprite), With<EnemyType03>>,
+    mut query: Query<(Entity, &Transform, Option<&EnemyComponent>, Option<&CarriedEnemy>, Option<&Parent>, &FacesPlayer, 
+        &mut TextureAtlasSprite, &mut Handle<TextureAtlas>), Without<PlayerComponent>>,
+    carrier_query: Query<&Transform, With<Carrier>>,
     mut maze: ResMut<Maze>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx: Res<AudioChannel<SfxChannel>>,
-    sounds: Res<Sounds>,) 
+    enemy_defs: Res<EnemyDefs>,) 
 {
     let player_transform = player_query.single();
 
-    for (enemy_transfrom, component, mut enemy_sprite) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
+    for (entity, transform, component, carried, parent, faces_player, mut sprite, mut atlas) in query.iter_mut() {
+        let state = match enemy_state(&mut maze, entity, transform, component, carried.zip(parent), &carrier_query) {
+            Some(state) => state,
+            None => continue,
+        };
 
-        if enemy_transfrom.translation.x > player_transform.translation.x {
-            enemy_sprite.index = 0;
-            enemy.direction = GameDirection::Left;
+        // 0 = left, 1 = right
+        let side = if state.position.x > player_transform.translation.x {
+            *state.direction = GameDirection::Left;
+            0
         }
         else {
-            enemy_sprite.index = 1;
-            enemy.direction = GameDirection::Right;
-        }
-
-        if enemy_03_shooting(&mut commands, &mut enemy.shooting_cooldown, enemy.shooting_cooldown_max, enemy.direction,
-            enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            player_transform.translation.y, &enemies_shot_images)
-        {
-            sfx.play(sounds.enemy_03_13_shot.clone());
-        }
-    }
-}
-
-fn enemy_05_shooting(
-    commands: &mut Commands, 
-    enemy_cooldown: &mut u16,
-    enemy_cooldown_max: u16,
-    enemy_posx: f32, enemy_posy: f32,
-    player_posx: f32,
-    enemies_shot_images: &Res<EnemiesShotImages>,) -> bool
-{
-    if player_posx > enemy_posx - 25.0 &&
-       player_posx < enemy_posx + 25.0
-    {
-        if *enemy_cooldown > 0 {
-            *enemy_cooldown -= 1;
-            return false;
-        }
-
-        commands.spawn_bundle(SpriteSheetBundle {
-            texture_atlas: enemies_shot_images.enemy_05_shot.clone(),
-            sprite: TextureAtlasSprite {
-                color: Color::WHITE,
-                index: 0,
-                custom_size: Some(SHOT_05_SIZE),
-                ..Default::default()
-            },
-            transform: Transform { 
-                translation: Vec3::new(enemy_posx, enemy_posy + 25.0, 100.0),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(EnemyShotComponent {
-            enemy_type: 5,
-            size: SHOT_05_SIZE,
-            velocity: Vec2::new(0.0, 300.0),
-        })
-        .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-
-        *enemy_cooldown = enemy_cooldown_max;
-        return true;
-    }
-
-    false
-}
-
-fn enemy_05_movement(
-    mut commands: Commands,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&Transform, &EnemyComponent), With<EnemyType05>>,
-    mut maze: ResMut<Maze>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx: Res<AudioChannel<Shooting05Channel>>,
-    mut sounds: ResMut<Sounds>,) 
-{
-    let player_transform = player_query.single();
-
-    for (enemy_transfrom, component) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
+            *state.direction = GameDirection::Right;
+            1
+        };
 
-        if enemy_05_shooting(&mut commands, &mut enemy.shooting_cooldown, enemy.shooting_cooldown_max,
-            enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            player_transform.translation.x, &enemies_shot_images)
-        {
-            sfx.stop();
-            sfx.play(sounds.enemy_05_shot.clone()).looped();
-            sounds.enemy_05_shot_counter += 1;
+        match faces_player.0 {
+            FacePlayer::Frame => sprite.index = side,
+            FacePlayer::Image => *atlas = enemy_defs.atlas(state.enemy_type, side),
         }
     }
 }
 
-fn enemy_06_movement(
-    mut commands: Commands,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&Transform, &EnemyComponent), With<EnemyType06>>,
-    mut maze: ResMut<Maze>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx: Res<AudioChannel<Shooting06Channel>>,
-    mut sounds: ResMut<Sounds>,) 
-{
-    let player_transform = player_query.single();
+// velocity of a shot fired now, None = the ship is not in the line of fire
+fn shot_velocity(shooting: &ShootingData, direction: GameDirection, position: Vec2, player: Vec2) -> Option<Vec2> {
+    let in_row = (player.y - position.y).abs() < shooting.range;
+    let in_column = (player.x - position.x).abs() < shooting.range;
 
-    for (enemy_transfrom, component) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-
-        if player_transform.translation.x > enemy_transfrom.translation.x - 25.0 &&
-           player_transform.translation.x < enemy_transfrom.translation.x + 25.0
-        {
-            if enemy.shooting_cooldown > 0 {
-                enemy.shooting_cooldown -= 1;
-                continue;
+    match shooting.aim {
+        ShotAim::Ahead if in_row => match direction {
+            GameDirection::Left => Some(Vec2::new(-shooting.speed, 0.0)),
+            GameDirection::Right => Some(Vec2::new(shooting.speed, 0.0)),
+            GameDirection::None => None,
+        },
+        ShotAim::AtPlayer => Some((player - position).normalize() * shooting.speed),
+        ShotAim::Up if in_column => Some(Vec2::new(0.0, shooting.speed)),
+        ShotAim::Down if in_column => Some(Vec2::new(0.0, -shooting.speed)),
+        ShotAim::DiagonalUp => {
+            let d = ((player.x - position.x).abs() - (player.y - position.y).abs()).abs();
+            match direction {
+                GameDirection::Left if d < shooting.range / 2.0 && player.x < position.x => Some(Vec2::new(-shooting.speed, shooting.speed)),
+                GameDirection::Right if d < shooting.range / 2.0 && player.x > position.x => Some(Vec2::new(shooting.speed, shooting.speed)),
+                _ => None,
             }
-
-            commands.spawn_bundle(SpriteSheetBundle {
-                texture_atlas: enemies_shot_images.enemy_06_shot.clone(),
-                sprite: TextureAtlasSprite {
-                    color: Color::WHITE,
-                    index: 0,
-                    custom_size: Some(SHOT_06_SIZE),
-                    ..Default::default()
-                },
-                transform: Transform { 
-                    translation: Vec3::new(enemy_transfrom.translation.x, enemy_transfrom.translation.y - 25.0, 100.0),
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .insert(EnemyShotComponent {
-                enemy_type: 6,
-                size: SHOT_06_SIZE,
-                velocity: Vec2::new(0.0, -200.0),
-            })
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-
-            sfx.stop();
-            sfx.play(sounds.enemy_06_shot.clone()).looped();
-            sounds.enemy_06_shot_counter += 1;
-
-            enemy.shooting_cooldown = enemy.shooting_cooldown_max;
-        }
+        },
+        ShotAim::Sideways if in_row => {
+            if player.x < position.x {
+                Some(Vec2::new(-shooting.speed, 0.0))
+            }
+            else {
+                Some(Vec2::new(shooting.speed, 0.0))
+            }
+        },
+        _ => None,
     }
 }
 
-fn enemy_07_shooting(
+// shot numbered as in ENEMY_SHOT_TYPES
+fn spawn_enemy_shot(
     commands: &mut Commands, 
-    enemy_cooldown: &mut u16,
-    enemy_cooldown_max: u16,
-    enemy_posx: f32, enemy_posy: f32,
-    player_posx: f32, player_posy: f32,
-    enemies_shot_images: &Res<EnemiesShotImages>,) -> bool
+    rng: &mut StdRng,
+    enemies_shot_images: &Res<EnemiesShotImages>,
+    enemy_type: usize,
+    shot: usize,
+    position: Vec3,
+    velocity: Vec2)
 {
-    if *enemy_cooldown > 0 {
-        *enemy_cooldown -= 1;
-        return false;
-    }
-
-    let mut rng = rand::thread_rng();
-
-    let mut velocity = Vec2::new(
-        player_posx - enemy_posx, 
-        player_posy - enemy_posy).normalize();
-    velocity *= 200.0;
-
-    commands.spawn_bundle(SpriteSheetBundle {
-        texture_atlas: enemies_shot_images.enemy_07_shot.clone(),
+    let (texture_atlas, size, animation) = match shot {
+        1 => (enemies_shot_images.enemy_01_shot.clone(), SHOT_01_SIZE, Some(0.1)),
+        2 => (enemies_shot_images.enemy_02_shot.clone(), SHOT_02_SIZE, None),
+        3 => (enemies_shot_images.enemy_03_shot.clone(), SHOT_03_SIZE, None),
+        5 => (enemies_shot_images.enemy_05_shot.clone(), SHOT_05_SIZE, Some(0.1)),
+        6 => (enemies_shot_images.enemy_06_shot.clone(), SHOT_06_SIZE, Some(0.1)),
+        7 => (enemies_shot_images.enemy_07_shot.clone(), SHOT_07_SIZE, Some(0.2)),
+        8 if velocity.x < 0.0 => (enemies_shot_images.enemy_08_shot_left.clone(), SHOT_08_SIZE, None),
+        8 => (enemies_shot_images.enemy_08_shot_right.clone(), SHOT_08_SIZE, None),
+        9 if velocity.x < 0.0 => (enemies_shot_images.enemy_09_shot_left.clone(), SHOT_09_SIZE, Some(0.05)),
+        9 => (enemies_shot_images.enemy_09_shot_right.clone(), SHOT_09_SIZE, Some(0.05)),
+        13 => (enemies_shot_images.enemy_13_shot.clone(), SHOT_13_SIZE, None),
+        _ => panic!("ENEMIES: Unexpected shot type!")
+    };
+
+    let e = commands.spawn_bundle(SpriteSheetBundle {
+        texture_atlas,
         sprite: TextureAtlasSprite {
             color: Color::WHITE,
-            index: rng.gen_range(0..=3),
-            custom_size: Some(SHOT_07_SIZE),
+            // shot 7 starts at a random frame
+            index: if shot == 7 { rng.gen_range(0..=3) } else { 0 },
+            custom_size: Some(size),
             ..Default::default()
         },
         transform: Transform { 
-            translation: Vec3::new(enemy_posx, enemy_posy + 10.0, 100.0),
+            translation: position,
             ..Default::default()
         },
         ..Default::default()
     })
     .insert(EnemyShotComponent {
-        enemy_type: 7,
-        size: SHOT_07_SIZE,
+        enemy_type,
+        shot,
+        size,
         velocity,
     })
-    .insert(AnimationTimer(Timer::from_seconds(0.2, true)));
+    .id();
 
-    *enemy_cooldown = enemy_cooldown_max;
-    true
+    if let Some(seconds) = animation {
+        commands.entity(e).insert(AnimationTimer(Timer::from_seconds(seconds, true)));
+    }
 }
 
-fn enemy_07_movement(
+fn enemy_shooting(
     mut commands: Commands,
     player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&Transform, &EnemyComponent), With<EnemyType07>>,
+    query: Query<(Entity, &Transform, Option<&EnemyComponent>, Option<&CarriedEnemy>, Option<&Parent>, &Shooting), Without<PlayerComponent>>,
+    carrier_query: Query<&Transform, With<Carrier>>,
     mut maze: ResMut<Maze>,
     enemies_shot_images: Res<EnemiesShotImages>,
     sfx: Res<AudioChannel<SfxChannel>>,
-    sounds: Res<Sounds>,) 
+    sfx01: Res<AudioChannel<Shooting01Channel>>,
+    sfx05: Res<AudioChannel<Shooting05Channel>>,
+    sfx06: Res<AudioChannel<Shooting06Channel>>,
+    sfx08: Res<AudioChannel<Shooting08Channel>>,
+    sfx09: Res<AudioChannel<Shooting09Channel>>,
+    mut sounds: ResMut<Sounds>,
+    mut rng: ResMut<GameRng>,) 
 {
-    let player_transform = player_query.single();
+    let player_position = player_query.single().translation.truncate();
 
-    for (enemy_transfrom, component) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
+    for (entity, transform, component, carried, parent, shooting) in query.iter() {
+        let state = match enemy_state(&mut maze, entity, transform, component, carried.zip(parent), &carrier_query) {
+            Some(state) => state,
+            None => continue,
+        };
+        let shooting = &shooting.0;
 
-        if enemy_07_shooting(&mut commands, &mut enemy.shooting_cooldown, enemy.shooting_cooldown_max, 
-            enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            player_transform.translation.x, player_transform.translation.y, 
-            &enemies_shot_images)
-        {
-            sfx.play(sounds.enemy_07_shot.clone());
+        let velocity = match shot_velocity(shooting, *state.direction, state.position, player_position) {
+            Some(velocity) => velocity,
+            None => continue,
+        };
+        if *state.shooting_cooldown > 0.0 {
+            *state.shooting_cooldown -= TIME_STEP;
+            continue;
         }
-    }
-}
+        *state.shooting_cooldown = state.shooting_cooldown_max;
 
-fn enemy_08_shooting(
-    commands: &mut Commands, 
-    enemy_entity: &Entity,
-    enemy_direction: GameDirection,
-    enemy_health: &mut i16,
-    enemy_posx: f32, enemy_posy: f32,
-    player_posx: f32, player_posy: f32,
-    enemies_shot_images: &Res<EnemiesShotImages>,) -> bool
-{
-    let dx = (player_posx - enemy_posx).abs();
-    let dy = (player_posy - enemy_posy).abs();
-    let d = (dx - dy).abs();
+        let offset_x = if *state.direction == GameDirection::Left { -shooting.offset_x } else { shooting.offset_x };
+        spawn_enemy_shot(&mut commands, &mut rng.game, &enemies_shot_images, state.enemy_type, shooting.shot, 
+            Vec3::new(state.position.x + offset_x, state.position.y + shooting.offset_y, 100.0), velocity);
 
-    if d < 25.0 / 2.0 && enemy_direction == GameDirection::Left && player_posx < enemy_posx 
-    {
-        // missile up left
-        commands.spawn_bundle(SpriteSheetBundle {
-            texture_atlas: enemies_shot_images.enemy_08_shot_left.clone(),
-            sprite: TextureAtlasSprite {
-                color: Color::WHITE,
-                index: 0,
-                custom_size: Some(SHOT_08_SIZE),
-                ..Default::default()
+        // looped sounds play until the last of their shots is gone, see enemy_shot_movement
+        match shooting.shot {
+            1 => {
+                if !sfx01.is_playing_sound() {
+                    sfx01.play(sounds.enemy_01_shot.clone()).looped();
+                }
+                sounds.enemy_01_shot_counter += 1;
             },
-            transform: Transform { 
-                translation: Vec3::new(enemy_posx, enemy_posy, 100.0),
-                ..Default::default()
+            2 => { sfx.play(sounds.enemy_02_shot.clone()); },
+            3 | 13 => { sfx.play(sounds.enemy_03_13_shot.clone()); },
+            5 => {
+                sfx05.stop();
+                sfx05.play(sounds.enemy_05_shot.clone()).looped();
+                sounds.enemy_05_shot_counter += 1;
             },
-            ..Default::default()
-        })
-        .insert(EnemyShotComponent {
-            enemy_type: 8,
-            size: SHOT_09_SIZE,
-            velocity: Vec2::new(-140.0, 140.0),
-        });
-
-        // despawn enemy
-        commands.entity(*enemy_entity).despawn_recursive();
-        *enemy_health = 0;
-
-        return true;
-    }
-    else if d < 25.0 / 2.0 && enemy_direction == GameDirection::Right && player_posx > enemy_posx 
-    {
-        // missile up right
-        commands.spawn_bundle(SpriteSheetBundle {
-            texture_atlas: enemies_shot_images.enemy_08_shot_right.clone(),
-            sprite: TextureAtlasSprite {
-                color: Color::WHITE,
-                index: 0,
-                custom_size: Some(SHOT_08_SIZE),
-                ..Default::default()
+            6 => {
+                sfx06.stop();
+                sfx06.play(sounds.enemy_06_shot.clone()).looped();
+                sounds.enemy_06_shot_counter += 1;
             },
-            transform: Transform { 
-                translation: Vec3::new(enemy_posx, enemy_posy, 100.0),
-                ..Default::default()
+            7 => { sfx.play(sounds.enemy_07_shot.clone()); },
+            8 => {
+                sfx08.stop();
+                sfx08.play(sounds.enemy_08_shot.clone()).looped();
+                sounds.enemy_08_shot_counter += 1;
             },
-            ..Default::default()
-        })
-        .insert(EnemyShotComponent {
-            enemy_type: 8,
-            size: SHOT_08_SIZE,
-            velocity: Vec2::new(140.0, 140.0),
-        });
-
-        // despawn enemy
-        commands.entity(*enemy_entity).despawn_recursive();
-        *enemy_health = 0;
+            9 => {
+                sfx.play(sounds.enemy_09_launch.clone());
 
-        return true;
-    }
-
-    false
-}
-
-fn enemy_08_movement(
-    mut commands: Commands,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(Entity, &Transform, &EnemyComponent), With<EnemyType08>>,
-    mut maze: ResMut<Maze>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx: Res<AudioChannel<Shooting08Channel>>,
-    mut sounds: ResMut<Sounds>,) 
-{
-    let player_transform = player_query.single();
-
-    for (enemy_entity, enemy_transfrom, component) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-
-        if enemy_08_shooting(&mut commands, &enemy_entity, enemy.direction, &mut enemy.health, 
-            enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            player_transform.translation.x, player_transform.translation.y, 
-            &enemies_shot_images)
-        {
-            sfx.stop();
-            sfx.play(sounds.enemy_08_shot.clone()).looped();
-            sounds.enemy_08_shot_counter += 1;
-        }
-    }
-}
-
-fn enemy_09_shooting(
-    commands: &mut Commands, 
-    enemy_entity: &Entity,
-    enemy_health: &mut i16,
-    enemy_posx: f32, enemy_posy: f32,
-    player_posx: f32, player_posy: f32,
-    enemies_shot_images: &Res<EnemiesShotImages>,) -> bool
-{
-    if player_posy > enemy_posy - 25.0 && player_posy < enemy_posy + 25.0
-    {
-        if player_posx < enemy_posx {
-            // missile left
-            commands.spawn_bundle(SpriteSheetBundle {
-                texture_atlas: enemies_shot_images.enemy_09_shot_left.clone(),
-                sprite: TextureAtlasSprite {
-                    color: Color::WHITE,
-                    index: 0,
-                    custom_size: Some(SHOT_09_SIZE),
-                    ..Default::default()
-                },
-                transform: Transform { 
-                    translation: Vec3::new(enemy_posx, enemy_posy, 100.0),
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .insert(EnemyShotComponent {
-                enemy_type: 9,
-                size: SHOT_09_SIZE,
-                velocity: Vec2::new(-200.0, 0.0),
-            })
-            .insert(AnimationTimer(Timer::from_seconds(0.05, true)));
-        }
-        else {
-            // missile right
-            commands.spawn_bundle(SpriteSheetBundle {
-                texture_atlas: enemies_shot_images.enemy_09_shot_right.clone(),
-                sprite: TextureAtlasSprite {
-                    color: Color::WHITE,
-                    index: 0,
-                    custom_size: Some(SHOT_09_SIZE),
-                    ..Default::default()
-                },
-                transform: Transform { 
-                    translation: Vec3::new(enemy_posx, enemy_posy, 100.0),
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .insert(EnemyShotComponent {
-                enemy_type: 9,
-                size: SHOT_09_SIZE,
-                velocity: Vec2::new(200.0, 0.0),
-            })
-            .insert(AnimationTimer(Timer::from_seconds(0.05, true)));
+                sfx09.stop();
+                sfx09.play(sounds.enemy_09_shot.clone()).looped();
+                sounds.enemy_09_shot_counter += 1;
+            },
+            _ => {}
         }
 
-        // despawn enemy
-        commands.entity(*enemy_entity).despawn_recursive();
-        *enemy_health = 0;
-        return true;
-    }
-
-    false
-}
-
-fn enemy_09_movement(
-    mut commands: Commands,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(Entity, &Transform, &EnemyComponent), With<EnemyType09>>,
-    mut maze: ResMut<Maze>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx: Res<AudioChannel<SfxChannel>>,
-    sfx09: Res<AudioChannel<Shooting09Channel>>,
-    mut sounds: ResMut<Sounds>,) 
-{
-    let player_transform = player_query.single();
-
-    for (enemy_entity, enemy_transfrom, component) in enemy_query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-
-        if enemy_09_shooting(&mut commands, &enemy_entity, &mut enemy.health, 
-            enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            player_transform.translation.x, player_transform.translation.y, 
-            &enemies_shot_images)
-        {
-            sfx.play(sounds.enemy_09_launch.clone());
-
-            sfx09.stop();
-            sfx09.play(sounds.enemy_09_shot.clone()).looped();
-            sounds.enemy_09_shot_counter += 1;
+        if shooting.launch {
+            commands.entity(state.entity).despawn_recursive();
+            *state.health = 0;
         }
     }
 }
 
-fn enemy_10_spawning(
+fn spawn_random_enemy(
     commands: &mut Commands, 
-    rng: &mut ThreadRng,
+    rng: &mut StdRng,
     maze: &mut Maze,
-    enemy_posx: f32, enemy_posy: f32,
+    position: Vec2,
     room_seq: usize,
-    enemies_images: &Res<EnemiesImages>) -> bool
+    spawner: &Spawner,
+    enemy_defs: &Res<EnemyDefs>)
 {
     let room = &mut maze.rooms[room_seq];
+    let spawns = &spawner.spawns;
 
-    if room.enemies_from_10.len() < 10 {
-        let id = rng.gen_range(11..=17); // random spawnable enemy
+    if room.enemies_from_10.len() < spawner.limit && !spawns.is_empty() {
+        let id = spawns[rng.gen_range(0..spawns.len())];
+        let def = enemy_defs.def(id);
         let color_idx = rng.gen_range(0..ENEMY_COLORS.len());
 
-        println!("Enemy 10: spawn enemy {}, seq={}", id, room.from_10_seq);
-
-        let velocity: Vec2;
-        if id == 15 || id == 16 {
-            let mut x = 150.0;
-            let mut y = 150.0;
-            if rng.gen_bool(1.0/2.0) {
-                x *= -1.0;
-            }
-            if rng.gen_bool(1.0/2.0) {
-                y *= -1.0;
-            }
-            velocity = Vec2::new(x, y);
-        }
-        else {
-            velocity = Vec2::splat(0.0);
-        }
-
-        let cooldown: u16;
-        if id == 13 {
-            cooldown = 500;
-        }
-        else {
-            cooldown = u16::MAX;
-        }
-
         let new_enemy = Enemy {
-            health: 10,
+            health: def.health,
             room_seq,
             enemy_seq: room.from_10_seq, 
             enemy_type: id, 
             enemy_subtype: 0, 
             first: false,
-            posx: enemy_posx, 
-            posy: enemy_posy, 
+            posx: position.x, 
+            posy: position.y, 
             color: ENEMY_COLORS[color_idx],
-            velocity,
-            shooting_cooldown: 0,
-            shooting_cooldown_max: cooldown,
-            direction: GameDirection::None,
+            velocity: start_velocity(def, rng),
+            shooting_cooldown: 0.0,
+            shooting_cooldown_max: def.shooting_cooldown.unwrap_or(f32::MAX),
+            direction: start_direction(def, 0),
             is_from_10: true,
             fellow_enemy: None,
             fellow_item: None,
         };
 
-        match id {
-            11 => {
-                let e = crate::maze::spawn_enemy(commands, &new_enemy, enemies_images.enemy_11.clone(), 
-                    ENEMY_NN_SIZE, Vec3::new(enemy_posx, enemy_posy, 100.0), 0);
-                commands.entity(e).insert(EnemyType11);
-            },
-            12 => {
-                let e = crate::maze::spawn_enemy(commands, &new_enemy, enemies_images.enemy_12.clone(), 
-                    ENEMY_NN_SIZE, Vec3::new(enemy_posx, enemy_posy, 100.0), 0);
-                commands.entity(e).insert(EnemyType12)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-            },
-            13 => {
-                let e = crate::maze::spawn_enemy(commands, &new_enemy, enemies_images.enemy_13_left.clone(), 
-                    ENEMY_NN_SIZE, Vec3::new(enemy_posx, enemy_posy, 100.0), 0);
-                commands.entity(e).insert(EnemyType13)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-            },
-            14 => {
-                let e = crate::maze::spawn_enemy(commands, &new_enemy, enemies_images.enemy_14_left.clone(), 
-                    ENEMY_NN_SIZE, Vec3::new(enemy_posx, enemy_posy, 100.0), 0);
-                commands.entity(e).insert(EnemyType14)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-            },
-            15 => {
-                let e = crate::maze::spawn_enemy(commands, &new_enemy, enemies_images.enemy_15.clone(), 
-                    ENEMY_NN_SIZE, Vec3::new(enemy_posx, enemy_posy, 100.0), 0);
-                commands.entity(e).insert(EnemyType15)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-            },
-            16 => {
-                let e = crate::maze::spawn_enemy(commands, &new_enemy, enemies_images.enemy_16.clone(), 
-                    ENEMY_NN_SIZE, Vec3::new(enemy_posx, enemy_posy, 100.0), 0);
-                commands.entity(e).insert(EnemyType16)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-            },
-            17 => {
-                let e = crate::maze::spawn_enemy(commands, &new_enemy, enemies_images.enemy_17_left.clone(), 
-                    ENEMY_NN_SIZE, Vec3::new(enemy_posx, enemy_posy, 100.0), 0);
-                commands.entity(e).insert(EnemyType17)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-            },
-            _ => panic!("Unexpected enemy id!")
-        }
+        crate::maze::spawn_defined_enemy(commands, &new_enemy, position.extend(100.0), enemy_defs);
 
         room.enemies_from_10.push(new_enemy);
         room.from_10_seq += 1;
     }
-
-    true
-}
-
-fn enemy_10_movement(
-    mut commands: Commands,
-    mut enemy_query: Query<(&Transform, &EnemyComponent, &mut TextureAtlasSprite), With<EnemyType10>>,
-    mut maze: ResMut<Maze>,
-    enemies_images: Res<EnemiesImages>,
-    sfx: Res<AudioChannel<SfxChannel>>,
-    sounds: Res<Sounds>,) 
-{
-    let mut rng = rand::thread_rng();
-
-    for (enemy_transfrom, component, mut sprite) in enemy_query.iter_mut() 
-    {
-        {
-            let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-            if enemy.shooting_cooldown > 0 {
-                enemy.shooting_cooldown -= 1;
-                continue;
-            }
-            enemy.shooting_cooldown = enemy.shooting_cooldown_max;
-        }
-
-        if enemy_10_spawning(&mut commands, &mut rng, &mut maze, enemy_transfrom.translation.x, enemy_transfrom.translation.y, 
-            component.room_seq, &enemies_images) 
-        {
-            if sprite.index == 0 {
-                sprite.index = 1;
-            }
-            else {
-                sprite.index = 0;
-            }
-            sfx.play(sounds.enemy_10.clone());
-        }
-    }
-}
-
-fn enemy_11_movement(
-    time: Res<Time>,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&mut Transform, &mut EnemyComponent, &mut TextureAtlasSprite), (With<EnemyType11>, Without<PlayerComponent>)>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>,) 
-{
-    let mut rng = rand::thread_rng();
-    let player_transform = player_query.single();
-
-    for (mut enemy_transfrom, component, mut enemy_sprite) in enemy_query.iter_mut() {
-        // println!("MOVEMENT: Enemy11");
-
-        if enemy_transfrom.translation.x > player_transform.translation.x {
-            enemy_sprite.index = 0;
-        }
-        else {
-            enemy_sprite.index = 1;
-        }
-
-        let enemy: &mut Enemy;
-        if component.is_from_10 {
-            // enemy = &mut maze.rooms[component.room_seq].enemies_from_10[component.enemy_seq];
-            let eopt = get_enemy_from_10(&mut maze.rooms[component.room_seq].enemies_from_10, component.enemy_seq);
-            if eopt.is_some() {
-                enemy = eopt.unwrap();
-            }
-            else {
-                return;
-            }
-        }
-        else {
-            enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-        }
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-        enemy.velocity += Vec2::new(0.0, enemy_transfrom.translation.y - player_transform.translation.y) * time.delta_seconds() * -1.0;
-
-        process_movement(time.delta_seconds(), enemy, &mut enemy_transfrom, ENEMY_NN_SIZE, &walls_query);
-    }
 }
 
-fn enemy_12_movement(
-    time: Res<Time>,
-    mut query: Query<(&mut Transform, &mut EnemyComponent), With<EnemyType12>>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>,) 
-{
-    let mut rng = rand::thread_rng();
-
-    for (mut transfrom, component) in query.iter_mut() {
-        let enemy: &mut Enemy;
-        if component.is_from_10 {
-            // enemy = &mut maze.rooms[component.room_seq].enemies_from_10[component.enemy_seq];
-            let eopt = get_enemy_from_10(&mut maze.rooms[component.room_seq].enemies_from_10, component.enemy_seq);
-            if eopt.is_some() {
-                enemy = eopt.unwrap();
-            }
-            else {
-                return;
-            }
-        }
-        else {
-            enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-        }
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-
-        process_movement(time.delta_seconds(), enemy, &mut transfrom, ENEMY_NN_SIZE, &walls_query);
-    }
-}
-
-fn enemy_13_movement(
+fn enemy_spawning(
     mut commands: Commands,
-    time: Res<Time>,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&mut Transform, &mut EnemyComponent, &mut Handle<TextureAtlas>), (With<EnemyType13>, Without<PlayerComponent>)>,
-    walls_query: Query<&WallComponent>,
+    mut query: Query<(Entity, &Transform, Option<&EnemyComponent>, Option<&CarriedEnemy>, Option<&Parent>, &Spawner, &mut TextureAtlasSprite)>,
+    carrier_query: Query<&Transform, With<Carrier>>,
     mut maze: ResMut<Maze>,
-    enemies_images: Res<EnemiesImages>,
-    enemies_shot_images: Res<EnemiesShotImages>,
+    enemy_defs: Res<EnemyDefs>,
     sfx: Res<AudioChannel<SfxChannel>>,
-    sounds: Res<Sounds>,) 
-{
-    let mut rng = rand::thread_rng();
-    let player_transform = player_query.single();
-
-    for (mut enemy_transfrom, component, mut enemy_atlas) in enemy_query.iter_mut() {
-        let enemy: &mut Enemy;
-        if component.is_from_10 {
-            // enemy = &mut maze.rooms[component.room_seq].enemies_from_10[component.enemy_seq];
-            let eopt = get_enemy_from_10(&mut maze.rooms[component.room_seq].enemies_from_10, component.enemy_seq);
-            if eopt.is_some() {
-                enemy = eopt.unwrap();
-            }
-            else {
-                return;
-            }
-        }
-        else {
-            enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-        }
-
-        if enemy_transfrom.translation.x > player_transform.translation.x {
-            *enemy_atlas = enemies_images.enemy_13_left.clone();
-            enemy.direction = GameDirection::Left;
-        }
-        else {
-            *enemy_atlas = enemies_images.enemy_13_right.clone();
-            enemy.direction = GameDirection::Right;
-        }
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-        enemy.velocity += Vec2::new(0.0, enemy_transfrom.translation.y - player_transform.translation.y) * time.delta_seconds() * -1.0;
-
-        process_movement(time.delta_seconds(), enemy, &mut enemy_transfrom, ENEMY_NN_SIZE, &walls_query);
-
-        if player_transform.translation.y > enemy_transfrom.translation.y - 25.0 &&
-           player_transform.translation.y < enemy_transfrom.translation.y + 25.0
-        {
-            if enemy.shooting_cooldown > 0 {
-                enemy.shooting_cooldown -= 1;
-                continue;
-            }
-
-            let velocity: Vec2;
-            let start_x: f32;
-            match enemy.direction {
-                GameDirection::Left => {
-                    velocity = Vec2::new(-300.0, 0.0);
-                    start_x = enemy_transfrom.translation.x - 12.0;
-                },
-                GameDirection::Right => {
-                    velocity = Vec2::new(300.0, 0.0);
-                    start_x = enemy_transfrom.translation.x + 12.0;
-                },
-                GameDirection::None => panic!("Unexpected direction!")
-            }
-
-            commands.spawn_bundle(SpriteSheetBundle {
-                texture_atlas: enemies_shot_images.enemy_13_shot.clone(),
-                sprite: TextureAtlasSprite {
-                    color: Color::WHITE,
-                    index: 0,
-                    custom_size: Some(SHOT_13_SIZE),
-                    ..Default::default()
-                },
-                transform: Transform { 
-                    translation: Vec3::new(start_x, enemy_transfrom.translation.y + 8.0, 100.0),
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .insert(EnemyShotComponent {
-                enemy_type: 13,
-                size: SHOT_13_SIZE,
-                velocity,
-            });
-
-            sfx.play(sounds.enemy_03_13_shot.clone());
-
-            enemy.shooting_cooldown = enemy.shooting_cooldown_max;
-        }
-    }
-}
-
-fn enemy_14_movement(
-    time: Res<Time>,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&mut Transform, &mut EnemyComponent, &mut Handle<TextureAtlas>), (With<EnemyType14>, Without<PlayerComponent>)>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>,
-    enemies_images: Res<EnemiesImages>) 
+    sounds: Res<Sounds>,
+    mut rng: ResMut<GameRng>,) 
 {
-    let mut rng = rand::thread_rng();
-    let player_transform = player_query.single();
-
-    for (mut enemy_transfrom, component, mut enemy_atlas) in enemy_query.iter_mut() {
-        if enemy_transfrom.translation.x > player_transform.translation.x {
-            *enemy_atlas = enemies_images.enemy_14_left.clone();
-        }
-        else {
-            *enemy_atlas = enemies_images.enemy_14_right.clone();
-        }
-
-        let enemy: &mut Enemy;
-        if component.is_from_10 {
-            // enemy = &mut maze.rooms[component.room_seq].enemies_from_10[component.enemy_seq];
-            let eopt = get_enemy_from_10(&mut maze.rooms[component.room_seq].enemies_from_10, component.enemy_seq);
-            if eopt.is_some() {
-                enemy = eopt.unwrap();
-            }
-            else {
-                return;
-            }
-        }
-        else {
-            enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-        }
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-        enemy.velocity += Vec2::new(0.0, enemy_transfrom.translation.y - player_transform.translation.y) * time.delta_seconds() * -1.0;
-
-        process_movement(time.delta_seconds(), enemy, &mut enemy_transfrom, ENEMY_NN_SIZE, &walls_query);
-    }
-}
-
-fn enemy_15_movement(
-    time: Res<Time>,
-    mut enemy_query: Query<(&mut Transform, &mut EnemyComponent), With<EnemyType15>>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>) 
-{
-    for (mut transfrom, component) in enemy_query.iter_mut() {
-
-        let enemy: &mut Enemy;
-        if component.is_from_10 {
-            // enemy = &mut maze.rooms[component.room_seq].enemies_from_10[component.enemy_seq];
-            let eopt = get_enemy_from_10(&mut maze.rooms[component.room_seq].enemies_from_10, component.enemy_seq);
-            if eopt.is_some() {
-                enemy = eopt.unwrap();
-            }
-            else {
-                return;
-            }
-        }
-        else {
-            enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-        }
-
-        process_movement(time.delta_seconds(), enemy, &mut transfrom, ENEMY_NN_SIZE, &walls_query);
-    }
-}
-
-fn enemy_16_movement(
-    time: Res<Time>,
-    mut enemy_query: Query<(&mut Transform, &mut EnemyComponent), With<EnemyType16>>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>) 
-{
-    for (mut transfrom, component) in enemy_query.iter_mut() {
-
-        let enemy: &mut Enemy;
-        if component.is_from_10 {
-            // enemy = &mut maze.rooms[component.room_seq].enemies_from_10[component.enemy_seq];
-            let eopt = get_enemy_from_10(&mut maze.rooms[component.room_seq].enemies_from_10, component.enemy_seq);
-            if eopt.is_some() {
-                enemy = eopt.unwrap();
-            }
-            else {
-                return;
-            }
-        }
-        else {
-            enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-        }
-
-        process_movement(time.delta_seconds(), enemy, &mut transfrom, ENEMY_NN_SIZE, &walls_query);
-    }
-}
+    for (entity, transform, component, carried, parent, spawner, mut sprite) in query.iter_mut() {
+        let (position, room_seq) = match enemy_state(&mut maze, entity, transform, component, carried.zip(parent), &carrier_query) {
+            Some(state) => {
+                if *state.shooting_cooldown > 0.0 {
+                    *state.shooting_cooldown -= TIME_STEP;
+                    continue;
+                }
+                *state.shooting_cooldown = state.shooting_cooldown_max;
+                (state.position, state.room_seq)
+            },
+            None => continue,
+        };
 
-fn enemy_17_movement(
-    time: Res<Time>,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemy_query: Query<(&mut Transform, &mut EnemyComponent, &mut Handle<TextureAtlas>), (With<EnemyType17>, Without<PlayerComponent>)>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>,
-    enemies_images: Res<EnemiesImages>) 
-{
-    let mut rng = rand::thread_rng();
-    let player_transform = player_query.single();
+        spawn_random_enemy(&mut commands, &mut rng.game, &mut maze, position, room_seq, spawner, &enemy_defs);
 
-    for (mut enemy_transfrom, component, mut enemy_atlas) in enemy_query.iter_mut() {
-        if enemy_transfrom.translation.x > player_transform.translation.x {
-            *enemy_atlas = enemies_images.enemy_17_left.clone();
+        if sprite.index == 0 {
+            sprite.index = 1;
         }
         else {
-            *enemy_atlas = enemies_images.enemy_17_right.clone();
-        }
-
-        let enemy: &mut Enemy;
-        if component.is_from_10 {
-            // enemy = &mut maze.rooms[component.room_seq].enemies_from_10[component.enemy_seq];
-            let eopt = get_enemy_from_10(&mut maze.rooms[component.room_seq].enemies_from_10, component.enemy_seq);
-            if eopt.is_some() {
-                enemy = eopt.unwrap();
-            }
-            else {
-                return;
-            }
+            sprite.index = 0;
         }
-        else {
-            enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-        }
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-        enemy.velocity += Vec2::new(0.0, enemy_transfrom.translation.y - player_transform.translation.y) * time.delta_seconds() * -1.0;
-
-        process_movement(time.delta_seconds(), enemy, &mut enemy_transfrom, ENEMY_NN_SIZE, &walls_query);
+        sfx.play(sounds.enemy_10.clone());
     }
 }
 
-fn enemy_18_movement(
-    time: Res<Time>,
-    mut query: Query<(&mut Transform, &mut EnemyComponent), With<EnemyType18>>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>,) 
-{
-    let mut rng = rand::thread_rng();
-
-    for (mut transfrom, component) in query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-
-        process_movement(time.delta_seconds(), enemy, &mut transfrom, ENEMY_18_SIZE, &walls_query);
-    }
-}
-
-fn enemy_19_movement(
-    time: Res<Time>,
-    mut query: Query<(&mut Transform, &mut EnemyComponent), With<EnemyType19>>,
-    walls_query: Query<&WallComponent>,
-    mut maze: ResMut<Maze>,) 
-{
-    let mut rng = rand::thread_rng();
-
-    for (mut transfrom, component) in query.iter_mut() {
-        let enemy = &mut maze.rooms[component.room_seq].enemies[component.enemy_seq];
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-
-        process_movement(time.delta_seconds(), enemy, &mut transfrom, ENEMY_19_SIZE, &walls_query);
-    }
-}
-
-fn process_carrier_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transform, walls_query: &Query<&WallComponent>) {
+fn process_carrier_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transform, size: Vec2, walls_query: &Query<&WallComponent>) {
     enemy.velocity.x = enemy.velocity.x.clamp(-400.0, 400.0);
     enemy.velocity.y = enemy.velocity.y.clamp(-400.0, 400.0);
 
@@ -1477,13 +820,10 @@ fn process_carrier_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transfor
     position += enemy.velocity;
     position *= dt;
 
-    let mut target_size = ENEMY_NN_SIZE;
+    // fellow enemy is drawn one carrier height above the carrier
+    let mut target_size = size;
     if enemy.fellow_enemy.is_some() {
-        let fellow = enemy.fellow_enemy.as_ref().unwrap();
-        match fellow.enemy_type {
-            7 => target_size.y += ENEMY_07_SIZE.y + 4.0,
-            _ => target_size.y += ENEMY_NN_SIZE.y,
-        }
+        target_size.y += size.y;
     }
     if enemy.fellow_item.is_some() {
         target_size.y += ITEM_H;
@@ -1494,7 +834,7 @@ fn process_carrier_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transfor
     let target = transform.translation + Vec3::new(position.x, 0.0, 0.0);
     let target_tmp = Vec3::new(
         transform.translation.x + position.x,
-        transform.translation.y - (ENEMY_NN_SIZE.y / 2.0) + (target_size.y / 2.0),
+        transform.translation.y - (size.y / 2.0) + (target_size.y / 2.0),
         0.0);
     if !walls_query.iter().any(|&wall| collision_check(
         target_tmp, target_size, 
@@ -1510,7 +850,7 @@ fn process_carrier_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transfor
     let target = transform.translation + Vec3::new(0.0, position.y, 0.0);
     let target_tmp = Vec3::new(
         transform.translation.x,
-        (transform.translation.y - (ENEMY_NN_SIZE.y / 2.0) + (target_size.y / 2.0)) + position.y,
+        (transform.translation.y - (size.y / 2.0) + (target_size.y / 2.0)) + position.y,
         0.0);
     if !walls_query.iter().any(|&wall| collision_check(
         target_tmp, target_size, 
@@ -1532,15 +872,15 @@ fn process_carrier_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transfor
         enemy.velocity.x *= -1.0;
     }
 
-    let y_tmp = transform.translation.y - (ENEMY_NN_SIZE.y / 2.0) + (target_size.y / 2.0);
+    let y_tmp = transform.translation.y - (size.y / 2.0) + (target_size.y / 2.0);
     
     if y_tmp > WINDOW_H - INFO_BAR_H - (target_size.y / 2.0) {
-        transform.translation.y = WINDOW_H - INFO_BAR_H - (target_size.y / 2.0) - (ENEMY_NN_SIZE.y / 2.0);
+        transform.translation.y = WINDOW_H - INFO_BAR_H - (target_size.y / 2.0) - (size.y / 2.0);
         enemy.velocity.y *= -1.0;
     }
 
     if y_tmp < (target_size.y / 2.0) {
-        transform.translation.y = ENEMY_NN_SIZE.y / 2.0;
+        transform.translation.y = size.y / 2.0;
         enemy.velocity.y *= -1.0;
     }
 
@@ -1549,145 +889,22 @@ fn process_carrier_movement(dt: f32, enemy: &mut Enemy, transform: &mut Transfor
     enemy.first = false;
 }
 
-fn enemy_20_movement(
-    mut commands: Commands, 
-    time: Res<Time>,
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut query_e20: Query<(Entity, &mut Transform, &mut EnemyComponent), (With<EnemyType20>, Without<PlayerComponent>)>,
-    mut query_e03: Query<&mut TextureAtlasSprite, (With<EnemyType03Fellow>, Without<EnemyType10Fellow>)>,
-    mut query_e10: Query<&mut TextureAtlasSprite, (With<EnemyType10Fellow>, Without<EnemyType03Fellow>)>,
+fn carrier_movement(
+    mut query: Query<(&mut Transform, &EnemyComponent, &Carrier)>,
     walls_query: Query<&WallComponent>,
     mut maze: ResMut<Maze>,
-    enemies_images: Res<EnemiesImages>,
-    enemies_shot_images: Res<EnemiesShotImages>,
-    sfx: Res<AudioChannel<SfxChannel>>,
-    sfx01: Res<AudioChannel<Shooting01Channel>>,
-    sfx05: Res<AudioChannel<Shooting05Channel>>,
-    sfx08: Res<AudioChannel<Shooting08Channel>>,
-    sfx09: Res<AudioChannel<Shooting09Channel>>,
-    mut sounds: ResMut<Sounds>,) 
+    enemy_defs: Res<EnemyDefs>,
+    mut rng: ResMut<GameRng>,) 
 {
-    let mut rng = rand::thread_rng();
-
-    for (e20_entity, mut e20_transfrom, e20_component) in query_e20.iter_mut() {
-        let enemy = &mut maze.rooms[e20_component.room_seq].enemies[e20_component.enemy_seq];
-
-        enemy.velocity += Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0)) * time.delta_seconds();
-        
-        process_carrier_movement(time.delta_seconds(), enemy, &mut e20_transfrom, &walls_query);
-
-        if let Some(fellow) = &mut enemy.fellow_enemy {
-            let player_transform = player_query.single();
-
-            // process (possible) shooting of 1, 2, 3, 5, 7, 8, 9
-            if fellow.enemy_type == 1 {
-                if enemy_01_shooting(&mut commands, &mut fellow.shooting_cooldown, fellow.shooting_cooldown_max, fellow.direction, 
-                    e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    player_transform.translation.y, 
-                    &enemies_shot_images)
-                {
-                    if !sfx01.is_playing_sound() {
-                        sfx01.play(sounds.enemy_01_shot.clone()).looped();
-                    }
-                    sounds.enemy_01_shot_counter += 1;
-                }
-            }
-            else if fellow.enemy_type == 2 {
-                if enemy_02_shooting(&mut commands, &mut fellow.shooting_cooldown, fellow.shooting_cooldown_max, 
-                    e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    player_transform.translation.x, player_transform.translation.y, 
-                    &enemies_shot_images)
-                {
-                    sfx.play(sounds.enemy_02_shot.clone());
-                }
-            }
-            else if fellow.enemy_type == 3 {
-                for mut sprite_e03 in query_e03.iter_mut() {
-                    if e20_transfrom.translation.x > player_transform.translation.x {
-                        sprite_e03.index = 0;
-                        fellow.direction = GameDirection::Left;
-                    }
-                    else {
-                        sprite_e03.index = 1;
-                        fellow.direction = GameDirection::Right;
-                    }
-                }
-
-                if enemy_03_shooting(&mut commands, &mut fellow.shooting_cooldown, fellow.shooting_cooldown_max, fellow.direction, 
-                    e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    player_transform.translation.y, 
-                    &enemies_shot_images)
-                {
-                    sfx.play(sounds.enemy_03_13_shot.clone());
-                }
-            }
-            else if fellow.enemy_type == 5 {
-                if enemy_05_shooting(&mut commands, &mut fellow.shooting_cooldown, fellow.shooting_cooldown_max, 
-                    e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    player_transform.translation.x, 
-                    &enemies_shot_images)
-                {
-                    sfx05.stop();
-                    sfx05.play(sounds.enemy_05_shot.clone()).looped();
-                    sounds.enemy_05_shot_counter += 1;
-                }
-            }
-            else if fellow.enemy_type == 7 {
-                if enemy_07_shooting(&mut commands, &mut fellow.shooting_cooldown, fellow.shooting_cooldown_max, 
-                    e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    player_transform.translation.x, player_transform.translation.y, 
-                    &enemies_shot_images)
-                {
-                    sfx.play(sounds.enemy_07_shot.clone());
-                }
-            }
-            else if fellow.enemy_type == 8 {
-                if enemy_08_shooting(&mut commands, &e20_entity, fellow.direction, &mut enemy.health, 
-                    e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    player_transform.translation.x, player_transform.translation.y, 
-                    &enemies_shot_images)
-                {
-                    sfx08.stop();
-                    sfx08.play(sounds.enemy_08_shot.clone()).looped();
-                    sounds.enemy_08_shot_counter += 1;
-                }
-            }
-            else if fellow.enemy_type == 9 {
-                if enemy_09_shooting(&mut commands, &e20_entity, &mut enemy.health, 
-                    e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    player_transform.translation.x, player_transform.translation.y, 
-                    &enemies_shot_images)
-                {
-                    sfx.play(sounds.enemy_09_launch.clone());
+    for (mut transform, component, carrier) in query.iter_mut() {
+        let enemy = match room_enemy(&mut maze, component) {
+            Some(enemy) => enemy,
+            None => continue,
+        };
 
-                    sfx09.stop();
-                    sfx09.play(sounds.enemy_09_shot.clone()).looped();
-                    sounds.enemy_09_shot_counter += 1;
-                }
-            }
-            // process (possible) spawning of 10
-            else if fellow.enemy_type == 10 {
-                if fellow.shooting_cooldown > 0 {
-                    fellow.shooting_cooldown -= 1;
-                    continue;
-                }
-                fellow.shooting_cooldown = fellow.shooting_cooldown_max;
+        enemy.velocity += random_acceleration(&mut rng.game, carrier.acceleration, TIME_STEP);
 
-                if enemy_10_spawning(&mut commands, &mut rng, &mut maze, e20_transfrom.translation.x, e20_transfrom.translation.y + ENEMY_NN_SIZE.y, 
-                    e20_component.room_seq, &enemies_images) 
-                {
-                    for mut sprite_e10 in query_e10.iter_mut() {
-                        if sprite_e10.index == 0 {
-                            sprite_e10.index = 1;
-                        }
-                        else {
-                            sprite_e10.index = 0;
-                        }
-                        sfx.play(sounds.enemy_10.clone());
-                    }
-                }
-            }
-        }
+        process_carrier_movement(TIME_STEP, enemy, &mut transform, enemy_defs.size(component.enemy_type), &walls_query);
     }
 }
 
@@ -1697,7 +914,6 @@ fn enemy_shot_movement(
     player_query: Query<&Transform, (With<PlayerComponent>, Without<EnemyShotComponent>)>,
     mut player: ResMut<Player>,
     walls_query: Query<&WallComponent>,
-    time: Res<Time>,
     explosions_images: Res<ExplosionsImages>,
     sfx: Res<AudioChannel<SfxChannel>>,
     sfx01: Res<AudioChannel<Shooting01Channel>>,
@@ -1705,7 +921,9 @@ fn enemy_shot_movement(
     sfx06: Res<AudioChannel<Shooting06Channel>>,
     sfx08: Res<AudioChannel<Shooting08Channel>>,
     sfx09: Res<AudioChannel<Shooting09Channel>>,
-    mut sounds: ResMut<Sounds>,) 
+    mut sounds: ResMut<Sounds>,
+    enemy_defs: Res<EnemyDefs>,
+    mut rng: ResMut<GameRng>,) 
 {
     let player_transform = player_query.single();
 
@@ -1714,8 +932,8 @@ fn enemy_shot_movement(
             if let Some(mut shot_transform) = topt {
 
                 let target = shot_transform.translation + 
-                    Vec3::new(shot_component.velocity.x * time.delta_seconds(), 
-                              shot_component.velocity.y * time.delta_seconds(), 0.0);
+                    Vec3::new(shot_component.velocity.x * TIME_STEP, 
+                              shot_component.velocity.y * TIME_STEP, 0.0);
 
                 // check shot collision with player
                 if collision_check(target, shot_component.size, 
@@ -1723,7 +941,7 @@ fn enemy_shot_movement(
                 {
                     // enemy shot hit player
 
-                    if shot_component.enemy_type == 1 && sounds.enemy_01_shot_counter > 0 {
+                    if shot_component.shot == 1 && sounds.enemy_01_shot_counter > 0 {
                         // println!("Shot 01 counter = {}", sounds.enemy_01_shot_counter);
                         sounds.enemy_01_shot_counter -= 1;
                         if sounds.enemy_01_shot_counter == 0 {
@@ -1731,44 +949,46 @@ fn enemy_shot_movement(
                         }
                     }
 
-                    if shot_component.enemy_type == 5 && sounds.enemy_05_shot_counter > 0 {
+                    if shot_component.shot == 5 && sounds.enemy_05_shot_counter > 0 {
                         sounds.enemy_05_shot_counter -= 1;
                         if sounds.enemy_05_shot_counter == 0 {
                             sfx05.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 6 && sounds.enemy_06_shot_counter > 0 {
+                    if shot_component.shot == 6 && sounds.enemy_06_shot_counter > 0 {
                         sounds.enemy_06_shot_counter -= 1;
                         if sounds.enemy_06_shot_counter == 0 {
                             sfx06.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 8 && sounds.enemy_08_shot_counter > 0 {
+                    if shot_component.shot == 8 && sounds.enemy_08_shot_counter > 0 {
                         sounds.enemy_08_shot_counter -= 1;
                         if sounds.enemy_08_shot_counter == 0 {
                             sfx08.stop();
                         }
                     }
 
-                    // enemy 9 -> player's instant death
-                    if shot_component.enemy_type == 9 {
+                    // shot 9 -> player's instant death
+                    if shot_component.shot == 9 {
                         if sounds.enemy_09_shot_counter > 0 {
                             sounds.enemy_09_shot_counter = 0;
                             sfx09.stop();
                         }
-                        player.health = 0.0;
+                        if player.invulnerable <= 0.0 {
+                            player.health = 0.0;
+                        }
                     }
 
                     // spawn boom for 1, 5, 6, 8
-                    if shot_component.enemy_type == 1 ||
-                        shot_component.enemy_type == 5 ||
-                        shot_component.enemy_type == 6 ||
-                        shot_component.enemy_type == 8 ||
-                        shot_component.enemy_type == 9
+                    if shot_component.shot == 1 ||
+                        shot_component.shot == 5 ||
+                        shot_component.shot == 6 ||
+                        shot_component.shot == 8 ||
+                        shot_component.shot == 9
                     {
-                        crate::explosions::spawn_boom(&mut commands, shot_transform.translation, &explosions_images);
+                        crate::explosions::spawn_boom(&mut commands, shot_transform.translation, &explosions_images, &mut rng.effects);
                         sfx.play(sounds.boom.clone());
                     }
                     else {
@@ -1779,8 +999,10 @@ fn enemy_shot_movement(
                         commands.entity(entity).despawn_recursive();
                     }
 
-                    // reduce player damage
-                    player.health -= 10.0;
+                    // reduce player damage, not right after respawn
+                    if player.invulnerable <= 0.0 {
+                        player.health -= enemy_defs.def(shot_component.enemy_type).damage;
+                    }
                     continue;
                 }
 
@@ -1792,17 +1014,17 @@ fn enemy_shot_movement(
                     // enemy shot hits wall
 
                     // spawn boom for 1, 5, 6, 8, 9
-                    if shot_component.enemy_type == 1 ||
-                        shot_component.enemy_type == 5 ||
-                        shot_component.enemy_type == 6 ||
-                        shot_component.enemy_type == 8 ||
-                        shot_component.enemy_type == 9
+                    if shot_component.shot == 1 ||
+                        shot_component.shot == 5 ||
+                        shot_component.shot == 6 ||
+                        shot_component.shot == 8 ||
+                        shot_component.shot == 9
                     {
-                        crate::explosions::spawn_boom(&mut commands, shot_transform.translation, &explosions_images);
+                        crate::explosions::spawn_boom(&mut commands, shot_transform.translation, &explosions_images, &mut rng.effects);
                         sfx.play(sounds.boom.clone());
                     }
                     
-                    if shot_component.enemy_type == 1 && sounds.enemy_01_shot_counter > 0 {
+                    if shot_component.shot == 1 && sounds.enemy_01_shot_counter > 0 {
                         // println!("Shot 01 counter = {}", sounds.enemy_01_shot_counter);
                         sounds.enemy_01_shot_counter -= 1;
                         if sounds.enemy_01_shot_counter == 0 {
@@ -1810,28 +1032,28 @@ fn enemy_shot_movement(
                         }
                     }
 
-                    if shot_component.enemy_type == 5 && sounds.enemy_05_shot_counter > 0 {
+                    if shot_component.shot == 5 && sounds.enemy_05_shot_counter > 0 {
                         sounds.enemy_05_shot_counter -= 1;
                         if sounds.enemy_05_shot_counter == 0 {
                             sfx05.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 6 && sounds.enemy_06_shot_counter > 0 {
+                    if shot_component.shot == 6 && sounds.enemy_06_shot_counter > 0 {
                         sounds.enemy_06_shot_counter -= 1;
                         if sounds.enemy_06_shot_counter == 0 {
                             sfx06.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 8 && sounds.enemy_08_shot_counter > 0 {
+                    if shot_component.shot == 8 && sounds.enemy_08_shot_counter > 0 {
                         sounds.enemy_08_shot_counter -= 1;
                         if sounds.enemy_08_shot_counter == 0 {
                             sfx08.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 9 && sounds.enemy_09_shot_counter > 0 {
+                    if shot_component.shot == 9 && sounds.enemy_09_shot_counter > 0 {
                         sounds.enemy_09_shot_counter -= 1;
                         if sounds.enemy_09_shot_counter == 0 {
                             sfx09.stop();
@@ -1849,7 +1071,7 @@ fn enemy_shot_movement(
                 if shot_transform.translation.x < 0.0 || shot_transform.translation.x > WINDOW_W ||
                    shot_transform.translation.y < 0.0 || shot_transform.translation.y > WINDOW_H - INFO_BAR_H
                 {
-                    if shot_component.enemy_type == 1 && sounds.enemy_01_shot_counter > 0 {
+                    if shot_component.shot == 1 && sounds.enemy_01_shot_counter > 0 {
                         // println!("Shot 01 counter = {}", sounds.enemy_01_shot_counter);
                         sounds.enemy_01_shot_counter -= 1;
                         if sounds.enemy_01_shot_counter == 0 {
@@ -1857,28 +1079,28 @@ fn enemy_shot_movement(
                         }
                     }
 
-                    if shot_component.enemy_type == 5 && sounds.enemy_05_shot_counter > 0 {
+                    if shot_component.shot == 5 && sounds.enemy_05_shot_counter > 0 {
                         sounds.enemy_05_shot_counter -= 1;
                         if sounds.enemy_05_shot_counter == 0 {
                             sfx05.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 6 && sounds.enemy_06_shot_counter > 0 {
+                    if shot_component.shot == 6 && sounds.enemy_06_shot_counter > 0 {
                         sounds.enemy_06_shot_counter -= 1;
                         if sounds.enemy_06_shot_counter == 0 {
                             sfx06.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 8 && sounds.enemy_08_shot_counter > 0 {
+                    if shot_component.shot == 8 && sounds.enemy_08_shot_counter > 0 {
                         sounds.enemy_08_shot_counter -= 1;
                         if sounds.enemy_08_shot_counter == 0 {
                             sfx08.stop();
                         }
                     }
 
-                    if shot_component.enemy_type == 9 && sounds.enemy_09_shot_counter > 0 {
+                    if shot_component.shot == 9 && sounds.enemy_09_shot_counter > 0 {
                         sounds.enemy_09_shot_counter -= 1;
                         if sounds.enemy_09_shot_counter == 0 {
                             sfx09.stop();
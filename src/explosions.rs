@@ -1,10 +1,14 @@
 use bevy::prelude::*;
 use rand::Rng;
+use rand::rngs::StdRng;
 
 use crate::{WINDOW_W, WINDOW_H, H_PADDING, GRAVITY, GameState, load_atlas, collision_check};
 use crate::maze::WallComponent;
+use crate::loading::LoadingAssets;
+use crate::random::GameRng;
 
-pub const FRAGMENT_COOLDOWN: u16 = 200;
+// seconds between two frames of a fragment
+pub const FRAGMENT_COOLDOWN: f32 = 0.2;
 
 pub struct ExplosionsPlugin;
 
@@ -36,7 +40,7 @@ pub struct Boom;
 #[derive(Component)]
 pub struct Fragment {
     pub velocity: Vec2,
-    pub cooldown: u16,
+    pub cooldown: f32,
 }
 
 #[derive(Component, Deref, DerefMut)]
@@ -50,14 +54,20 @@ pub struct FlashEffect {
 #[derive(Component, Deref, DerefMut)]
 pub struct FlashAnimationTimer(pub Timer);
 
-pub fn load_explosions_images(mut commands: Commands, assets: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>) {
+pub fn load_explosions_images(
+    mut commands: Commands, 
+    assets: Res<AssetServer>, 
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>, 
+    mut loading: ResMut<LoadingAssets>)
+{
     commands.insert_resource(ExplosionsImages {
-        boom_image: load_atlas(&assets, &mut texture_atlases, "images/boom.png", Vec2::splat(50.0), 1, 6, Some(H_PADDING)),
-        fragment_image: load_atlas(&assets, &mut texture_atlases, "images/fragment.png", Vec2::splat(25.0), 1, 5, Some(H_PADDING)),
+        boom_image: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/boom.png", Vec2::splat(50.0), 1, 6, Some(H_PADDING)),
+        fragment_image: load_atlas(&assets, &mut texture_atlases, &mut loading, "images/fragment.png", Vec2::splat(25.0), 1, 5, Some(H_PADDING)),
     });
 }
 
-pub fn spawn_boom(commands: &mut Commands, position: Vec3, explosions_images: &Res<ExplosionsImages>,) {
+// rng is the effects stream of GameRng
+pub fn spawn_boom(commands: &mut Commands, position: Vec3, explosions_images: &Res<ExplosionsImages>, rng: &mut StdRng) {
     // boom
     commands.spawn_bundle(SpriteSheetBundle {
         texture_atlas: explosions_images.boom_image.clone(),
@@ -80,8 +90,6 @@ pub fn spawn_boom(commands: &mut Commands, position: Vec3, explosions_images: &R
     // 3   4
     // 5 6 7
 
-    let mut rng = rand::thread_rng();
-
     // fragment 0
     commands.spawn_bundle(SpriteSheetBundle {
         texture_atlas: explosions_images.fragment_image.clone(),
@@ -238,17 +246,20 @@ fn animate_boom(
     }
 }
 
+// per frame and not in GAME_STEP: fragments fly on in the Death state too, where the steps do not run,
+// and they only draw from the effects stream, so the frame rate does not change the game
 fn fragment_movement(
     mut commands: Commands,
     time: Res<Time>,
     mut fragment_query: Query<(Entity, &mut Fragment, &mut Transform, &mut TextureAtlasSprite)>,
-    walls_query: Query<&WallComponent>) 
+    walls_query: Query<&WallComponent>,
+    mut rng: ResMut<GameRng>) 
 {
-    let mut rng = rand::thread_rng();
+    let rng = &mut rng.effects;
 
     for (entity, mut fragment, mut transform, mut sprite) in fragment_query.iter_mut() {
-        if fragment.cooldown > 0 {
-            fragment.cooldown -= 1;
+        if fragment.cooldown > 0.0 {
+            fragment.cooldown -= time.delta_seconds();
         }
         else {
             fragment.cooldown = FRAGMENT_COOLDOWN;
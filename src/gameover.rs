@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 
-use crate::{GameState, WINDOW_W2, WINDOW_H2, mainmenu::UiAssets, maze::Maze};
+use crate::{GameState, WINDOW_W2, WINDOW_H2, mainmenu::UiAssets, maze::Maze, player::Player};
+use crate::savegame::Checkpoint;
+use crate::actions::{ActionInput, GameAction};
 
 pub struct GameOverPlugin;
 
@@ -22,7 +24,7 @@ impl Plugin for GameOverPlugin {
 #[derive(Component)]
 pub struct GameOverText;
 
-fn spawn_game_over(mut commands: Commands, ui_assets: Res<UiAssets>, mut maze: ResMut<Maze>) {
+fn spawn_game_over(mut commands: Commands, ui_assets: Res<UiAssets>, maze: Res<Maze>, checkpoint: Res<Checkpoint>) {
     commands.spawn_bundle(Text2dBundle {
         text: Text::from_section(
             "Game Over!", 
@@ -59,7 +61,19 @@ fn spawn_game_over(mut commands: Commands, ui_assets: Res<UiAssets>, mut maze: R
         ..Default::default()
     }).insert(GameOverText);
 
-    maze.loaded = false;
+    if checkpoint.exists() {
+        commands.spawn_bundle(Text2dBundle {
+            text: Text::from_section(
+                "ENTER / pad South - restart from checkpoint, any other key - menu", 
+                TextStyle {
+                    font: ui_assets.font.clone(),
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                }).with_alignment(TextAlignment::CENTER),
+            transform: Transform::from_xyz(WINDOW_W2, WINDOW_H2 - 150.0, 0.0),
+            ..Default::default()
+        }).insert(GameOverText);
+    }
 }
 
 fn despawn_game_over(mut commands: Commands, text_query: Query<Entity, With<GameOverText>>) {
@@ -68,9 +82,30 @@ fn despawn_game_over(mut commands: Commands, text_query: Query<Entity, With<Game
     }
 }
 
-fn game_over_keyboard_input(mut keyboard: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
-    if keyboard.get_just_pressed().len() != 0 {
-        keyboard.clear();
+fn game_over_keyboard_input(
+    mut actions: ResMut<ActionInput>,
+    mut state: ResMut<State<GameState>>,
+    mut maze: ResMut<Maze>,
+    mut player: ResMut<Player>,
+    checkpoint: Res<Checkpoint>)
+{
+    if actions.just_pressed(GameAction::Confirm) && checkpoint.exists() {
+        actions.clear();
+        match checkpoint.restore(&mut maze, &mut player) {
+            Ok(()) => {
+                state.set(GameState::Game).expect("GameOver: Failed to change state!");
+            },
+            Err(error) => {
+                println!("Failed to restore checkpoint: {}", error);
+                maze.loaded = false;
+                maze.load_error = Some(error);
+                state.set(GameState::MazeError).expect("GameOver: Failed to change state!");
+            }
+        }
+    }
+    else if actions.any_just_pressed() {
+        actions.clear();
+        maze.loaded = false;
         state.set(GameState::Menu).expect("GameOver: Failed to change state!");
     }
 }
@@ -0,0 +1,75 @@
+// Gamepad state shared by the action bindings and the menus.
+// Every connected gamepad is read, so any of them can play.
+
+use bevy::prelude::*;
+use bevy::input::InputSystem;
+
+// stick position counted as pressed
+pub const STICK_THRESHOLD: f32 = 0.5;
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(PadInput::default())
+        .add_system_to_stage(CoreStage::PreUpdate, read_gamepads.label("ReadGamepadsSystem").after(InputSystem));
+    }
+}
+
+// state of the gamepads in the current frame
+#[derive(Default)]
+pub struct PadInput {
+    // left stick, the one pushed furthest on each axis
+    pub stick: Vec2,
+    // first button pressed in this frame, for "press any key" screens and redefining
+    pub any_button: Option<GamepadButtonType>,
+}
+
+impl PadInput {
+    pub fn stick_left(&self) -> bool {
+        self.stick.x < -STICK_THRESHOLD
+    }
+
+    pub fn stick_right(&self) -> bool {
+        self.stick.x > STICK_THRESHOLD
+    }
+
+    pub fn stick_up(&self) -> bool {
+        self.stick.y > STICK_THRESHOLD
+    }
+
+    pub fn stick_down(&self) -> bool {
+        self.stick.y < -STICK_THRESHOLD
+    }
+}
+
+fn read_gamepads(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut pad: ResMut<PadInput>)
+{
+    let mut any_button = None;
+    let mut stick = Vec2::ZERO;
+
+    for gamepad in gamepads.iter() {
+        let stick_x = axes.get(GamepadAxis(*gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0);
+        let stick_y = axes.get(GamepadAxis(*gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.0);
+        if stick_x.abs() > stick.x.abs() {
+            stick.x = stick_x;
+        }
+        if stick_y.abs() > stick.y.abs() {
+            stick.y = stick_y;
+        }
+    }
+
+    for button in buttons.get_just_pressed() {
+        if any_button.is_none() {
+            any_button = Some(button.1);
+        }
+    }
+
+    pad.stick = stick;
+    pad.any_button = any_button;
+}
@@ -0,0 +1,79 @@
+// Fixed time step of the gameplay: movement, shooting, collisions and every cooldown advance
+// by TIME_STEP, the GameStep stage runs as many steps as fit in the frame time.
+// The game runs at the same speed with any frame rate, rates are given per second.
+
+use bevy::prelude::*;
+use bevy::ecs::schedule::ShouldRun;
+
+use crate::GameState;
+use crate::actions::ActionInput;
+
+// seconds of game time in one step
+pub const TIME_STEP: f32 = 1.0 / 120.0;
+// longest frame time caught up with, a stalled window does not fast forward the game
+pub const MAX_FRAME_TIME: f32 = 0.25;
+
+pub const GAME_STEP: &str = "GameStep";
+
+pub struct GameStepPlugin;
+
+impl Plugin for GameStepPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(GameStep::default())
+        .add_stage_after(CoreStage::Update, GAME_STEP, SystemStage::parallel().with_run_criteria(game_step));
+    }
+}
+
+#[derive(Default)]
+pub struct GameStep {
+    // frame time not stepped yet
+    accumulator: f32,
+    // a step has just run, the criterion is asked again in the same frame
+    stepping: bool,
+    // a state change is queued, see stop
+    stopped: bool,
+}
+
+impl GameStep {
+    // the steps left in this frame are dropped, the state change is applied before the next one
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+}
+
+fn game_step(
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    mut step: ResMut<GameStep>,
+    mut actions: ResMut<ActionInput>) -> ShouldRun
+{
+    let in_game = state.current() == &GameState::Game;
+
+    // presses were seen by the step that just ran, menu presses do not reach the game
+    if step.stepping || !in_game {
+        actions.end_step();
+    }
+
+    // paused or out of the game, the game time stands still
+    if !in_game || step.stopped {
+        step.accumulator = 0.0;
+        step.stepping = false;
+        step.stopped = false;
+        return ShouldRun::No;
+    }
+
+    if !step.stepping {
+        step.accumulator = (step.accumulator + time.delta_seconds()).min(MAX_FRAME_TIME);
+    }
+
+    if step.accumulator >= TIME_STEP {
+        step.accumulator -= TIME_STEP;
+        step.stepping = true;
+        ShouldRun::YesAndCheckAgain
+    }
+    else {
+        step.stepping = false;
+        ShouldRun::No
+    }
+}
@@ -10,11 +10,13 @@ use crate::player::AMMO_MAX;
 use crate::player::FUEL_MAX;
 use crate::player::HEALTH_MAX;
 use crate::player::Player;
+use crate::player::PlayerImages;
 use crate::special::BALL_H2;
 use crate::special::BALL_SIZE;
 use crate::special::BALL_W2;
 use crate::special::SpecialImages;
 use crate::special::SpecialType;
+use crate::random::GameRng;
 
 pub struct InfoBarPlugin;
 
@@ -47,6 +49,12 @@ pub struct ScoreText;
 #[derive(Component)]
 pub struct BasesCountText;
 
+#[derive(Component)]
+pub struct LivesInfo;
+
+#[derive(Component)]
+pub struct LivesText;
+
 #[derive(Component, Deref, DerefMut)]
 pub struct InfoBarRectsTimer(pub Timer);
 
@@ -54,6 +62,7 @@ pub struct SpecialChange(pub SpecialType);
 pub struct SpecialAmmoChange(pub u8);
 pub struct ScoreChange(pub u16);
 pub struct BaseCountChange(pub u8);
+pub struct LivesChange(pub u8);
 
 pub const BAR_SIZE: f32 = 22.0;
 pub const BAR_SIZE2: f32 = BAR_SIZE / 2.0;
@@ -86,6 +95,13 @@ pub const SCORE_TEXT_Y: f32 = 25.0;
 pub const BASES_COUNT_TEXT_X: f32 = 730.0;
 pub const BASES_COUNT_TEXT_Y: f32 = 50.0;
 
+pub const LIVES_ICON_X: f32 = 600.0;
+pub const LIVES_ICON_Y: f32 = 50.0;
+pub const LIVES_ICON_SIZE: Vec2 = Vec2::new(44.0, 21.0);
+
+pub const LIVES_TEXT_X: f32 = 650.0;
+pub const LIVES_TEXT_Y: f32 = 50.0;
+
 impl Plugin for InfoBarPlugin {
     fn build(&self, app: &mut App) {
         app
@@ -93,6 +109,7 @@ impl Plugin for InfoBarPlugin {
         .add_event::<SpecialAmmoChange>()
         .add_event::<ScoreChange>()
         .add_event::<BaseCountChange>()
+        .add_event::<LivesChange>()
         .add_system_set(SystemSet::on_enter(GameState::Game)
             .with_system(spawn_info_bar))
         .add_system_set(SystemSet::on_update(GameState::Game)
@@ -101,6 +118,7 @@ impl Plugin for InfoBarPlugin {
             .with_system(special_ammo_changed)
             .with_system(score_changed)
             .with_system(base_count_changed)
+            .with_system(lives_changed)
             .with_system(change_rects_color))
         .add_system_set(SystemSet::on_exit(GameState::Game)
             .with_system(despawn_info_bar));
@@ -127,7 +145,15 @@ fn spawn_rects(commands: &mut Commands, start_x: f32, y: f32, amount: u8, assets
     }
 }
 
-fn spawn_info_bar(mut commands: Commands, assets: Res<AssetServer>, ui_assets: Res<UiAssets>, special_images: Res<SpecialImages>, maze: Res<Maze>) {
+fn spawn_info_bar(
+    mut commands: Commands, 
+    assets: Res<AssetServer>, 
+    ui_assets: Res<UiAssets>, 
+    special_images: Res<SpecialImages>, 
+    player_images: Res<PlayerImages>, 
+    maze: Res<Maze>, 
+    player: Res<Player>) 
+{
     // text style
     let text_style = TextStyle {
         font: ui_assets.font.clone(),
@@ -206,7 +232,7 @@ fn spawn_info_bar(mut commands: Commands, assets: Res<AssetServer>, ui_assets: R
 
     // special info 1
     commands.spawn_bundle(SpriteBundle {
-        texture: special_images.ball.clone(),
+        texture: special_image(&player.special_type, &special_images),
         sprite: Sprite { 
             color: Color::WHITE,
             custom_size: Some(BALL_SIZE),
@@ -223,7 +249,7 @@ fn spawn_info_bar(mut commands: Commands, assets: Res<AssetServer>, ui_assets: R
 
     // special info 2
     commands.spawn_bundle(SpriteBundle {
-        texture: special_images.ball.clone(),
+        texture: special_image(&player.special_type, &special_images),
         sprite: Sprite { 
             color: Color::WHITE,
             custom_size: Some(BALL_SIZE),
@@ -240,7 +266,7 @@ fn spawn_info_bar(mut commands: Commands, assets: Res<AssetServer>, ui_assets: R
 
     // special info text
     commands.spawn_bundle(Text2dBundle {
-        text: Text::from_section("04", text_style.clone()),
+        text: Text::from_section(format!("{:0>2}", player.ammo_special), text_style.clone()),
         transform: Transform { 
             translation: Vec3::new(SPECIAL_INFO_TEXT_X, WINDOW_H - SPECIAL_INFO_TEXT_Y, 300.0), 
             ..Default::default()
@@ -251,7 +277,7 @@ fn spawn_info_bar(mut commands: Commands, assets: Res<AssetServer>, ui_assets: R
 
     // score info text
     commands.spawn_bundle(Text2dBundle {
-        text: Text::from_section("0000000", text_style.clone()),
+        text: Text::from_section(format!("{:0>7}", maze.score), text_style.clone()),
         transform: Transform { 
             translation: Vec3::new(SCORE_TEXT_X, WINDOW_H - SCORE_TEXT_Y, 300.0), 
             ..Default::default()
@@ -272,6 +298,35 @@ fn spawn_info_bar(mut commands: Commands, assets: Res<AssetServer>, ui_assets: R
     })
     .insert(BasesCountText);
 
+    // lives icon
+    commands.spawn_bundle(SpriteBundle {
+        texture: player_images.ship.clone(),
+        sprite: Sprite { 
+            color: Color::WHITE,
+            custom_size: Some(LIVES_ICON_SIZE),
+            flip_x: false,
+            ..Default::default()
+        },
+        transform: Transform {
+            translation: Vec3::new(LIVES_ICON_X + LIVES_ICON_SIZE.x / 2.0 + BAR_GAP, WINDOW_H - LIVES_ICON_Y - BAR_SIZE2, 300.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .insert(LivesInfo);
+
+    // lives info text
+    commands.spawn_bundle(Text2dBundle {
+        text: Text::from_section(format!("x{}", player.lives), text_style.clone()),
+        transform: Transform { 
+            translation: Vec3::new(LIVES_TEXT_X, WINDOW_H - LIVES_TEXT_Y, 300.0), 
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .insert(LivesInfo)
+    .insert(LivesText);
+
     // rects - row 1
     spawn_rects(&mut commands, 0.0, 0.0, 32, &assets);
     // rects - row 2
@@ -285,7 +340,8 @@ fn spawn_info_bar(mut commands: Commands, assets: Res<AssetServer>, ui_assets: R
     spawn_rects(&mut commands, 0.0, 50.0, 1, &assets);
     spawn_rects(&mut commands, 250.0, 50.0, 1, &assets);
     spawn_rects(&mut commands, 325.0, 50.0, 1, &assets);
-    spawn_rects(&mut commands, 500.0, 50.0, 9, &assets);
+    spawn_rects(&mut commands, 500.0, 50.0, 4, &assets);
+    spawn_rects(&mut commands, 700.0, 50.0, 1, &assets);
     spawn_rects(&mut commands, 775.0, 50.0, 1, &assets);
     // rects - row 4
     spawn_rects(&mut commands, 0.0, 75.0, 32, &assets);
@@ -301,6 +357,7 @@ fn despawn_info_bar(
     special_text_query: Query<Entity, With<SpecialInfoText>>,
     score_text_query: Query<Entity, With<ScoreText>>,
     base_count_text_query: Query<Entity, With<BasesCountText>>,
+    lives_info_query: Query<Entity, With<LivesInfo>>,
     info_bar_rect_query: Query<Entity, With<InfoBarRect>>,
 ) 
 {
@@ -328,6 +385,9 @@ fn despawn_info_bar(
     for entity in base_count_text_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    for entity in lives_info_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
     for entity in info_bar_rect_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
@@ -356,6 +416,15 @@ fn update_bars(
     ammo_bar_transform.translation.x = AMMO_BAR_X_POS + w / 2.0;
 }
 
+fn special_image(special_type: &SpecialType, special_images: &Res<SpecialImages>) -> Handle<Image> {
+    match special_type {
+        SpecialType::Ball => special_images.ball.clone(),
+        SpecialType::MissileDown => special_images.missile_down.clone(),
+        SpecialType::MissileSide => special_images.missile_side.clone(),
+        SpecialType::Star => special_images.star.clone(),
+    }
+}
+
 pub fn special_changed(
     mut change_event: EventReader<SpecialChange>,
     mut sprite_query: Query<&mut Handle<Image>, With<SpecialInfoRect>>,
@@ -364,12 +433,7 @@ pub fn special_changed(
 {
     for change in change_event.iter() {
         for mut texture in sprite_query.iter_mut() {
-            match change.0 {
-                SpecialType::Ball => *texture = special_images.ball.clone(),
-                SpecialType::MissileDown => *texture = special_images.missile_down.clone(),
-                SpecialType::MissileSide => *texture = special_images.missile_side.clone(),
-                SpecialType::Star => *texture = special_images.star.clone(),
-            }
+            *texture = special_image(&change.0, &special_images);
         }
 
         for (mut sprite, mut rect) in rects_query.iter_mut() {
@@ -403,16 +467,25 @@ pub fn base_count_changed(mut change_event: EventReader<BaseCountChange>, mut te
     }
 }
 
+pub fn lives_changed(mut change_event: EventReader<LivesChange>, mut text_query: Query<&mut Text, With<LivesText>>) {
+    for change in change_event.iter() {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = format!("x{}", change.0);
+        }
+    }
+}
+
 pub fn change_rects_color(
     time: Res<Time>,
     mut timer_query: Query<&mut InfoBarRectsTimer>,
-    mut rects_query: Query<(&mut Sprite, &mut InfoBarRect)>)
+    mut rects_query: Query<(&mut Sprite, &mut InfoBarRect)>,
+    mut rng: ResMut<GameRng>)
 {
+    let rng = &mut rng.effects;
+
     for mut timer in &mut timer_query {
         timer.tick(time.delta());
         if timer.just_finished() {
-            let mut rng = rand::thread_rng();
-
             for (mut sprite, mut rect) in rects_query.iter_mut() {
                 if rng.gen_bool(1.0 / 10.0) {
                     let color_idx = rng.gen_range(0..ENEMY_COLORS.len());
@@ -1,19 +1,22 @@
 use bevy::prelude::*;
 use bevy_kira_audio::{AudioControl, AudioChannel};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::GameState;
+use crate::gamestep::GAME_STEP;
 use crate::maze::Maze;
 use crate::player::{Player, PLAYER_W, PLAYER_H, AMMO_MAX, FUEL_MAX, HEALTH_MAX, PlayerComponent};
 use crate::collision_check;
 use crate::audio::{SfxChannel, Sounds};
 use crate::special::SpecialType;
-use crate::infobar::{SpecialChange, SpecialAmmoChange};
+use crate::infobar::{SpecialChange, SpecialAmmoChange, LivesChange};
+use crate::loading::LoadingAssets;
+use crate::random::GameRng;
 
 pub struct ItemsPlugin;
 
-pub const ITEM_W: f32 = 49.0;
-pub const ITEM_H: f32 = 43.0;
+pub use crate::mazefile::{ITEM_W, ITEM_H};
 pub const ITEM_W2: f32 = ITEM_W / 2.0;
 pub const ITEM_H2: f32 = ITEM_H / 2.0;
 
@@ -22,11 +25,13 @@ impl Plugin for ItemsPlugin {
         app
         .add_startup_system_to_stage(StartupStage::PreStartup, load_items_resources)
         .add_system_set(SystemSet::on_update(GameState::Game)
-            .with_system(change_items_color)
-            .with_system(item_vs_player));
+            .with_system(change_items_color))
+        .add_system_set_to_stage(GAME_STEP, SystemSet::new()
+            .with_system(item_vs_player.label("ItemsPickupSystem").after("PlayerMovementSystem")));
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Item {
     pub posx: f32,
     pub posy: f32,
@@ -36,6 +41,7 @@ pub struct Item {
     pub room_seq: usize,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct FellowItem {
     pub posx: f32,
     pub posy: f32,
@@ -59,18 +65,20 @@ pub struct ItemsImages {
     pub item_random: Handle<Image>,
     pub item_shield: Handle<Image>,
     pub item_star: Handle<Image>,
+    pub item_life: Handle<Image>,
 }
 
-pub fn load_items_resources(mut commands: Commands, assets: Res<AssetServer>) {
+pub fn load_items_resources(mut commands: Commands, assets: Res<AssetServer>, mut loading: ResMut<LoadingAssets>) {
     commands.insert_resource(ItemsImages {
-        item_ammo: assets.load("images/items/item_ammo.png"),
-        item_ball: assets.load("images/items/item_ball.png"),
-        item_fuel: assets.load("images/items/item_fuel.png"),
-        item_missile_down: assets.load("images/items/item_m_d.png"),
-        item_missile_side: assets.load("images/items/item_m_lr.png"),
-        item_random: assets.load("images/items/item_random.png"),
-        item_shield: assets.load("images/items/item_shield.png"),
-        item_star: assets.load("images/items/item_star.png"),
+        item_ammo: loading.track(assets.load("images/items/item_ammo.png")),
+        item_ball: loading.track(assets.load("images/items/item_ball.png")),
+        item_fuel: loading.track(assets.load("images/items/item_fuel.png")),
+        item_missile_down: loading.track(assets.load("images/items/item_m_d.png")),
+        item_missile_side: loading.track(assets.load("images/items/item_m_lr.png")),
+        item_random: loading.track(assets.load("images/items/item_random.png")),
+        item_shield: loading.track(assets.load("images/items/item_shield.png")),
+        item_star: loading.track(assets.load("images/items/item_star.png")),
+        item_life: loading.track(assets.load("images/items/item_life.png")),
     });
 }
 
@@ -117,13 +125,16 @@ pub fn item_vs_player(
     sfx: Res<AudioChannel<SfxChannel>>, 
     sounds: Res<Sounds>,
     mut change_special_event: EventWriter<SpecialChange>,
-    mut change_special_ammo_event: EventWriter<SpecialAmmoChange>) 
+    mut change_special_ammo_event: EventWriter<SpecialAmmoChange>,
+    mut change_lives_event: EventWriter<LivesChange>,
+    mut rng: ResMut<GameRng>) 
 {
     let player_transform = player_query.single();
 
     for (item_entity, item_component, item_transform) in items_query.iter() 
     {
-        if item_component.room_seq > 127 {
+        // fellow item carried by enemy 20 is not in any room
+        if item_component.room_seq >= maze.rooms.len() {
             continue;
         }
         
@@ -156,8 +167,7 @@ pub fn item_vs_player(
                     player.ammo_special = 20;
                 }, 
                 7 => { 
-                    let mut rng = rand::thread_rng();
-                    match rng.gen_range(0..=6) {
+                    match rng.game.gen_range(0..=6) {
                         0 => player.ammo = AMMO_MAX,
                         1 => {
                             player.special_type = SpecialType::Ball;
@@ -180,6 +190,10 @@ pub fn item_vs_player(
                         _ => panic!("Unexpected item type for random pickup!")
                     }
                 }, 
+                8 => {
+                    player.add_life();
+                    change_lives_event.send(LivesChange(player.lives));
+                },
                 _ => panic!("Unexpected special type!")
             }
 
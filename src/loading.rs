@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use bevy::asset::{Asset, LoadState};
+
+use crate::{GameState, WINDOW_W2};
+
+const BAR_W: f32 = 400.0;
+const BAR_H: f32 = 12.0;
+const BAR_Y: f32 = 40.0;
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        // inserted here, the load_* startup systems need it already in PreStartup
+        .insert_resource(LoadingAssets { handles: Vec::new() })
+        .add_system_set(SystemSet::on_enter(GameState::Loading)
+            .with_system(spawn_loading_bar)
+        )
+        .add_system_set(SystemSet::on_update(GameState::Loading)
+            .with_system(check_loading)
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Loading)
+            .with_system(despawn_loading_bar)
+        );
+    }
+}
+
+// every asset the game needs before the menu is shown
+pub struct LoadingAssets {
+    handles: Vec<HandleUntyped>,
+}
+
+impl LoadingAssets {
+    pub fn track<T: Asset>(&mut self, handle: Handle<T>) -> Handle<T> {
+        self.handles.push(handle.clone_untyped());
+        handle
+    }
+}
+
+#[derive(Component)]
+pub struct LoadingBar;
+
+#[derive(Component)]
+pub struct LoadingBarFill;
+
+fn spawn_loading_bar(mut commands: Commands) {
+    commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: Color::DARK_GRAY,
+            custom_size: Some(Vec2::new(BAR_W + 4.0, BAR_H + 4.0)),
+            ..Default::default()
+        },
+        transform: Transform::from_xyz(WINDOW_W2, BAR_Y, 10.0),
+        ..Default::default()
+    })
+    .insert(LoadingBar);
+
+    commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: Color::WHITE,
+            custom_size: Some(Vec2::new(0.0, BAR_H)),
+            ..Default::default()
+        },
+        transform: Transform::from_xyz(WINDOW_W2 - BAR_W / 2.0, BAR_Y, 11.0),
+        ..Default::default()
+    })
+    .insert(LoadingBar)
+    .insert(LoadingBarFill);
+}
+
+fn check_loading(
+    assets: Res<AssetServer>,
+    loading: Res<LoadingAssets>,
+    mut fill_query: Query<(&mut Sprite, &mut Transform), With<LoadingBarFill>>,
+    mut state: ResMut<State<GameState>>)
+{
+    let mut done = 0;
+    for handle in loading.handles.iter() {
+        match assets.get_load_state(handle.id) {
+            LoadState::Loaded | LoadState::Failed => done += 1,
+            _ => {}
+        }
+    }
+
+    let progress = if loading.handles.is_empty() { 1.0 } else { done as f32 / loading.handles.len() as f32 };
+    for (mut sprite, mut transform) in fill_query.iter_mut() {
+        sprite.custom_size = Some(Vec2::new(BAR_W * progress, BAR_H));
+        transform.translation.x = WINDOW_W2 - BAR_W / 2.0 + BAR_W * progress / 2.0;
+    }
+
+    if done == loading.handles.len() {
+        // missing asset is not fatal, it is just not drawn / played
+        for handle in loading.handles.iter() {
+            if assets.get_load_state(handle.id) == LoadState::Failed {
+                match assets.get_handle_path(handle.id) {
+                    Some(path) => println!("Failed to load asset: {}", path.path().display()),
+                    None => println!("Failed to load asset: {:?}", handle.id),
+                }
+            }
+        }
+        println!("Assets loaded: {}", done);
+        state.set(GameState::Start).expect("LOADING: Failed to change state!");
+    }
+}
+
+fn despawn_loading_bar(mut commands: Commands, query: Query<Entity, With<LoadingBar>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -3,12 +3,13 @@
 use bevy::prelude::*;
 use bevy::window::PresentMode;
 use bevy::render::camera::WindowOrigin;
-use bevy::sprite::collide_aabb::collide;
+use serde::{Deserialize, Serialize};
 
 pub const BG_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
 
-pub const WINDOW_W: f32 = 800.0;
-pub const WINDOW_H: f32 = 600.0;
+// the play area is shared with the maze tools, see mazefile.rs
+pub const WINDOW_W: f32 = mazefile::PLAY_AREA_W;
+pub const WINDOW_H: f32 = mazefile::PLAY_AREA_H + INFO_BAR_H;
 pub const WINDOW_W2: f32 = WINDOW_W / 2.0;
 pub const WINDOW_H2: f32 = WINDOW_H / 2.0;
 
@@ -19,6 +20,9 @@ pub const GRAVITY: Vec2 = Vec2::new(0.0, -60.0);
 mod audio;
 use audio::GameAudioPlugin;
 
+mod loading;
+use loading::{LoadingPlugin, LoadingAssets};
+
 mod mainmenu;
 use mainmenu::MainMenuPlugin;
 
@@ -28,6 +32,12 @@ use player::PlayerPlugin;
 mod maze;
 use maze::MazePlugin;
 
+// shared with the tools in src/bin, not all of it is used by the game
+#[allow(dead_code)]
+mod mazefile;
+
+mod savegame;
+
 mod enemies;
 use enemies::EnemiesPlugin;
 
@@ -43,6 +53,9 @@ use items::ItemsPlugin;
 mod special;
 use special::SpecialPlugin;
 
+mod damage;
+use damage::DamagePlugin;
+
 mod pause;
 use pause::PausePlugin;
 
@@ -58,8 +71,30 @@ use victory::VictoryPlugin;
 mod redefinekeys;
 use redefinekeys::RedefineKeysPlugin;
 
+mod settings;
+use settings::{SettingsPlugin, load_settings};
+
+mod gamepad;
+use gamepad::GamepadPlugin;
+
+mod actions;
+use actions::{ActionsPlugin, ActionInput, GameAction};
+
+mod gamestep;
+use gamestep::GameStepPlugin;
+
+mod random;
+use random::RandomPlugin;
+
+mod mazeerror;
+use mazeerror::MazeErrorPlugin;
+
+mod editor;
+use editor::EditorPlugin;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum GameState {
+    Loading,
     Start,
     Menu,
     Game,
@@ -68,51 +103,94 @@ pub enum GameState {
     GameOver,
     Victory,
     RedefineKeys,
+    SaveSlots,
+    MazeError,
+    Editor,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Serialize, Deserialize)]
 pub enum GameDirection {
     Left,
     Right,
     None
 }
 
+// keyboard and gamepad bindings, stored in the settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(from = "settings::GameKeysNames")]
 pub struct GameKeys {
+    #[serde(serialize_with = "settings::key_code::serialize")]
     pub up: KeyCode,
+    #[serde(serialize_with = "settings::key_code::serialize")]
     pub down: KeyCode,
+    #[serde(serialize_with = "settings::key_code::serialize")]
     pub left: KeyCode,
+    #[serde(serialize_with = "settings::key_code::serialize")]
     pub right: KeyCode,
+    #[serde(serialize_with = "settings::key_code::serialize")]
     pub fire: KeyCode,
+    #[serde(serialize_with = "settings::key_code::serialize")]
     pub pause: KeyCode,
+    #[serde(serialize_with = "settings::pad_button::serialize")]
+    pub pad_up: GamepadButtonType,
+    #[serde(serialize_with = "settings::pad_button::serialize")]
+    pub pad_down: GamepadButtonType,
+    #[serde(serialize_with = "settings::pad_button::serialize")]
+    pub pad_left: GamepadButtonType,
+    #[serde(serialize_with = "settings::pad_button::serialize")]
+    pub pad_right: GamepadButtonType,
+    #[serde(serialize_with = "settings::pad_button::serialize")]
+    pub pad_fire: GamepadButtonType,
+    #[serde(serialize_with = "settings::pad_button::serialize")]
+    pub pad_pause: GamepadButtonType,
+}
+
+impl Default for GameKeys {
+    fn default() -> Self {
+        GameKeys {
+            up: KeyCode::Up,
+            down: KeyCode::Down,
+            left: KeyCode::Left,
+            right: KeyCode::Right,
+            fire: KeyCode::A,
+            pause: KeyCode::P,
+            pad_up: GamepadButtonType::DPadUp,
+            pad_down: GamepadButtonType::East,
+            pad_left: GamepadButtonType::DPadLeft,
+            pad_right: GamepadButtonType::DPadRight,
+            pad_fire: GamepadButtonType::South,
+            pad_pause: GamepadButtonType::Start,
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct StartScreenImage;
 
 fn main() {
+    let settings = load_settings();
+
     let window = WindowDescriptor {
         width: WINDOW_W,
         height: WINDOW_H,
-        position: WindowPosition::At(Vec2::new(150.0,50.0)),
+        position: WindowPosition::At(Vec2::new(settings.window_x as f32, settings.window_y as f32)),
         title: String::from("RTH Jet-Story"),
-        present_mode: PresentMode::Immediate,
+        // the gameplay runs in fixed steps, the frame rate can follow the display
+        present_mode: PresentMode::Fifo,
         resizable: false,
         ..Default::default()
     };
 
     App::new()
-    .add_state(GameState::Start)
+    .add_state(GameState::Loading)
     .insert_resource(ClearColor(BG_COLOR))
     .insert_resource(window)
-    .insert_resource(GameKeys {
-        up: KeyCode::Up,
-        down: KeyCode::Down,
-        left: KeyCode::Left,
-        right: KeyCode::Right,
-        fire: KeyCode::A,
-        pause: KeyCode::P,
-    })
+    .insert_resource(settings.keys)
+    .insert_resource(settings)
     .add_plugins(DefaultPlugins)
+    .add_plugin(GameStepPlugin)
+    .add_plugin(RandomPlugin)
+    .add_plugin(LoadingPlugin)
     .add_plugin(GameAudioPlugin)
     .add_plugin(MainMenuPlugin)
     .add_plugin(PlayerPlugin)
@@ -122,16 +200,22 @@ fn main() {
     .add_plugin(ExplosionsPlugin)
     .add_plugin(ItemsPlugin)
     .add_plugin(SpecialPlugin)
+    .add_plugin(DamagePlugin)
     .add_plugin(PausePlugin)
     .add_plugin(DeathScreenPlugin)
     .add_plugin(GameOverPlugin)
     .add_plugin(VictoryPlugin)
     .add_plugin(RedefineKeysPlugin)
+    .add_plugin(SettingsPlugin)
+    .add_plugin(GamepadPlugin)
+    .add_plugin(ActionsPlugin)
+    .add_plugin(MazeErrorPlugin)
+    .add_plugin(EditorPlugin)
     .add_startup_system(spawn_camera)
     .add_startup_system(spawn_start_screen)
     .add_system_set(SystemSet::on_exit(GameState::Start).with_system(despawn_start_screen))
     // .add_startup_system(spawn_axes)
-    .add_system(main_keyboard_input)
+    .add_system(main_keyboard_input.before("MenuNavigationSystem"))
     .run();
 }
 
@@ -145,14 +229,14 @@ fn spawn_camera(mut commands: Commands) {
     }); 
 }
 
+// the rule lives in mazefile.rs, so that jetstory-analyse uses the same one
 pub fn collision_check(target_pos: Vec3, target_size: Vec2, tile_pos: Vec3, tile_size: Vec2) -> bool {
-    let collision = collide(
-        target_pos, 
-        target_size, 
-        tile_pos, 
-        tile_size
-    );
-    collision.is_some()
+    mazefile::boxes_overlap(
+        (target_pos.x, target_pos.y), 
+        (target_size.x, target_size.y), 
+        (tile_pos.x, tile_pos.y), 
+        (tile_size.x, tile_size.y)
+    )
 }
 
 pub const H_PADDING: Vec2 = Vec2::new(2.0, 0.0);
@@ -161,13 +245,14 @@ pub const V_PADDING: Vec2 = Vec2::new(0.0, 2.0);
 fn load_atlas(
     assets: &Res<AssetServer>, 
     texture_atlases: &mut ResMut<Assets<TextureAtlas>>,
+    loading: &mut ResMut<LoadingAssets>,
     file_name: &str,
     tile_size: Vec2,
     rows: usize,
     cols: usize,
     padding: Option<Vec2>,) -> Handle<TextureAtlas>
 {
-    let image: Handle<Image> = assets.load(file_name);
+    let image: Handle<Image> = loading.track(assets.load(file_name));
 
     match padding {
         Some(padding) => {
@@ -181,32 +266,38 @@ fn load_atlas(
     }
 }
 
-fn main_keyboard_input(mut keyboard: ResMut<Input<KeyCode>>, game_input: Res<GameKeys>, mut state: ResMut<State<GameState>>) {
-    if state.current() == &GameState::Start && keyboard.get_just_pressed().len() > 0 {
-        keyboard.clear();
+fn main_keyboard_input(
+    mut actions: ResMut<ActionInput>,
+    mut state: ResMut<State<GameState>>)
+{
+    if state.current() == &GameState::Start && actions.any_just_pressed() {
+        actions.clear();
         state.set(GameState::Menu).expect("main: Failed to change state!");
     }
 
-    if keyboard.just_pressed(game_input.pause) {
+    if actions.just_pressed(GameAction::Pause) {
+        // cleared, a menu can not act on the same press again
         if state.current() == &GameState::Game {
+            actions.clear();
             state.push(GameState::Pause).expect("main: Failed to push PAUSE state!");
         }
         else if state.current() == &GameState::Pause {
+            actions.clear();
             state.pop().expect("main: Failed to po PAUSE state!");
         }
     }
 
-    if keyboard.just_pressed(KeyCode::Escape) {
+    if actions.just_pressed(GameAction::Back) {
         if state.current() == &GameState::Game {
-            keyboard.clear();
+            actions.clear();
             state.set(GameState::Menu).expect("main: Failed to change state!");
         }
     }
 }
 
-fn spawn_start_screen(mut commands: Commands, assets: Res<AssetServer>) {
+fn spawn_start_screen(mut commands: Commands, assets: Res<AssetServer>, mut loading: ResMut<LoadingAssets>) {
     commands.spawn_bundle(SpriteBundle {
-        texture: assets.load("images/jet-story_loadscr.png"),
+        texture: loading.track(assets.load("images/jet-story_loadscr.png")),
         sprite: Sprite {
             custom_size: Some(Vec2::new(WINDOW_W, WINDOW_H)),
             flip_x: false,
@@ -1,6 +1,12 @@
 use bevy::{prelude::*, ui::FocusPolicy, app::AppExit};
 
-use crate::{GameState, maze::Maze, player::Player};
+use crate::{GameState, GameKeys, maze::Maze, player::Player};
+use crate::enemies::EnemyDefs;
+use crate::loading::LoadingAssets;
+use crate::settings::{Settings, reset_settings};
+use crate::actions::{ActionInput, GameAction};
+use crate::savegame::{Checkpoint, SAVE_SLOTS, SaveInfo, slot_file, save_exists, any_save_exists, read_save_info, save_game, load_game,
+    delete_save, format_playtime, format_timestamp};
 
 pub struct MainMenuPlugin;
 
@@ -25,23 +31,72 @@ impl Plugin for MainMenuPlugin {
             .with_system(spawn_main_menu)
         )
         .add_system_set(SystemSet::on_update(GameState::Menu)
-            .with_system(handle_menu_buttons)
+            .with_system(menu_navigation.label("MenuNavigationSystem"))
+            .with_system(handle_menu_buttons.after("MenuNavigationSystem"))
             .with_system(keyboard_input)
+            .with_system(rebuild_main_menu)
+        )
+        .insert_resource(MenuFocus(0))
+        .insert_resource(MainMenuScreen { confirm_exit: false, rebuild: false })
+        .insert_resource(SaveSlotsScreen { confirm: None, rebuild: false, message: String::new() })
+        .add_system_set(SystemSet::on_enter(GameState::SaveSlots)
+            .with_system(spawn_save_slots)
+        )
+        .add_system_set(SystemSet::on_update(GameState::SaveSlots)
+            .with_system(menu_navigation.label("MenuNavigationSystem"))
+            .with_system(handle_save_slots_buttons.after("MenuNavigationSystem"))
+            .with_system(save_slots_keyboard_input)
+            .with_system(rebuild_save_slots)
+        )
+        .add_system_set(SystemSet::on_exit(GameState::SaveSlots)
+            .with_system(despawn_main_menu)
         );
     }
 }
 
-pub fn load_menu_resources(mut commands: Commands, assets: Res<AssetServer>) {
+// buttons reachable with the keyboard and gamepad, in the order they are selected
+#[derive(Component)]
+pub struct MenuItem(pub usize);
+
+// button selected with the keyboard or gamepad, the mouse moves it too
+pub struct MenuFocus(pub usize);
+
+// Escape asks before quitting, the question replaces the menu buttons
+pub struct MainMenuScreen {
+    confirm_exit: bool,
+    rebuild: bool,
+}
+
+// overwrite and delete need a second click, the button waiting for it is stored here
+pub struct SaveSlotsScreen {
+    confirm: Option<String>,
+    rebuild: bool,
+    // failed save or delete, shown until the next click
+    message: String,
+}
+
+pub fn load_menu_resources(mut commands: Commands, assets: Res<AssetServer>, mut loading: ResMut<LoadingAssets>) {
     let ui_assets = UiAssets {
-        font: assets.load("esp.ttf"),
+        font: loading.track(assets.load("esp.ttf")),
     };
     commands.insert_resource(ui_assets);
 }
 
 pub fn spawn_button(commands: &mut Commands, ui_assets: &Res<UiAssets>, name: &str, text: &str) -> Entity {
+    spawn_sized_button(commands, ui_assets, name, text, Size::new(Val::Percent(50.0), Val::Percent(14.0)), 30.0)
+}
+
+pub fn spawn_sized_button(
+    commands: &mut Commands,
+    ui_assets: &Res<UiAssets>,
+    name: &str,
+    text: &str,
+    size: Size<Val>,
+    font_size: f32) -> Entity
+{
     commands.spawn_bundle(ButtonBundle {
         style: Style {
-            size: Size::new(Val::Percent(50.0), Val::Percent(20.0)),
+            size,
             margin: UiRect::all(Val::Auto),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
@@ -57,7 +112,7 @@ pub fn spawn_button(commands: &mut Commands, ui_assets: &Res<UiAssets>, name: &s
                 text, 
                 TextStyle { 
                     font: ui_assets.font.clone(), 
-                    font_size: 30.0, 
+                    font_size, 
                     color: Color::rgb(0.9, 0.9, 0.9) 
                 }
             ).with_alignment(TextAlignment::CENTER),
@@ -68,10 +123,10 @@ pub fn spawn_button(commands: &mut Commands, ui_assets: &Res<UiAssets>, name: &s
     .id()
 }
 
-pub fn spawn_main_menu(mut commands: Commands, ui_assets: Res<UiAssets>, maze: Res<Maze>) {
+fn spawn_main_menu_screen(commands: &mut Commands, ui_assets: &Res<UiAssets>, maze: &Maze, screen: &MainMenuScreen) {
     let panel = commands.spawn_bundle(NodeBundle{
         style: Style { 
-            size: Size::new(Val::Percent(100.0), Val::Percent(50.0)),
+            size: Size::new(Val::Percent(100.0), Val::Percent(70.0)),
             flex_direction: FlexDirection::ColumnReverse,
             align_content: AlignContent::Center,
             justify_content: JustifyContent::Center,
@@ -84,14 +139,77 @@ pub fn spawn_main_menu(mut commands: Commands, ui_assets: Res<UiAssets>, maze: R
 
     let mut items = Vec::new();
 
-    if maze.loaded {
-        items.push(spawn_button(&mut commands, &ui_assets, "ResumeButton", "Resume"));
+    if screen.confirm_exit {
+        items.push(spawn_button(commands, ui_assets, "ExitYesButton", "Yes, quit"));
+        items.push(spawn_button(commands, ui_assets, "ExitNoButton", "No"));
+    }
+    else {
+        if maze.loaded {
+            items.push(spawn_button(commands, ui_assets, "ResumeButton", "Resume"));
+        }
+        items.push(spawn_button(commands, ui_assets, "StartButton", "New Game"));
+        if maze.loaded || any_save_exists() {
+            items.push(spawn_button(commands, ui_assets, "SaveSlotsButton", "Saved Games"));
+        }
+        items.push(spawn_button(commands, ui_assets, "RedefineButton", "Redefine Keys"));
+        items.push(spawn_button(commands, ui_assets, "ResetSettingsButton", "Reset Settings"));
+        items.push(spawn_button(commands, ui_assets, "EditorButton", "Editor"));
+        items.push(spawn_button(commands, ui_assets, "ExitButton", "Exit"));
     }
-    items.push(spawn_button(&mut commands, &ui_assets, "StartButton", "New Game"));
-    items.push(spawn_button(&mut commands, &ui_assets, "RedefineButton", "Redefine Keys"));
-    items.push(spawn_button(&mut commands, &ui_assets, "ExitButton", "Exit"));
 
+    for (index, item) in items.iter().enumerate() {
+        commands.entity(*item).insert(MenuItem(index));
+    }
     commands.entity(panel).push_children(&items);
+
+    if screen.confirm_exit {
+        let question = commands.spawn_bundle(TextBundle {
+            text: Text::from_section(
+                "Quit the game?", 
+                TextStyle { 
+                    font: ui_assets.font.clone(), 
+                    font_size: 40.0, 
+                    color: Color::WHITE 
+                }
+            ),
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            ..Default::default()
+        }).id();
+        commands.entity(panel).insert_children(0, &[question]);
+    }
+}
+
+pub fn spawn_main_menu(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    maze: Res<Maze>,
+    mut screen: ResMut<MainMenuScreen>,
+    mut focus: ResMut<MenuFocus>)
+{
+    focus.0 = 0;
+    screen.confirm_exit = false;
+    screen.rebuild = false;
+    spawn_main_menu_screen(&mut commands, &ui_assets, &maze, &screen);
+}
+
+// exit question shown or taken back
+fn rebuild_main_menu(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    maze: Res<Maze>,
+    mut screen: ResMut<MainMenuScreen>,
+    node_query: Query<Entity, With<Node>>)
+{
+    if screen.rebuild {
+        screen.rebuild = false;
+        for entity in node_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_main_menu_screen(&mut commands, &ui_assets, &maze, &screen);
+    }
 }
 
 fn despawn_main_menu(mut commands: Commands, button_query: Query<Entity, With<Node>>) {
@@ -102,24 +220,35 @@ fn despawn_main_menu(mut commands: Commands, button_query: Query<Entity, With<No
 
 fn handle_menu_buttons(
     // mut commands: Commands,
-    mut interaction_query: Query<(&Name, &Interaction, &mut UiColor), Changed<Interaction>>,
+    interaction_query: Query<(&Name, &Interaction), Changed<Interaction>>,
     mut state: ResMut<State<GameState>>,
     mut mouse: ResMut<Input<MouseButton>>,
     mut player: ResMut<Player>,
     mut maze: ResMut<Maze>,
+    mut checkpoint: ResMut<Checkpoint>,
+    mut settings: ResMut<Settings>,
+    mut game_keys: ResMut<GameKeys>,
+    mut windows: ResMut<Windows>,
+    mut screen: ResMut<MainMenuScreen>,
+    mut focus: ResMut<MenuFocus>,
     mut exit: EventWriter<AppExit>)
 {
-    for (name, interaction, mut color) in interaction_query.iter_mut() {
+    for (name, interaction) in interaction_query.iter() {
         match interaction {
             Interaction::Clicked => {
                 println!("Button clicked: {}", name.as_str());
                 match name.as_str() {
                     "StartButton" => {
-                        player.clear();
+                        player.clear(settings.lives);
                         maze.clear();
+                        checkpoint.clear();
                         mouse.clear();
                         state.set(GameState::Game).expect("MAIN MENU: Failed to change state!");
                     },
+                    "SaveSlotsButton" => {
+                        mouse.clear();
+                        state.push(GameState::SaveSlots).expect("MAIN MENU: Failed to push state!");
+                    },
                     "ResumeButton" => {
                         mouse.clear();
                         if maze.loaded {
@@ -131,28 +260,332 @@ fn handle_menu_buttons(
                         // state.set(GameState::RedefineKeys).expect("MAIN MENU: Failed to change state!");
                         state.push(GameState::RedefineKeys).expect("MAIN MENU: Failed to push state!");
                     },
-                    "ExitButton" => {
+                    "ResetSettingsButton" => {
+                        mouse.clear();
+                        reset_settings(&mut settings, &mut game_keys, &mut windows);
+                    },
+                    "EditorButton" => {
+                        mouse.clear();
+                        state.set(GameState::Editor).expect("MAIN MENU: Failed to change state!");
+                    },
+                    "ExitButton" | "ExitYesButton" => {
                         exit.send(AppExit); //exit
                     },
+                    "ExitNoButton" => {
+                        mouse.clear();
+                        screen.confirm_exit = false;
+                        screen.rebuild = true;
+                        focus.0 = 0;
+                    },
                     _ => panic!("MAIN MENU: Unexpected button!")
                 }
             },
-            Interaction::Hovered => { 
-                *color = UiColor(Color::GRAY);
-            },
-            Interaction::None => {
-                *color = Color::DARK_GRAY.into();
+            // colors follow the selection, see menu_navigation
+            Interaction::Hovered | Interaction::None => {}
+        }
+    }
+}
+
+// up / down move the selection, Confirm or fire clicks the selected button for the button handlers
+pub fn menu_navigation(
+    actions: Res<ActionInput>,
+    mut focus: ResMut<MenuFocus>,
+    mut item_query: Query<(&MenuItem, &mut Interaction, &mut UiColor)>)
+{
+    let count = item_query.iter().count();
+    if count == 0 {
+        return;
+    }
+
+    // the mouse takes the selection only when it moves onto a button, it may rest on another one
+    for (item, interaction, _) in item_query.iter_mut() {
+        if interaction.is_changed() && *interaction == Interaction::Hovered {
+            focus.0 = item.0;
+        }
+    }
+    // the screen may have been spawned again with fewer buttons
+    focus.0 = focus.0.min(count - 1);
+    if actions.just_pressed(GameAction::MenuUp) {
+        focus.0 = (focus.0 + count - 1) % count;
+    }
+    if actions.just_pressed(GameAction::MenuDown) {
+        focus.0 = (focus.0 + 1) % count;
+    }
+
+    for (item, mut interaction, mut color) in item_query.iter_mut() {
+        if item.0 == focus.0 {
+            if actions.just_pressed(GameAction::Confirm) || actions.just_pressed(GameAction::FireCannon) {
+                *interaction = Interaction::Clicked;
             }
+            *color = UiColor(Color::GRAY);
+        }
+        else if *interaction != Interaction::Hovered {
+            *color = Color::DARK_GRAY.into();
         }
     }
 }
 
 fn keyboard_input(
-    keyboard: Res<Input<KeyCode>>, 
-    state: ResMut<State<GameState>>, 
-    mut exit: EventWriter<AppExit>) 
+    mut actions: ResMut<ActionInput>, 
+    mut screen: ResMut<MainMenuScreen>,
+    mut focus: ResMut<MenuFocus>) 
 {
-    if keyboard.just_pressed(KeyCode::Escape) && state.current() == &GameState::Menu {
-        exit.send(AppExit); //exit
+    // Escape asks, Escape again takes the question back
+    if actions.just_pressed(GameAction::Back) {
+        actions.clear();
+        screen.confirm_exit = !screen.confirm_exit;
+        screen.rebuild = true;
+        // "No" is selected, quitting needs a deliberate choice
+        focus.0 = if screen.confirm_exit { 1 } else { 0 };
+    }
+}
+
+fn slot_text(slot: usize, info: &Option<Result<SaveInfo, String>>) -> String {
+    match info {
+        None => format!("Slot {}  empty", slot),
+        Some(Ok(info)) => format!("Slot {}  room {},{}  score {:0>7}  bases {}/{}\nplaytime {}  saved {}",
+            slot, info.room.0, info.room.1, info.score, info.bases, info.bases_total,
+            format_playtime(info.playtime), format_timestamp(info.saved_at)),
+        Some(Err(error)) => format!("Slot {}  unreadable: {}", slot, error),
+    }
+}
+
+fn spawn_save_slots_screen(commands: &mut Commands, ui_assets: &Res<UiAssets>, maze: &Maze, screen: &SaveSlotsScreen) {
+    let panel = commands.spawn_bundle(NodeBundle{
+        style: Style { 
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_content: AlignContent::Center,
+            justify_content: JustifyContent::Center,
+            align_self: AlignSelf::Center,
+            ..Default::default()
+        },
+        color: UiColor(Color::NONE),
+        ..Default::default()
+    }).id();
+
+    let mut items = Vec::new();
+
+    items.push(commands.spawn_bundle(TextBundle {
+        text: Text::from_section(
+            "Saved Games", 
+            TextStyle { 
+                font: ui_assets.font.clone(), 
+                font_size: 40.0, 
+                color: Color::WHITE 
+            }
+        ),
+        style: Style {
+            margin: UiRect::all(Val::Auto),
+            ..Default::default()
+        },
+        ..Default::default()
+    }).id());
+
+    if !screen.message.is_empty() {
+        items.push(commands.spawn_bundle(TextBundle {
+            text: Text::from_section(
+                screen.message.as_str(), 
+                TextStyle { 
+                    font: ui_assets.font.clone(), 
+                    font_size: 18.0, 
+                    color: Color::RED 
+                }
+            ),
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            ..Default::default()
+        }).id());
+    }
+
+    let mut buttons = Vec::new();
+    let button_size = Size::new(Val::Px(110.0), Val::Px(40.0));
+    let is_confirm = |name: &str| screen.confirm.as_deref() == Some(name);
+
+    for slot in 1..=SAVE_SLOTS {
+        let file = slot_file(slot);
+        let info = if save_exists(&file) { Some(read_save_info(&file).map_err(|e| e.to_string())) } else { None };
+
+        let row = commands.spawn_bundle(NodeBundle{
+            style: Style { 
+                size: Size::new(Val::Percent(96.0), Val::Px(70.0)),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            color: UiColor(Color::rgb(0.1, 0.1, 0.1)),
+            ..Default::default()
+        }).id();
+
+        let mut row_items = Vec::new();
+        row_items.push(commands.spawn_bundle(TextBundle {
+            text: Text::from_section(
+                slot_text(slot, &info), 
+                TextStyle { 
+                    font: ui_assets.font.clone(), 
+                    font_size: 18.0, 
+                    color: Color::rgb(0.9, 0.9, 0.9) 
+                }
+            ),
+            style: Style {
+                flex_grow: 1.0,
+                margin: UiRect::new(Val::Px(10.0), Val::Px(10.0), Val::Auto, Val::Auto),
+                ..Default::default()
+            },
+            focus_policy: FocusPolicy::Pass,
+            ..Default::default()
+        }).id());
+
+        let first_button = buttons.len();
+        if maze.loaded {
+            let name = format!("SlotSave{}", slot);
+            let text = if is_confirm(&name) { "Confirm?" } else if info.is_some() { "Overwrite" } else { "Save" };
+            buttons.push(spawn_sized_button(commands, ui_assets, &name, text, button_size, 20.0));
+        }
+        if let Some(Ok(_)) = info {
+            buttons.push(spawn_sized_button(commands, ui_assets, &format!("SlotLoad{}", slot), "Load", button_size, 20.0));
+        }
+        if info.is_some() {
+            let name = format!("SlotDelete{}", slot);
+            let text = if is_confirm(&name) { "Confirm?" } else { "Delete" };
+            buttons.push(spawn_sized_button(commands, ui_assets, &name, text, button_size, 20.0));
+        }
+
+        row_items.extend_from_slice(&buttons[first_button..]);
+        commands.entity(row).push_children(&row_items);
+        items.push(row);
+    }
+
+    let back = spawn_sized_button(commands, ui_assets, "SlotsBack", "Back", Size::new(Val::Percent(30.0), Val::Px(50.0)), 30.0);
+    buttons.push(back);
+    items.push(back);
+
+    for (index, button) in buttons.iter().enumerate() {
+        commands.entity(*button).insert(MenuItem(index));
+    }
+    commands.entity(panel).push_children(&items);
+}
+
+fn spawn_save_slots(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    maze: Res<Maze>,
+    mut screen: ResMut<SaveSlotsScreen>,
+    mut focus: ResMut<MenuFocus>)
+{
+    focus.0 = 0;
+    screen.confirm = None;
+    screen.rebuild = false;
+    screen.message.clear();
+    spawn_save_slots_screen(&mut commands, &ui_assets, &maze, &screen);
+}
+
+// slot contents or confirmation changed, the whole screen is spawned again
+fn rebuild_save_slots(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    maze: Res<Maze>,
+    mut screen: ResMut<SaveSlotsScreen>,
+    node_query: Query<Entity, With<Node>>)
+{
+    if screen.rebuild {
+        screen.rebuild = false;
+        for entity in node_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_save_slots_screen(&mut commands, &ui_assets, &maze, &screen);
+    }
+}
+
+fn handle_save_slots_buttons(
+    interaction_query: Query<(&Name, &Interaction), Changed<Interaction>>,
+    mut state: ResMut<State<GameState>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    mut player: ResMut<Player>,
+    mut maze: ResMut<Maze>,
+    mut checkpoint: ResMut<Checkpoint>,
+    enemy_defs: Res<EnemyDefs>,
+    mut screen: ResMut<SaveSlotsScreen>)
+{
+    for (name, interaction) in interaction_query.iter() {
+        match interaction {
+            Interaction::Clicked => {
+                println!("Button clicked: {}", name.as_str());
+                mouse.clear();
+                let name = name.as_str();
+                let confirmed = screen.confirm.as_deref() == Some(name);
+                screen.confirm = None;
+                screen.rebuild = true;
+                screen.message.clear();
+
+                if name == "SlotsBack" {
+                    screen.rebuild = false;
+                    state.pop().expect("SAVE SLOTS: Failed to pop state!");
+                }
+                else if let Some(slot) = name.strip_prefix("SlotSave") {
+                    let file = slot_file(slot.parse().expect("SAVE SLOTS: Unexpected slot!"));
+                    if save_exists(&file) && !confirmed {
+                        screen.confirm = Some(String::from(name));
+                    }
+                    else {
+                        match save_game(&file, &maze, &player) {
+                            Ok(()) => println!("Game saved: {}", file),
+                            Err(e) => {
+                                println!("Failed to save game: {}: {}", file, e);
+                                screen.message = format!("Failed to save game: {}: {}", file, e);
+                            }
+                        }
+                    }
+                }
+                else if let Some(slot) = name.strip_prefix("SlotDelete") {
+                    let file = slot_file(slot.parse().expect("SAVE SLOTS: Unexpected slot!"));
+                    if !confirmed {
+                        screen.confirm = Some(String::from(name));
+                    }
+                    else {
+                        match delete_save(&file) {
+                            Ok(()) => println!("Game deleted: {}", file),
+                            Err(e) => {
+                                println!("Failed to delete game: {}: {}", file, e);
+                                screen.message = format!("Failed to delete game: {}: {}", file, e);
+                            }
+                        }
+                    }
+                }
+                else if let Some(slot) = name.strip_prefix("SlotLoad") {
+                    let file = slot_file(slot.parse().expect("SAVE SLOTS: Unexpected slot!"));
+                    screen.rebuild = false;
+                    // replace, the menu below on the stack is left too
+                    match load_game(&file, &mut maze, &mut player, &enemy_defs) {
+                        Ok(()) => {
+                            checkpoint.clear();
+                            state.replace(GameState::Game).expect("SAVE SLOTS: Failed to change state!");
+                        },
+                        Err(error) => {
+                            println!("Failed to load game: {}", error);
+                            maze.load_error = Some(error);
+                            state.replace(GameState::MazeError).expect("SAVE SLOTS: Failed to change state!");
+                        }
+                    }
+                }
+                else {
+                    panic!("SAVE SLOTS: Unexpected button!");
+                }
+                // one click per frame, the buttons are spawned again
+                break;
+            },
+            // colors follow the selection, see menu_navigation
+            Interaction::Hovered | Interaction::None => {}
+        }
+    }
+}
+
+fn save_slots_keyboard_input(mut actions: ResMut<ActionInput>, mut state: ResMut<State<GameState>>) {
+    if actions.just_pressed(GameAction::Back) {
+        actions.clear();
+        state.pop().expect("SAVE SLOTS: Failed to pop state!");
     }
 }
@@ -1,28 +1,26 @@
 use bevy::prelude::*;
-use std::fs::File;
-use std::io::{prelude::*, BufReader};
 use std::usize;
 use rand::Rng;
+use rand::rngs::StdRng;
 use bevy_kira_audio::{AudioControl, AudioChannel};
+use serde::{Deserialize, Serialize};
 
 use crate::audio::{Sounds, DamageChannel, Shooting01Channel, Shooting05Channel, Shooting06Channel, Shooting08Channel, Shooting09Channel};
-use crate::enemies::{EnemiesImages, Enemy, EnemyComponent, EnemyShotComponent, ENEMY_COLORS, ENEMY_NN_SIZE, ENEMY_07_SIZE, ENEMY_18_SIZE, ENEMY_19_SIZE, 
-    EnemyType01, EnemyType02, EnemyType03, EnemyType05, EnemyType06, EnemyType07, EnemyType08, EnemyType09, EnemyType10,
-    EnemyType11, EnemyType12, EnemyType13, EnemyType14, EnemyType15, EnemyType16, EnemyType17, EnemyType18, EnemyType19, EnemyType20, FellowEnemy, 
-    EnemyType03Fellow, EnemyType10Fellow};
+use crate::enemies::{EnemyDefs, Enemy, EnemyComponent, EnemyShotComponent, ENEMY_COLORS, FellowEnemy, CarriedEnemy,
+    start_direction, start_velocity, insert_behaviours};
 use crate::explosions::{Fragment, Boom, FlashEffect};
+use crate::mazefile::{MAZE_FILE, TILESET_FILE, MazeLoadError, TilesetData, TileData, read_tileset, MazeGrid, EdgePolicy, RoomData, WallData, EnemyData, FellowData, ItemData, read_maze, CARRIER_TYPE,
+    wall_box, enemy_start_centre};
 use crate::items::{Item, ITEM_W, ITEM_H, ITEM_W2, ITEM_H2, ItemsImages, ItemComponent, ItemAnimationTimer, FellowItem};
 use crate::player::{Player, CannonShot, PlayerComponent};
 use crate::{GameState, WINDOW_H, INFO_BAR_H, GameDirection};
 use crate::special::{SpecialBall, SpecialStar};
+use crate::loading::LoadingAssets;
+use crate::savegame::Checkpoint;
+use crate::gamestep::GAME_STEP;
+use crate::random::GameRng;
 
-pub const MAZE_ROWS: usize = 8;
-pub const MAZE_COLS: usize = 16;
-
-pub const START_ROOM_INDEX: usize = 0;
-// pub const START_ROOM_INDEX: usize = (1 * MAZE_COLS) + 14; // carrier 5, 8, 9
-// pub const START_ROOM_INDEX: usize = (2 * MAZE_COLS) + 11; // carrier 2
-// pub const START_ROOM_INDEX: usize = (6 * MAZE_COLS) + 3; // fellow item test
+pub use crate::mazefile::START_ROOM;
 
 pub struct MazePlugin;
 
@@ -30,18 +28,24 @@ impl Plugin for MazePlugin {
     fn build(&self, app: &mut App) {
         app
         .add_event::<RoomChangeEvent>()
-        .add_startup_system_to_stage(StartupStage::PreStartup, load_wall_images)
+        .insert_resource(Checkpoint::new())
+        .insert_resource(MazeFile(maze_file_from_args()))
+        .add_startup_system_to_stage(StartupStage::PreStartup, load_tileset)
         .add_startup_system_to_stage(StartupStage::PreStartup, create_maze_resource)
         .add_system_set(SystemSet::on_exit(GameState::Menu)
             .with_system(load_maze).label("LoadMazeSystem"))
         .add_system_set(SystemSet::on_enter(GameState::Game)
+            .with_system(check_maze_error).after("LoadMazeSystem")
             .with_system(spawn_current_room).after("LoadMazeSystem"))
+        .add_system_set_to_stage(GAME_STEP, SystemSet::new()
+            .with_system(change_room).after("PlayerMovementSystem").after("PlayerStatusSystem")
+            .with_system(change_room_sounds).after("PlayerMovementSystem").after("PlayerStatusSystem")
+            .with_system(change_room_special_ball).after("PlayerMovementSystem").after("PlayerStatusSystem")
+            .with_system(change_room_special_star).after("PlayerMovementSystem").after("PlayerStatusSystem")
+        )
         .add_system_set(SystemSet::on_update(GameState::Game)
-            .with_system(change_room).after("PlayerMovementSystem")
-            .with_system(change_room_sounds).after("PlayerMovementSystem")
-            .with_system(change_room_special_ball).after("PlayerMovementSystem")
-            .with_system(change_room_special_star).after("PlayerMovementSystem")
             .with_system(check_bases_count)
+            .with_system(count_playtime)
         )
         .add_system_set(SystemSet::on_exit(GameState::Game)
             .with_system(despawn_room)
@@ -49,22 +53,48 @@ impl Plugin for MazePlugin {
     }
 }
 
-pub struct WallImages {
-    wall_images: Vec<Handle<Image>>,
+pub const MAZE_ARG: &str = "--maze";
+
+// maze the game plays and the editor edits, given on the command line: --maze <file>
+pub struct MazeFile(pub String);
+
+fn maze_file_from_args() -> String {
+    let mut args = std::env::args().skip_while(|arg| arg != MAZE_ARG).skip(1);
+    args.next().unwrap_or_else(|| String::from(MAZE_FILE))
+}
+
+pub struct Tileset {
+    pub data: TilesetData,
+    // same order as data.tiles
+    pub images: Vec<Handle<Image>>,
+    pub load_error: Option<MazeLoadError>,
 }
 
+impl Tileset {
+    pub fn tile(&self, id: usize) -> Option<(&TileData, &Handle<Image>)> {
+        let index = self.data.tiles.iter().position(|tile| tile.id == id)?;
+        Some((&self.data.tiles[index], &self.images[index]))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct Wall {
     id: usize,
     posx: f32,
     posy: f32
 }
 
+// every wall, also the ones without collision
+#[derive(Component)]
+pub struct WallSprite;
+
 #[derive(Component, Copy, Clone)]
 pub struct WallComponent {
     pub position: Vec3,
     pub size: Vec2,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Room {
     pub walls: Vec<Wall>,
     pub enemies: Vec<Enemy>,
@@ -82,13 +112,22 @@ impl Room {
     }
 }
 
+// saved as a whole by savegame.rs
+#[derive(Serialize, Deserialize)]
 pub struct Maze {
+    #[serde(skip)]
     pub loaded: bool,
+    #[serde(skip)]
+    pub load_error: Option<MazeLoadError>,
+    pub grid: MazeGrid,
     pub rooms: Vec<Room>,
     pub current_room: usize,
     pub score: u16,
     pub bases: u8,
     pub bases_total: u8,
+    // seconds spent in the game
+    #[serde(default)]
+    pub playtime: f32,
 }
 
 impl Maze {
@@ -98,9 +137,10 @@ impl Maze {
         }
         self.rooms.clear();
         self.loaded = false;
-        self.current_room = START_ROOM_INDEX;
+        self.current_room = 0;
         self.score = 0;
         self.bases = 0;
+        self.playtime = 0.0;
     }
 }
 
@@ -112,344 +152,146 @@ pub struct RoomChangeEvent {
 #[derive(Component, Deref, DerefMut)]
 pub struct AnimationTimer(pub Timer);
 
-fn load_wall_images(mut commands: Commands, assets: Res<AssetServer>) {
-    let mut wall_images = WallImages {
-        wall_images: Vec::new(),
+fn load_tileset(mut commands: Commands, assets: Res<AssetServer>, mut loading: ResMut<LoadingAssets>) {
+    let mut tileset = Tileset {
+        data: TilesetData { tiles: Vec::new() },
+        images: Vec::new(),
+        load_error: None,
     };
 
-    wall_images.wall_images.push(assets.load("images/walls/wall_00.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_01.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_02.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_03.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_04.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_05.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_06.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_07.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_08.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_09.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_10.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_11.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_12.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_13.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_14.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_15.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_16.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_17.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_18.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_19.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_20.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_21.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_22.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_23.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_24.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_25.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_26.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_27.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_28.png"));
-    wall_images.wall_images.push(assets.load("images/walls/wall_29.png"));
-
-    commands.insert_resource(wall_images);
+    match read_tileset(TILESET_FILE) {
+        Ok(data) => {
+            for tile in data.tiles.iter() {
+                tileset.images.push(loading.track(assets.load(tile.image.as_str())));
+            }
+            tileset.data = data;
+        },
+        Err(error) => {
+            println!("Tileset load error: {}", error);
+            tileset.load_error = Some(error);
+        }
+    }
+
+    commands.insert_resource(tileset);
 }
 
 fn create_maze_resource(mut commands: Commands) {
     let maze = Maze { 
         loaded: false, 
+        load_error: None,
+        grid: MazeGrid { rows: 0, cols: 0, edges: EdgePolicy::Solid, exit_room: (0, 0) },
         rooms: Vec::new(), 
-        current_room: START_ROOM_INDEX, 
+        current_room: 0, 
         score: 0, 
         bases: 0,
         bases_total:0,
+        playtime: 0.0,
     };
     commands.insert_resource(maze);
 }
 
-fn load_walls(file_name: &str, room: &mut Room) {
-    // println!("- Load walls: {}", file_name);
-
-    let file = File::open(file_name).expect("Failed to open file!");
-    let mut line = String::new();
-    let mut reader = BufReader::new(file);
-
-    // count
-    reader.read_line(&mut line).expect("Error reading file!");
-    let count = line.trim().parse::<i16>().unwrap();
-    line.clear();
-
-    // ;
-    reader.read_line(&mut line).expect("Error reading file!");
-    line.clear();
-
-    for _ in 0..count {
-        // type
-        reader.read_line(&mut line).expect("Error reading file!");
-        let id = line.trim().parse::<usize>().unwrap();
-        line.clear();
-
-        // x
-        reader.read_line(&mut line).expect("Error reading file!");
-        let posx = line.trim().parse::<f32>().unwrap();
-        line.clear();
-
-        // y
-        reader.read_line(&mut line).expect("Error reading file!");
-        let posy = line.trim().parse::<f32>().unwrap();
-        line.clear();
-
-        // ;
-        reader.read_line(&mut line).expect("Error reading file!");
-        line.clear();
-
-        // println!("Wall: {}, {}, {}", id, posx, posy);
-        room.walls.push(Wall { id, posx, posy });
-    }
-}
-
-fn get_enemy_health(enemy_type: usize) -> i16 {
-    match enemy_type {
-        0 | 4 => 200,
-        1 => 60,
-        2 | 5 | 6 | 7 | 9 | 10 => 90,
-        3 => 30,
-        8 => 20,
-        11 | 12 | 13 | 14 | 15 | 16 | 17 | 20 => 10,
-        18 | 19 => 50,
-        _ => panic!("Unexpected enemy id!")
+fn load_walls(walls: &Vec<WallData>, room: &mut Room) {
+    for wall in walls.iter() {
+        // println!("Wall: {}, {}, {}", wall.id, wall.x, wall.y);
+        room.walls.push(Wall { id: wall.id, posx: wall.x, posy: wall.y });
     }
 }
 
-fn get_enemy_shooting_cooldown(enemy_type: usize) -> u16 {
-    match enemy_type {
-        1 | 3 | 5 | 6 | 13 => 500,
-        2 => 2000,
-        7 => 1000,
-        10 => 10000,
-        _ => u16::MAX
-    }
+fn unknown_enemy_error(file_name: &str, room_data: &RoomData) -> MazeLoadError {
+    MazeLoadError::new(
+        file_name, 
+        0, 
+        format!("room {},{} enemy_type", room_data.row, room_data.col).as_str(), 
+        "an enemy type from the enemy definitions")
 }
 
-fn get_enemy_direction(enemy_type: usize, enemy_subtype: usize) -> GameDirection {
-    if enemy_type == 1 || enemy_type == 8 { 
-        if enemy_subtype == 0 {
-            return GameDirection::Left;
-        }
-        else {
-            return GameDirection::Right;
-        }
-    }
-    GameDirection::None
-}
-
-fn load_enemies(file_name: &str, room: &mut Room, room_seq: usize, base_count: &mut u8) {
-    // println!("- Load enemies: {}", file_name);
-
-    let mut rng = rand::thread_rng();
-
-    let file = File::open(file_name).expect("Failed to open file!");
-    let mut line = String::new();
-    let mut reader = BufReader::new(file);
-
-    // count
-    reader.read_line(&mut line).expect("Error reading file!");
-    let count = line.trim().parse::<i16>().unwrap();
-    line.clear();
-
-    // ;
-    reader.read_line(&mut line).expect("Error reading file!");
-    line.clear();
-
-    for enemy_seq in 0..count {
+fn load_enemies(file_name: &str, room_data: &RoomData, room: &mut Room, room_seq: usize, base_count: &mut u8, enemy_defs: &EnemyDefs, rng: &mut StdRng) -> Result<(), MazeLoadError> {
+    for (enemy_seq, enemy_data) in room_data.enemies.iter().enumerate() {
         let color_idx = rng.gen_range(0..ENEMY_COLORS.len());
 
-        // load enemy
-
-        // x
-        reader.read_line(&mut line).expect("Error reading file!");
-        let posx = line.trim().parse::<f32>().unwrap();
-        line.clear();
-
-        // y
-        reader.read_line(&mut line).expect("Error reading file!");
-        let posy = line.trim().parse::<f32>().unwrap();
-        line.clear();
-
-        // type
-        reader.read_line(&mut line).expect("Error reading file!");
-        let id = line.trim().parse::<usize>().unwrap();
-        line.clear();
-
-        // subtype
-        reader.read_line(&mut line).expect("Error reading file!");
-        let subid = line.trim().parse::<usize>().unwrap();
-        line.clear();
-
-        // ;
-        reader.read_line(&mut line).expect("Error reading file!");
-        line.clear();
+        let posx = enemy_data.x;
+        let posy = enemy_data.y;
+        let id = enemy_data.enemy_type;
+        let subid = enemy_data.subtype;
+        let def = enemy_defs.get(id).ok_or_else(|| unknown_enemy_error(file_name, room_data))?;
 
         if id == 0 {
             *base_count += 1;
         }
 
         // if enemy 20, load fellow
-        let fellow_enemy: Option<FellowEnemy>;
-        let fellow_item: Option<FellowItem>;
-        if id == 20 {
-            // fellow type
-            reader.read_line(&mut line).expect("Error reading file!");
-            let fellow_type = line.trim().parse::<u8>().unwrap();
-            line.clear();
-
-            // x
-            reader.read_line(&mut line).expect("Error reading file!");
-            let posx = line.trim().parse::<f32>().unwrap();
-            line.clear();
-
-            // y
-            reader.read_line(&mut line).expect("Error reading file!");
-            let posy = line.trim().parse::<f32>().unwrap();
-            line.clear();
-
-            // type
-            reader.read_line(&mut line).expect("Error reading file!");
-            let id = line.trim().parse::<usize>().unwrap();
-            line.clear();
-
-            // subtype
-            reader.read_line(&mut line).expect("Error reading file!");
-            let subid = line.trim().parse::<usize>().unwrap();
-            line.clear();
-
-            // ;
-            reader.read_line(&mut line).expect("Error reading file!");
-            line.clear();
-
-            // in case base is carried (original maze does not have any of these)
-            if id == 0 {
-                *base_count += 1;
-            }
-
-            if fellow_type == 0 {
-                // println!("Carrier ---> enemy {}, {}", id, subid);
-                // fellow enemy
-                fellow_enemy = Some(FellowEnemy {
-                    health: get_enemy_health(id),
-                    enemy_type: id,
-                    enemy_subtype: subid,
-                    first: true,
-                    posx,
-                    posy, 
-                    color: ENEMY_COLORS[color_idx],
-                    shooting_cooldown: 0,
-                    shooting_cooldown_max: get_enemy_shooting_cooldown(id),
-                    direction: get_enemy_direction(id, subid),
-                });
+        let mut fellow_enemy: Option<FellowEnemy> = None;
+        let mut fellow_item: Option<FellowItem> = None;
+        if id == CARRIER_TYPE {
+            match &enemy_data.fellow {
+                Some(FellowData::Enemy { x, y, enemy_type, subtype }) => {
+                    // in case base is carried (original maze does not have any of these)
+                    if *enemy_type == 0 {
+                        *base_count += 1;
+                    }
 
-                // no item
-                fellow_item = None;
-            }
-            else {
-                // fellow item
-                fellow_item = Some(FellowItem { 
-                    posx, posy, item_type: id, collected: false
-                });
-                // no enemy
-                fellow_enemy = None;
+                    // println!("Carrier ---> enemy {}, {}", enemy_type, subtype);
+                    let fellow_def = enemy_defs.get(*enemy_type).ok_or_else(|| unknown_enemy_error(file_name, room_data))?;
+                    fellow_enemy = Some(FellowEnemy {
+                        health: fellow_def.health,
+                        enemy_type: *enemy_type,
+                        enemy_subtype: *subtype,
+                        first: true,
+                        posx: *x,
+                        posy: *y, 
+                        color: ENEMY_COLORS[color_idx],
+                        shooting_cooldown: 0.0,
+                        shooting_cooldown_max: fellow_def.shooting_cooldown.unwrap_or(f32::MAX),
+                        direction: start_direction(fellow_def, *subtype),
+                    });
+                },
+                Some(FellowData::Item { x, y, item_type }) => {
+                    fellow_item = Some(FellowItem { 
+                        posx: *x, posy: *y, item_type: *item_type, collected: false
+                    });
+                },
+                None => {}
             }
         }
-        else {
-            // not 20
-            fellow_enemy = None;
-            fellow_item = None;
-        }
-
-        // enemy 15 and 16 have constant velocity 
-        // (other movable enemies calculates their velocity in corresponding enemy_NN_movement system)
-        let velocity: Vec2;
-        if id == 15 || id == 16 {
-            let mut x = 150.0;
-            let mut y = 150.0;
-            if rng.gen_bool(1.0/2.0) {
-                x *= -1.0;
-            }
-            if rng.gen_bool(1.0/2.0) {
-                y *= -1.0;
-            }
-            velocity = Vec2::new(x, y);
-        }
-        else {
-            velocity = Vec2::splat(0.0);
-        }
 
         room.enemies.push(Enemy { 
-            health: get_enemy_health(id),
+            health: def.health,
             room_seq, 
-            enemy_seq: enemy_seq as usize, 
+            enemy_seq, 
             enemy_type: id, 
             enemy_subtype: subid, 
             first: true,
             posx, 
             posy, 
             color: ENEMY_COLORS[color_idx],
-            velocity,
-            shooting_cooldown: 0,
-            shooting_cooldown_max: get_enemy_shooting_cooldown(id),
-            direction: get_enemy_direction(id, subid),
+            velocity: start_velocity(def, rng),
+            shooting_cooldown: 0.0,
+            shooting_cooldown_max: def.shooting_cooldown.unwrap_or(f32::MAX),
+            direction: start_direction(def, subid),
             is_from_10: false,
             fellow_enemy,
             fellow_item,
         });
     } // end for
-}
-
-fn load_items(file_name: &str, room: &mut Room, room_seq: usize) {
-    // println!("- Load items: {}", file_name);
-
-    let file = File::open(file_name).expect("Failed to open file!");
-    let mut line = String::new();
-    let mut reader = BufReader::new(file);
-
-    // count
-    reader.read_line(&mut line).expect("Error reading file!");
-    let count = line.trim().parse::<i16>().unwrap();
-    line.clear();
 
-    // ;
-    reader.read_line(&mut line).expect("Error reading file!");
-    line.clear();
-
-    for item_seq in 0..count {
-        // x
-        reader.read_line(&mut line).expect("Error reading file!");
-        let posx = line.trim().parse::<f32>().unwrap();
-        line.clear();
-
-        // y
-        reader.read_line(&mut line).expect("Error reading file!");
-        let posy = line.trim().parse::<f32>().unwrap();
-        line.clear();
-
-        // type
-        reader.read_line(&mut line).expect("Error reading file!");
-        let id = line.trim().parse::<usize>().unwrap();
-        line.clear();
-
-        // ;
-        reader.read_line(&mut line).expect("Error reading file!");
-        line.clear();
+    Ok(())
+}
 
+fn load_items(items: &Vec<ItemData>, room: &mut Room, room_seq: usize) {
+    for (item_seq, item) in items.iter().enumerate() {
         room.items.push(Item {
-            posx,
-            posy,
-            item_type: id,
+            posx: item.x,
+            posy: item.y,
+            item_type: item.item_type,
             collected: false,
             room_seq,
-            item_seq: item_seq as usize,
+            item_seq,
         });
     }
 }
 
-fn load_room(row: usize, col: usize, room_seq: usize, base_count: &mut u8) -> Room {
-    // println!("Load room: {},{}", row, col);
+pub fn load_room(file_name: &str, room_data: &RoomData, room_seq: usize, base_count: &mut u8, enemy_defs: &EnemyDefs, rng: &mut StdRng) -> Result<Room, MazeLoadError> {
+    // println!("Load room: {},{}", room_data.row, room_data.col);
 
     let mut room = Room { 
         walls: Vec::new(),
@@ -459,26 +301,65 @@ fn load_room(row: usize, col: usize, room_seq: usize, base_count: &mut u8) -> Ro
         items: Vec::new(),
     };
 
-    load_walls(format!("assets/data/rooms/room{}{}.txt", row, col).as_str(), &mut room);
-    load_enemies(format!("assets/data/enemies/enemy{}{}.txt", row, col).as_str(), &mut room, room_seq, base_count);
-    load_items(format!("assets/data/items/item{}{}.txt", row, col).as_str(), &mut room, room_seq);
+    load_walls(&room_data.walls, &mut room);
+    load_enemies(file_name, room_data, &mut room, room_seq, base_count, enemy_defs, rng)?;
+    load_items(&room_data.items, &mut room, room_seq);
 
-    room
+    Ok(room)
 }
 
-fn load_maze(mut maze: ResMut<Maze>) {
+fn load_rooms(maze: &mut Maze, file_name: &str, tileset: &Tileset, enemy_defs: &EnemyDefs, base_count: &mut u8, rng: &mut StdRng) -> Result<(), MazeLoadError> {
+    if let Some(error) = &tileset.load_error {
+        return Err(error.clone());
+    }
+    if let Some(error) = &enemy_defs.load_error {
+        return Err(error.clone());
+    }
+
+    let maze_data = read_maze(file_name)?;
+    tileset.data.check_maze(&maze_data, file_name)?;
+
+    maze.grid = maze_data.grid(file_name)?;
+    maze.current_room = maze.grid.room_index(START_ROOM.0, START_ROOM.1)
+        .ok_or_else(|| MazeLoadError::new(file_name, 0, "rows, cols", format!("start room {},{} inside the maze", START_ROOM.0, START_ROOM.1).as_str()))?;
+
+    let mut room_seq: usize = 0;
+
+    for row in 0..maze.grid.rows {
+        for col in 0..maze.grid.cols {
+            let room_data = maze_data.rooms.iter()
+                .find(|room| room.row == row && room.col == col)
+                .ok_or_else(|| MazeLoadError::new(file_name, 0, "rooms", format!("room {},{}", row, col).as_str()))?;
+            maze.rooms.push(load_room(file_name, room_data, room_seq, base_count, enemy_defs, rng)?);
+            room_seq += 1;
+        }
+    }
+
+    Ok(())
+}
+
+fn load_maze(
+    mut maze: ResMut<Maze>,
+    maze_file: Res<MazeFile>,
+    tileset: Res<Tileset>,
+    enemy_defs: Res<EnemyDefs>,
+    mut rng: ResMut<GameRng>)
+{
     if !maze.loaded {
-        println!("Load maze");
-        
-        let mut room_seq: usize = 0;
+        println!("Load maze: {}", maze_file.0);
+
+        // new game, the enemy colours and directions are the first draws of the seed
+        rng.new_game();
         let mut base_count: u8 = 0;
 
-        for row in 0..MAZE_ROWS {
-            for col in 0..MAZE_COLS {
-                maze.rooms.push(load_room(row, col, room_seq, &mut base_count));
-                room_seq += 1;
-            }
+        maze.load_error = None;
+        if let Err(error) = load_rooms(&mut maze, &maze_file.0, &tileset, &enemy_defs, &mut base_count, &mut rng.game) {
+            println!("Maze load error: {}", error);
+            maze.rooms.clear();
+            maze.load_error = Some(error);
+            return;
         }
+
         println!("Base count = {}", base_count);
         // maze.bases = 1; // for debug
         maze.bases = base_count;
@@ -488,6 +369,12 @@ fn load_maze(mut maze: ResMut<Maze>) {
     }
 }
 
+fn check_maze_error(maze: Res<Maze>, mut state: ResMut<State<GameState>>) {
+    if maze.load_error.is_some() {
+        state.set(GameState::MazeError).expect("MAZE: Failed to change state!");
+    }
+}
+
 pub fn spawn_enemy(
     commands: &mut Commands,
     enemy: &Enemy,
@@ -511,10 +398,28 @@ pub fn spawn_enemy(
         },
         ..Default::default()
     })
-    .insert(EnemyComponent { room_seq: enemy.room_seq, enemy_seq: enemy.enemy_seq, is_from_10: enemy.is_from_10 })
+    .insert(EnemyComponent { enemy_type: enemy.enemy_type, room_seq: enemy.room_seq, enemy_seq: enemy.enemy_seq, is_from_10: enemy.is_from_10 })
     .id()
 }
 
+// sprite, animation and behaviours of the enemy as its definition says
+pub fn spawn_defined_enemy(commands: &mut Commands, enemy: &Enemy, position: Vec3, enemy_defs: &EnemyDefs) -> Entity {
+    let def = enemy_defs.def(enemy.enemy_type);
+    let subtype = def.subtype(enemy.enemy_subtype);
+    let e = spawn_enemy(commands, enemy, enemy_defs.atlas(enemy.enemy_type, subtype.image), enemy_defs.size(enemy.enemy_type), 
+        position, subtype.frame);
+
+    // animated enemy gets AnimationTimer - general handler = enemies::animate_sprite
+    if let Some(seconds) = def.animation {
+        commands.entity(e).insert(AnimationTimer(Timer::from_seconds(seconds, true)));
+    }
+
+    // movement, shooting, spawning and turning to the ship - handlers in enemies.rs
+    insert_behaviours(commands, e, def, false);
+
+    e
+}
+
 pub fn spawn_fellow_enemy(
     commands: &mut Commands,
     fellow_enemy: &FellowEnemy,
@@ -565,338 +470,100 @@ pub fn spawn_fellow_item(
     .id()
 }
 
-fn spawn_room(
+pub fn spawn_room(
     commands: &mut Commands, 
     walls: &Vec<Wall>, 
-    wall_images: &Res<WallImages>, 
+    tileset: &Res<Tileset>, 
     enemies: &Vec<Enemy>,
     enemies_from_10: &Vec<Enemy>,
-    enemies_images: &Res<EnemiesImages>,
+    enemy_defs: &Res<EnemyDefs>,
     items: &Vec<Item>,
-    items_images: &Res<ItemsImages>,
-    assets: &Res<Assets<Image>>) 
+    items_images: &Res<ItemsImages>) 
 {
     // spawn walls
 
     for wall in walls.iter() {
-        let imgopt = assets.get(&wall_images.wall_images[wall.id]);
-        if let Some(img) = imgopt {
-            let imgw = img.texture_descriptor.size.width as f32;
-            let imgh = img.texture_descriptor.size.height as f32;
-
-            commands.spawn_bundle(SpriteBundle {
-                texture: wall_images.wall_images[wall.id].clone(),
+        if let Some((tile, image)) = tileset.tile(wall.id) {
+            let e = commands.spawn_bundle(SpriteBundle {
+                texture: image.clone(),
                 sprite: Sprite { 
                     color: Color::WHITE,
-                    custom_size: Some(Vec2::new(imgw, imgh)),
+                    custom_size: Some(Vec2::new(tile.w, tile.h)),
                     flip_x: false,
                     ..Default::default()
                 },
                 transform: Transform {
-                    translation: Vec3::new(wall.posx + imgw/2.0, WINDOW_H - wall.posy - INFO_BAR_H - imgh/2.0, 50.0),
+                    translation: Vec3::new(wall.posx + tile.w/2.0, WINDOW_H - wall.posy - INFO_BAR_H - tile.h/2.0, 50.0),
                     ..Default::default()
                 },
                 ..Default::default()
             })
-            .insert(WallComponent {
-                position: Vec3::new(wall.posx + imgw/2.0, WINDOW_H - wall.posy - INFO_BAR_H - imgh/2.0, 50.0),
-                size: Vec2::new(imgw - 1.0, imgh - 1.0),
-            });
+            .insert(WallSprite)
+            .id();
+
+            if tile.solid {
+                let (position, size) = wall_box(wall.posx, wall.posy, &tile.collision);
+                commands.entity(e).insert(WallComponent {
+                    position: Vec3::new(position.0, position.1, 50.0),
+                    size: Vec2::new(size.0, size.1),
+                });
+            }
         }
     }
 
     // spawn enemies
 
-    for enemy in enemies.iter() {
+    for enemy in enemies.iter().chain(enemies_from_10.iter()) {
         if enemy.health == 0 {
             continue;
         }
 
-        let spawn_x: f32;
-        let spawn_y: f32;
-        if enemy.first {
-            spawn_x = enemy.posx + 25.0;
-            spawn_y = WINDOW_H - INFO_BAR_H - enemy.posy - 25.0;
+        let (spawn_x, spawn_y) = if enemy.first {
+            enemy_start_centre(enemy.posx, enemy.posy)
         }
         else {
-            spawn_x = enemy.posx;
-            spawn_y = enemy.posy;
-        }
-
-        // animated enemy gets AnimationTimer - general handler = enemies::animate_sprite
-        // enemy which is moving or/and shooting gets EnemyTypeN component - special handler = enemies::enemy_N_movement
-
-        if enemy.enemy_type == 0 {
-            spawn_enemy(commands, &enemy, enemies_images.enemy_00.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-        }
-        else if enemy.enemy_type == 1 {
-            if enemy.enemy_subtype == 0 {
-                let e = spawn_enemy(commands, &enemy, enemies_images.enemy_01.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-                commands.entity(e).insert(EnemyType01);
-            }
-            else {
-                let e = spawn_enemy(commands, &enemy, enemies_images.enemy_01.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 1);
-                commands.entity(e).insert(EnemyType01);
+            (enemy.posx, enemy.posy)
+        };
+
+        let e = spawn_defined_enemy(commands, enemy, Vec3::new(spawn_x, spawn_y, 100.0), enemy_defs);
+
+        // fellow is drawn one carrier height above the carrier
+        let fellow_y = enemy_defs.size(enemy.enemy_type).y;
+
+        // spawn fellow enemy
+        if let Some(fellow_enemy) = &enemy.fellow_enemy {
+            let def = enemy_defs.def(fellow_enemy.enemy_type);
+            let subtype = def.subtype(fellow_enemy.enemy_subtype);
+            let fellow = spawn_fellow_enemy(commands, fellow_enemy, enemy_defs.atlas(fellow_enemy.enemy_type, subtype.image), 
+                enemy_defs.size(fellow_enemy.enemy_type), Vec3::new(0.0, fellow_y, 100.0), subtype.frame);
+            if let Some(seconds) = def.animation {
+                commands.entity(fellow).insert(AnimationTimer(Timer::from_seconds(seconds, true)));
             }
-        }
-        else if enemy.enemy_type == 2 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_02.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType02);
-        }
-        else if enemy.enemy_type == 3 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_03.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType03);
-        }
-        else if enemy.enemy_type == 4 {
-            spawn_enemy(commands, &enemy, enemies_images.enemy_04.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-        }
-        else if enemy.enemy_type == 5 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_05.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType05);
-        }
-        else if enemy.enemy_type == 6 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_06.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType06);
-        }
-        else if enemy.enemy_type == 7 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_07.clone(), ENEMY_07_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType07);
-        }
-        else if enemy.enemy_type == 8 {
-            if enemy.enemy_subtype == 0 {
-                let e = spawn_enemy(commands, &enemy, enemies_images.enemy_08.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-                commands.entity(e).insert(EnemyType08);
+            // fellow shoots, spawns and turns on its own, but moves with the carrier
+            commands.entity(fellow).insert(CarriedEnemy { enemy_type: fellow_enemy.enemy_type, room_seq: enemy.room_seq, enemy_seq: enemy.enemy_seq });
+            insert_behaviours(commands, fellow, def, true);
+            commands.entity(e).add_child(fellow);
+        }
+
+        // spawn fellow item
+        if let Some(fellow_item) = &enemy.fellow_item {
+            let fellow: Entity;
+            match fellow_item.item_type {
+                0 => fellow = spawn_fellow_item(commands, items_images.item_ammo.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                1 => fellow = spawn_fellow_item(commands, items_images.item_ball.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                2 => fellow = spawn_fellow_item(commands, items_images.item_fuel.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                3 => fellow = spawn_fellow_item(commands, items_images.item_missile_down.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                4 => fellow = spawn_fellow_item(commands, items_images.item_missile_side.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                5 => fellow = spawn_fellow_item(commands, items_images.item_shield.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                6 => fellow = spawn_fellow_item(commands, items_images.item_star.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                7 => fellow = spawn_fellow_item(commands, items_images.item_random.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                8 => fellow = spawn_fellow_item(commands, items_images.item_life.clone(), Vec3::new(0.0, fellow_y, 100.0)),
+                _ => panic!("Unexpected fellow item type!")
             }
-            else {
-                let e = spawn_enemy(commands, &enemy, enemies_images.enemy_08.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 1);
-                commands.entity(e).insert(EnemyType08);
-            }
-        }
-        else if enemy.enemy_type == 9 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_09.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType09);
-        }
-        else if enemy.enemy_type == 10 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_10.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType10);
-        }
-        else if enemy.enemy_type == 11 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_11.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType11);
-        }
-        else if enemy.enemy_type == 12 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_12.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType12);
-        }
-        else if enemy.enemy_type == 13 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_13_left.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType13);
-        }
-        else if enemy.enemy_type == 14 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_14_left.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType14);
-        }
-        else if enemy.enemy_type == 15 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_15.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType15);
-        }
-        else if enemy.enemy_type == 16 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_16.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType16);
+            commands.entity(e).add_child(fellow);
         }
-        else if enemy.enemy_type == 17 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_17_left.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType17);
-        }
-        else if enemy.enemy_type == 18 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_18.clone(), ENEMY_18_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType18);
-        }
-        else if enemy.enemy_type == 19 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_19.clone(), ENEMY_19_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType19);
-        }
-        else if enemy.enemy_type == 20 {
-            let e20: Entity;
-            if enemy.enemy_subtype == 0 {
-                e20 = spawn_enemy(commands, &enemy, enemies_images.enemy_20_v1.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-                commands.entity(e20)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-                .insert(EnemyType20);
-            }
-            else {
-                e20 = spawn_enemy(commands, &enemy, enemies_images.enemy_20_v2.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-                commands.entity(e20)
-                .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-                .insert(EnemyType20);
-            }
-            // spawn fellow enemy
-            if let Some(fellow_enemy) = &enemy.fellow_enemy {
-                if fellow_enemy.enemy_type == 0 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_00.clone(), ENEMY_NN_SIZE, 
-                        Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                    commands.entity(e20).add_child(e);
-                }
-                else if fellow_enemy.enemy_type == 1 {
-                    // println!("Spawn fellow 1, {}", fellow_enemy.enemy_subtype);
-                    if fellow_enemy.enemy_subtype == 0 {
-                        let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_01.clone(), ENEMY_NN_SIZE, 
-                            Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                        commands.entity(e20).add_child(e);
-                    }
-                    else {
-                        let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_01.clone(), ENEMY_NN_SIZE, 
-                            Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 1);
-                        commands.entity(e20).add_child(e);
-                    }
-                }
-                else if fellow_enemy.enemy_type == 2 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_02.clone(), ENEMY_NN_SIZE, 
-                        Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                    commands.entity(e20).add_child(e);
-                }
-                else if fellow_enemy.enemy_type == 3 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_03.clone(), ENEMY_NN_SIZE, 
-                        Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                    commands.entity(e).insert(EnemyType03Fellow);
-                    commands.entity(e20).add_child(e);
-                }
-                else if fellow_enemy.enemy_type == 4 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_04.clone(), ENEMY_NN_SIZE, 
-                        Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                    commands.entity(e20).add_child(e);
-                }
-                else if fellow_enemy.enemy_type == 5 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_05.clone(), ENEMY_NN_SIZE, 
-                        Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                    commands.entity(e20).add_child(e);
-                }
-                else if fellow_enemy.enemy_type == 7 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_07.clone(), ENEMY_07_SIZE, 
-                        Vec3::new(0.0, ENEMY_07_SIZE.y + 4.0, 100.0), 0);
-                    commands.entity(e).insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-                    commands.entity(e20).add_child(e);
-                }
-                else if fellow_enemy.enemy_type == 8 {
-                    if fellow_enemy.enemy_subtype == 0 {
-                        let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_08.clone(), ENEMY_NN_SIZE, 
-                            Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                        commands.entity(e20).add_child(e);
-                    }
-                    else {
-                        let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_08.clone(), ENEMY_NN_SIZE, 
-                            Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 1);
-                        commands.entity(e20).add_child(e);
-                    }
-                }
-                else if fellow_enemy.enemy_type == 9 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_09.clone(), ENEMY_NN_SIZE, 
-                        Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                    commands.entity(e).insert(AnimationTimer(Timer::from_seconds(0.1, true)));
-                    commands.entity(e20).add_child(e);
-                }
-                else if fellow_enemy.enemy_type == 10 {
-                    let e = spawn_fellow_enemy(commands, fellow_enemy, enemies_images.enemy_10.clone(), ENEMY_NN_SIZE, 
-                        Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0), 0);
-                    commands.entity(e).insert(EnemyType10Fellow);
-                    commands.entity(e20).add_child(e);
-                }
-            } // end spawn fellow enemy
-            // spawn fellow item
-            if let Some(fellow_item) = &enemy.fellow_item {
-                let e: Entity;
-                match fellow_item.item_type {
-                    0 => e = spawn_fellow_item(commands, items_images.item_ammo.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    1 => e = spawn_fellow_item(commands, items_images.item_ball.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    2 => e = spawn_fellow_item(commands, items_images.item_fuel.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    3 => e = spawn_fellow_item(commands, items_images.item_missile_down.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    4 => e = spawn_fellow_item(commands, items_images.item_missile_side.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    5 => e = spawn_fellow_item(commands, items_images.item_shield.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    6 => e = spawn_fellow_item(commands, items_images.item_star.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    7 => e = spawn_fellow_item(commands, items_images.item_random.clone(), Vec3::new(0.0, ENEMY_NN_SIZE.y, 100.0)),
-                    _ => panic!("Unexpected fellow item type!")
-                }
-                commands.entity(e20).add_child(e);
-            }
-        } // end 20
     } // end enemies
 
-    for enemy in enemies_from_10.iter() {
-        if enemy.health == 0 {
-            continue;
-        }
-
-        let spawn_x: f32;
-        let spawn_y: f32;
-        if enemy.first {
-            spawn_x = enemy.posx + 25.0;
-            spawn_y = WINDOW_H - INFO_BAR_H - enemy.posy - 25.0;
-        }
-        else {
-            spawn_x = enemy.posx;
-            spawn_y = enemy.posy;
-        }
-
-        if enemy.enemy_type == 11 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_11.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e).insert(EnemyType11);
-        }
-        else if enemy.enemy_type == 12 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_12.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType12);
-        }
-        else if enemy.enemy_type == 13 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_13_left.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType13);
-        }
-        else if enemy.enemy_type == 14 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_14_left.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType14);
-        }
-        else if enemy.enemy_type == 15 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_15.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType15);
-        }
-        else if enemy.enemy_type == 16 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_16.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType16);
-        }
-        else if enemy.enemy_type == 17 {
-            let e = spawn_enemy(commands, &enemy, enemies_images.enemy_17_left.clone(), ENEMY_NN_SIZE, Vec3::new(spawn_x, spawn_y, 100.0), 0);
-            commands.entity(e)
-            .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
-            .insert(EnemyType17);
-        }
-    } // end from 10
-
     // spawn items
     for item in items.iter() {
         if item.collected {
@@ -913,6 +580,7 @@ fn spawn_room(
                 5 => items_images.item_shield.clone(),
                 6 => items_images.item_star.clone(),
                 7 => items_images.item_random.clone(),
+                8 => items_images.item_life.clone(),
                 _ => panic!("Unexpected item type!")
             },
             sprite: Sprite { 
@@ -935,22 +603,32 @@ fn spawn_room(
 fn spawn_current_room(
     mut commands: Commands, 
     maze: Res<Maze>, 
-    wall_images: Res<WallImages>, 
-    enemies_images: Res<EnemiesImages>, 
-    items_images: Res<ItemsImages>,
-    assets: Res<Assets<Image>>) 
+    player: Res<Player>,
+    mut checkpoint: ResMut<Checkpoint>,
+    tileset: Res<Tileset>, 
+    enemy_defs: Res<EnemyDefs>, 
+    items_images: Res<ItemsImages>) 
 {
+    if !maze.loaded {
+        return;
+    }
+
+    // new or loaded game starts with a checkpoint in its first room
+    if !checkpoint.exists() {
+        checkpoint.take(&maze, &player);
+    }
+
     let walls = &maze.rooms[maze.current_room].walls;
     let enemies = &maze.rooms[maze.current_room].enemies;
     let enemies_from_10 = &maze.rooms[maze.current_room].enemies_from_10;
     let items = &maze.rooms[maze.current_room].items;
-    spawn_room(&mut commands, walls, &wall_images, &enemies, &enemies_from_10, &enemies_images, items, &items_images, &assets);
+    spawn_room(&mut commands, walls, &tileset, &enemies, &enemies_from_10, &enemy_defs, items, &items_images);
 }
 
 fn change_room(
     mut commands: Commands, 
     mut change_room_event: EventReader<RoomChangeEvent>,
-    walls_query: Query<Entity, With<WallComponent>>,
+    walls_query: Query<Entity, With<WallSprite>>,
     enemies_query: Query<Entity, With<EnemyComponent>>,
     enemies_shots_query: Query<Entity, With<EnemyShotComponent>>,
     booms_query: Query<Entity, With<Boom>>,
@@ -960,14 +638,24 @@ fn change_room(
     mut player: ResMut<Player>,
     cannon_query: Query<Entity, With<CannonShot>>,
     mut maze: ResMut<Maze>, 
-    wall_images: Res<WallImages>, 
-    enemies_images: Res<EnemiesImages>,
-    items_images: Res<ItemsImages>,
-    assets: Res<Assets<Image>>) 
+    mut checkpoint: ResMut<Checkpoint>,
+    tileset: Res<Tileset>, 
+    enemy_defs: Res<EnemyDefs>,
+    items_images: Res<ItemsImages>) 
 {
     for event in change_room_event.iter() {
         println!("Change room -> {},{}", event.row, event.col);
-        
+
+        // checked first, the current room stays as it is
+        let index = match maze.grid.room_index(event.row, event.col) {
+            Some(index) => index,
+            None => {
+                println!("Room {},{} is outside the maze!", event.row, event.col);
+                player.changing_room = false;
+                continue;
+            }
+        };
+
         // despawn current room
         for entity in walls_query.iter() {
             commands.entity(entity).despawn_recursive(); // despawn walls
@@ -991,20 +679,20 @@ fn change_room(
             commands.entity(entity).despawn_recursive(); // despawn items
         }
 
-        let index = (event.row * MAZE_COLS) + event.col;
         {
             maze.current_room = index;
             let walls = &maze.rooms[index].walls;
             let enemies = &maze.rooms[index].enemies;
             let enemies_from_10 = &maze.rooms[index].enemies_from_10;
             let items = &maze.rooms[index].items;
-            spawn_room(&mut commands, walls, &wall_images, enemies, enemies_from_10, &enemies_images, items, &items_images, &assets);
+            spawn_room(&mut commands, walls, &tileset, enemies, enemies_from_10, &enemy_defs, items, &items_images);
         }
 
+        // spawners keep their cooldown, leaving the room does not make them spawn at once
         let enemies = &mut maze.rooms[index].enemies;
         for enemy in enemies.iter_mut() {
-            if enemy.enemy_type != 10 {
-                enemy.shooting_cooldown = 0;
+            if !enemy_defs.def(enemy.enemy_type).spawner() {
+                enemy.shooting_cooldown = 0.0;
             }
         }
 
@@ -1016,6 +704,9 @@ fn change_room(
             player.shooting_cannon = false;
         }
         player.changing_room = false;
+        player.mark_room_entry();
+
+        checkpoint.take(&maze, &player);
 
         // println!("Change room END");
     }
@@ -1081,7 +772,7 @@ fn change_room_special_star(
 
 fn despawn_room(
     mut commands: Commands, 
-    walls_query: Query<Entity, With<WallComponent>>,
+    walls_query: Query<Entity, With<WallSprite>>,
     enemies_query: Query<Entity, With<EnemyComponent>>,
     enemies_shots_query: Query<Entity, With<EnemyShotComponent>>,
     booms_query: Query<Entity, With<Boom>>,
@@ -1114,8 +805,14 @@ fn despawn_room(
     }
 }
 
+fn count_playtime(time: Res<Time>, mut maze: ResMut<Maze>) {
+    if maze.loaded {
+        maze.playtime += time.delta_seconds();
+    }
+}
+
 fn check_bases_count(maze: Res<Maze>, mut state: ResMut<State<GameState>>) {
-    if maze.bases == 0 {
+    if maze.loaded && maze.bases == 0 {
         state.set(GameState::Victory).expect("MAZE: Failed to change state!");
     }
 }
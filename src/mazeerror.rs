@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use bevy::text::Text2dBounds;
+
+use crate::{GameState, WINDOW_W, WINDOW_W2, WINDOW_H2, mainmenu::UiAssets, maze::Maze};
+use crate::savegame::{SAVE_DIR, CHECKPOINT};
+use crate::actions::ActionInput;
+
+pub struct MazeErrorPlugin;
+
+impl Plugin for MazeErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system_set(SystemSet::on_enter(GameState::MazeError)
+            .with_system(spawn_maze_error)
+        )
+        .add_system_set(SystemSet::on_update(GameState::MazeError)
+            .with_system(maze_error_keyboard_input)
+        )
+        .add_system_set(SystemSet::on_exit(GameState::MazeError)
+            .with_system(despawn_maze_error)
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct MazeErrorText;
+
+fn spawn_maze_error(mut commands: Commands, ui_assets: Res<UiAssets>, maze: Res<Maze>) {
+    let title = match &maze.load_error {
+        Some(error) if error.file.starts_with(SAVE_DIR) => "Failed to load game!",
+        Some(error) if error.file == CHECKPOINT => "Failed to restore checkpoint!",
+        _ => "Failed to load maze!",
+    };
+
+    commands.spawn_bundle(Text2dBundle {
+        text: Text::from_section(
+            title, 
+            TextStyle {
+                font: ui_assets.font.clone(),
+                font_size: 40.0,
+                color: Color::RED,
+            }).with_alignment(TextAlignment::CENTER),
+        transform: Transform::from_xyz(WINDOW_W2, WINDOW_H2 + 60.0, 0.0),
+        ..Default::default()
+    }).insert(MazeErrorText);
+
+    let message = match &maze.load_error {
+        Some(error) => error.to_string(),
+        None => String::new(),
+    };
+
+    commands.spawn_bundle(Text2dBundle {
+        text: Text::from_section(
+            message, 
+            TextStyle {
+                font: ui_assets.font.clone(),
+                font_size: 20.0,
+                color: Color::RED,
+            }).with_alignment(TextAlignment::CENTER),
+        text_2d_bounds: Text2dBounds { size: Vec2::new(WINDOW_W - 80.0, WINDOW_H2) },
+        transform: Transform::from_xyz(WINDOW_W2, WINDOW_H2 - 20.0, 0.0),
+        ..Default::default()
+    }).insert(MazeErrorText);
+}
+
+fn despawn_maze_error(mut commands: Commands, text_query: Query<Entity, With<MazeErrorText>>, mut maze: ResMut<Maze>) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    // shown once, a maze still loaded from before can be resumed
+    maze.load_error = None;
+}
+
+fn maze_error_keyboard_input(mut actions: ResMut<ActionInput>, mut state: ResMut<State<GameState>>) {
+    if actions.any_just_pressed() {
+        actions.clear();
+        state.set(GameState::Menu).expect("MazeError: Failed to change state!");
+    }
+}
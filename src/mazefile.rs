@@ -0,0 +1,926 @@
+// Maze data file (RON) and reader of the legacy per-room text files.
+//
+// This module does not depend on the rest of the game, so it can be shared
+// with the command-line tools in src/bin.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::DeserializeOwned;
+use serde::de::{self, Unexpected};
+use std::fmt;
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+
+pub const MAZE_FILE: &str = "assets/data/maze.ron";
+pub const TILESET_FILE: &str = "assets/data/tileset.ron";
+pub const ENEMIES_FILE: &str = "assets/data/enemies.ron";
+
+// wall ids of the legacy data, the game takes them from the tileset
+pub const WALL_COUNT: usize = 30;
+pub const ENEMY_TYPE_MAX: usize = 20;
+pub const ITEM_TYPE_MAX: usize = 8;
+// shots enemies.rs can draw, numbered after the enemy type that fired them first
+pub const ENEMY_SHOT_TYPES: [usize; 9] = [1, 2, 3, 5, 6, 7, 8, 9, 13];
+
+// geometry of the game the tools check the maze against,
+// maze positions go down from the top left corner of the play area
+pub const PLAY_AREA_W: f32 = 800.0;
+pub const PLAY_AREA_H: f32 = 500.0;
+pub const PLAYER_W: f32 = 99.0;
+pub const PLAYER_H: f32 = 48.0;
+pub const ITEM_W: f32 = 49.0;
+pub const ITEM_H: f32 = 43.0;
+
+pub const START_ROOM: (usize, usize) = (0, 0);
+// pub const START_ROOM: (usize, usize) = (1, 14); // carrier 5, 8, 9
+// pub const START_ROOM: (usize, usize) = (2, 11); // carrier 2
+// pub const START_ROOM: (usize, usize) = (6, 3); // fellow item test
+// centre of the ship at the start, in game coordinates (y goes up from the bottom of the play area)
+pub const PLAYER_START_X: f32 = 210.0;
+pub const PLAYER_START_Y: f32 = 300.0;
+// pub const PLAYER_START_X: f32 = 650.0;  // room 1,14
+// pub const PLAYER_START_Y: f32 = 200.0;  // room 1,14
+// ship centre beyond these leaves the room (game coordinates)
+pub const LEFT_EDGE: f32 = PLAYER_W / 2.0;
+pub const RIGHT_EDGE: f32 = PLAY_AREA_W - PLAYER_W / 2.0;
+pub const TOP_EDGE: f32 = PLAY_AREA_H - PLAYER_H / 2.0;
+pub const BOTTOM_EDGE: f32 = PLAYER_H / 2.0;
+
+pub const CARRIER_TYPE: usize = 20;
+// enemy types spawn_room can put on a carrier
+pub const CARRIED_ENEMY_TYPES: [usize; 10] = [0, 1, 2, 3, 4, 5, 7, 8, 9, 10];
+
+// line 0 means the line is not known
+#[derive(Clone, Debug, PartialEq)]
+pub struct MazeLoadError {
+    pub file: String,
+    pub line: usize,
+    pub field: String,
+    pub expected: String,
+}
+
+impl MazeLoadError {
+    pub fn new(file: &str, line: usize, field: &str, expected: &str) -> Self {
+        MazeLoadError {
+            file: String::from(file),
+            line,
+            field: String::from(field),
+            expected: String::from(expected),
+        }
+    }
+}
+
+impl fmt::Display for MazeLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "{}:{}: {}: expected {}", self.file, self.line, self.field, self.expected)
+        } else {
+            write!(f, "{}: {}: expected {}", self.file, self.field, self.expected)
+        }
+    }
+}
+
+impl std::error::Error for MazeLoadError {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MazeData {
+    pub rows: usize,
+    pub cols: usize,
+    #[serde(default)]
+    pub edges: EdgePolicy,
+    pub rooms: Vec<RoomData>,
+}
+
+// what happens when the ship leaves the maze at its outer edge
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum EdgePolicy {
+    // the outer edge blocks the ship like a wall
+    #[default]
+    Solid,
+    // the ship comes back on the opposite side of the maze
+    Wrap,
+    // the ship enters the room with the given name
+    Exit(String),
+}
+
+// room layout of the maze with the edge policy resolved
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MazeGrid {
+    pub rows: usize,
+    pub cols: usize,
+    pub edges: EdgePolicy,
+    // target of EdgePolicy::Exit
+    pub exit_room: (usize, usize),
+}
+
+impl MazeGrid {
+    // index to rooms, None if the room is outside the maze
+    pub fn room_index(&self, row: usize, col: usize) -> Option<usize> {
+        if row < self.rows && col < self.cols {
+            Some((row * self.cols) + col)
+        }
+        else {
+            None
+        }
+    }
+
+    // room next to row,col in direction d_row,d_col (-1, 0 or 1), 
+    // None if the edge policy does not allow to leave the maze there
+    pub fn next_room(&self, row: usize, col: usize, d_row: isize, d_col: isize) -> Option<(usize, usize)> {
+        let next_row = row as isize + d_row;
+        let next_col = col as isize + d_col;
+
+        if next_row >= 0 && next_col >= 0 && (next_row as usize) < self.rows && (next_col as usize) < self.cols {
+            return Some((next_row as usize, next_col as usize));
+        }
+
+        match self.edges {
+            EdgePolicy::Solid => None,
+            EdgePolicy::Wrap => Some((
+                next_row.rem_euclid(self.rows as isize) as usize, 
+                next_col.rem_euclid(self.cols as isize) as usize)),
+            EdgePolicy::Exit(_) => Some(self.exit_room),
+        }
+    }
+}
+
+impl MazeData {
+    pub fn grid(&self, file_name: &str) -> Result<MazeGrid, MazeLoadError> {
+        let mut grid = MazeGrid { rows: self.rows, cols: self.cols, edges: self.edges.clone(), exit_room: (0, 0) };
+
+        if let EdgePolicy::Exit(name) = &self.edges {
+            let room = self.rooms.iter()
+                .find(|room| room.name.as_ref() == Some(name) && grid.room_index(room.row, room.col).is_some())
+                .ok_or_else(|| MazeLoadError::new(file_name, 0, "edges", format!("name of a room, found \"{}\"", name).as_str()))?;
+            grid.exit_room = (room.row, room.col);
+        }
+
+        Ok(grid)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoomData {
+    pub row: usize,
+    pub col: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub walls: Vec<WallData>,
+    #[serde(default)]
+    pub enemies: Vec<EnemyData>,
+    #[serde(default)]
+    pub items: Vec<ItemData>,
+}
+
+// wall id must be in the tileset, see TilesetData::check_maze
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WallData {
+    pub id: usize,
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EnemyData {
+    pub x: f32,
+    pub y: f32,
+    #[serde(deserialize_with = "enemy_type")]
+    pub enemy_type: usize,
+    #[serde(default)]
+    pub subtype: usize,
+    // only carrier (type 20) has a fellow
+    #[serde(default)]
+    pub fellow: Option<FellowData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum FellowData {
+    Enemy {
+        x: f32,
+        y: f32,
+        #[serde(deserialize_with = "enemy_type")]
+        enemy_type: usize,
+        #[serde(default)]
+        subtype: usize,
+    },
+    Item {
+        x: f32,
+        y: f32,
+        #[serde(deserialize_with = "item_type")]
+        item_type: usize,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ItemData {
+    pub x: f32,
+    pub y: f32,
+    #[serde(deserialize_with = "item_type")]
+    pub item_type: usize,
+}
+
+fn checked_id<'de, D: Deserializer<'de>>(deserializer: D, max: usize, expected: &'static str) -> Result<usize, D::Error> {
+    let id = usize::deserialize(deserializer)?;
+    if id > max {
+        return Err(de::Error::invalid_value(Unexpected::Unsigned(id as u64), &expected));
+    }
+    Ok(id)
+}
+
+fn enemy_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    checked_id(deserializer, ENEMY_TYPE_MAX, "an enemy type 0-20")
+}
+
+fn item_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    checked_id(deserializer, ITEM_TYPE_MAX, "an item type 0-8")
+}
+
+// ron reports syntax errors with a position, but errors raised by serde (missing field, invalid value, ...)
+// come without one, so the position is taken from the part of the text the deserializer did not consume
+fn ron_error(file_name: &str, text: &str, remainder_len: usize, error: &ron::Error) -> MazeLoadError {
+    let offset = if error.position.line > 0 {
+        text.split_inclusive('\n')
+            .take(error.position.line - 1)
+            .map(|line| line.len())
+            .sum::<usize>() + error.position.col.saturating_sub(1)
+    }
+    else {
+        text.len() - remainder_len
+    };
+    let offset = offset.min(text.len());
+    let line = text[..offset].matches('\n').count() + 1;
+
+    let message = error.code.to_string();
+    let mut field = field_before(&text[..offset]);
+    let expected: String;
+
+    if let Some(name) = message.strip_prefix("missing field ") {
+        field = name.trim_matches('`').to_string();
+        expected = String::from("field to be present");
+    }
+    else if let Some(rest) = message.strip_prefix("unknown field ") {
+        let (name, known) = rest.split_once(", expected ").unwrap_or((rest, "a known field"));
+        field = name.trim_matches('`').to_string();
+        expected = known.to_string();
+    }
+    else if let Some((_, rest)) = message.split_once(", expected ") {
+        expected = rest.to_string();
+    }
+    else if let Some(rest) = message.strip_prefix("Expected ") {
+        expected = rest.to_string();
+    }
+    else {
+        expected = message;
+    }
+
+    MazeLoadError { file: String::from(file_name), line, field, expected }
+}
+
+// name of the last "field:" before the error position
+fn field_before(text: &str) -> String {
+    let mut rest = text;
+    while let Some(colon) = rest.rfind(':') {
+        let name: String = rest[..colon].trim_end().chars().rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect::<Vec<char>>().into_iter().rev().collect();
+        if !name.is_empty() {
+            return name;
+        }
+        rest = &rest[..colon];
+    }
+    String::from("maze")
+}
+
+pub fn read_ron<T: DeserializeOwned>(file_name: &str, what: &str) -> Result<T, MazeLoadError> {
+    let text = std::fs::read_to_string(file_name)
+        .map_err(|e| MazeLoadError::new(file_name, 0, "file", &format!("readable {} file ({})", what, e)))?;
+    parse_ron(file_name, &text)
+}
+
+// file_name is only used in the errors
+pub fn parse_ron<T: DeserializeOwned>(file_name: &str, text: &str) -> Result<T, MazeLoadError> {
+    let mut deserializer = ron::de::Deserializer::from_str(text)
+        .map_err(|e| ron_error(file_name, text, text.len(), &e))?;
+
+    let result = T::deserialize(&mut deserializer).and_then(|data| deserializer.end().map(|_| data));
+    result.map_err(|e| ron_error(file_name, text, deserializer.remainder().len(), &e))
+}
+
+pub fn read_maze(file_name: &str) -> Result<MazeData, MazeLoadError> {
+    read_ron(file_name, "maze")
+}
+
+pub fn write_maze(file_name: &str, maze: &MazeData) -> std::io::Result<()> {
+    let config = ron::ser::PrettyConfig::new().depth_limit(4);
+    let text = ron::ser::to_string_pretty(maze, config).expect("Failed to serialize maze!");
+    std::fs::write(file_name, text)
+}
+
+// wall tileset: image, size and collision rectangle of every wall id
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TilesetData {
+    pub tiles: Vec<TileData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TileData {
+    pub id: usize,
+    // path under assets/
+    pub image: String,
+    // drawn size, the wall position is its top left corner
+    pub w: f32,
+    pub h: f32,
+    pub collision: CollisionRect,
+    // walls which are not solid are only drawn
+    #[serde(default = "default_solid")]
+    pub solid: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+// relative to the top left corner of the tile, y goes down
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CollisionRect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+fn default_solid() -> bool {
+    true
+}
+
+impl TilesetData {
+    pub fn tile(&self, id: usize) -> Option<&TileData> {
+        self.tiles.iter().find(|tile| tile.id == id)
+    }
+
+    pub fn check_maze(&self, maze: &MazeData, file_name: &str) -> Result<(), MazeLoadError> {
+        for room in maze.rooms.iter() {
+            if let Some(wall) = room.walls.iter().find(|wall| self.tile(wall.id).is_none()) {
+                return Err(MazeLoadError::new(file_name, 0, "id",
+                    format!("a wall id from the tileset, found {} in room {},{}", wall.id, room.row, room.col).as_str()));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn read_tileset(file_name: &str) -> Result<TilesetData, MazeLoadError> {
+    let tileset: TilesetData = read_ron(file_name, "tileset")?;
+
+    for (index, tile) in tileset.tiles.iter().enumerate() {
+        if tileset.tiles[..index].iter().any(|other| other.id == tile.id) {
+            return Err(MazeLoadError::new(file_name, 0, "id", format!("unique tile id, found {} again", tile.id).as_str()));
+        }
+        if tile.collision.w <= 0.0 || tile.collision.h <= 0.0 {
+            return Err(MazeLoadError::new(file_name, 0, "collision", format!("positive width and height for tile {}", tile.id).as_str()));
+        }
+    }
+
+    Ok(tileset)
+}
+
+// enemy definitions: stats, sprite sheet and behaviours of every enemy type
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EnemyDefsData {
+    pub enemies: Vec<EnemyDefData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EnemyDefData {
+    #[serde(deserialize_with = "enemy_type")]
+    pub enemy_type: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub health: i16,
+    // seconds between two shots (or spawns), None = the enemy does not shoot
+    #[serde(default)]
+    pub shooting_cooldown: Option<f32>,
+    // drawn size, also used for collisions with walls
+    pub w: f32,
+    pub h: f32,
+    // rectangle hit by the ship and its shots, centered on the sprite
+    pub hitbox: HitboxSize,
+    // sprite sheets with frames in one row, paths under assets/
+    pub images: Vec<String>,
+    #[serde(default = "default_frames")]
+    pub frames: usize,
+    // seconds per frame, None = the frame is set by the movement or by the subtype
+    #[serde(default)]
+    pub animation: Option<f32>,
+    // added to the score when the enemy is destroyed
+    pub score: u16,
+    // health taken from the ship by a contact or by a shot of the enemy
+    pub damage: f32,
+    pub movement: EnemyMovement,
+    // turns to the ship, which also sets the direction it shoots at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub face_player: Option<FacePlayer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shooting: Option<ShootingData>,
+    // indexed by the subtype of the maze file, a subtype not listed uses the defaults
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtypes: Vec<EnemySubtypeData>,
+    // enemy types spawned at random, one at every cooldown
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spawns: Vec<usize>,
+    // spawned enemies alive in the room at the same time at most
+    #[serde(default = "default_spawn_limit")]
+    pub spawn_limit: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct HitboxSize {
+    pub w: f32,
+    pub h: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum EnemyMovement {
+    // stays where the maze puts it
+    FixedTurret,
+    // random walk, acceleration in pixels per second squared
+    Patrol { acceleration: f32 },
+    // random walk pulled to the height of the ship, pull = acceleration per pixel of height difference
+    FollowPlayer { acceleration: f32, pull: f32 },
+    // starts diagonally at the given speed and bounces off walls
+    Bounce { speed: f32 },
+    // random walk with its fellow enemy or item on top
+    Carrier { acceleration: f32 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct EnemySubtypeData {
+    // index to images
+    pub image: usize,
+    pub frame: usize,
+    // direction the enemy shoots at, the movement may change it
+    pub facing: Option<Facing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Facing {
+    Left,
+    Right,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FacePlayer {
+    // frame 0 looks left, frame 1 right
+    Frame,
+    // image 0 looks left, image 1 right
+    Image,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ShootingData {
+    // one of ENEMY_SHOT_TYPES, gives the sprite and the sound of the shot
+    pub shot: usize,
+    pub aim: ShotAim,
+    // pixels per second, per axis for DiagonalUp
+    pub speed: f32,
+    // start of the shot from the middle of the enemy, x is mirrored when it faces left
+    #[serde(default)]
+    pub offset_x: f32,
+    #[serde(default)]
+    pub offset_y: f32,
+    // the ship must be this close to the line of fire, the cooldown runs only then
+    #[serde(default = "default_range")]
+    pub range: f32,
+    // the enemy itself flies off as the shot, so it fires once
+    #[serde(default)]
+    pub launch: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ShotAim {
+    // horizontally to the facing direction, when the ship is at the same height
+    Ahead,
+    // straight at the ship, without waiting for it
+    AtPlayer,
+    // vertically, when the ship is above or below
+    Up,
+    Down,
+    // diagonally up to the facing direction, when the ship is on that diagonal
+    DiagonalUp,
+    // horizontally to the side of the ship, when it is at the same height
+    Sideways,
+}
+
+fn default_range() -> f32 {
+    25.0
+}
+
+fn default_frames() -> usize {
+    1
+}
+
+fn default_spawn_limit() -> usize {
+    10
+}
+
+impl EnemyDefData {
+    // the enemy gets the Spawner behaviour
+    pub fn spawner(&self) -> bool {
+        !self.spawns.is_empty()
+    }
+
+    pub fn subtype(&self, subtype: usize) -> EnemySubtypeData {
+        self.subtypes.get(subtype).copied().unwrap_or_default()
+    }
+}
+
+impl EnemyDefsData {
+    pub fn enemy(&self, enemy_type: usize) -> Option<&EnemyDefData> {
+        self.enemies.iter().find(|enemy| enemy.enemy_type == enemy_type)
+    }
+}
+
+pub fn read_enemy_defs(file_name: &str) -> Result<EnemyDefsData, MazeLoadError> {
+    let defs: EnemyDefsData = read_ron(file_name, "enemy definitions")?;
+
+    for (index, enemy) in defs.enemies.iter().enumerate() {
+        let id = enemy.enemy_type;
+        let error = |field: &str, expected: String| MazeLoadError::new(file_name, 0, field, expected.as_str());
+
+        if defs.enemies[..index].iter().any(|other| other.enemy_type == id) {
+            return Err(error("enemy_type", format!("unique enemy type, found {} again", id)));
+        }
+        if enemy.health <= 0 {
+            return Err(error("health", format!("positive health for enemy {}", id)));
+        }
+        if enemy.w <= 0.0 || enemy.h <= 0.0 || enemy.hitbox.w <= 0.0 || enemy.hitbox.h <= 0.0 {
+            return Err(error("w, h", format!("positive width and height for enemy {}", id)));
+        }
+        if enemy.images.is_empty() || enemy.frames == 0 {
+            return Err(error("images", format!("at least one image with one frame for enemy {}", id)));
+        }
+        if matches!(enemy.animation, Some(seconds) if seconds <= 0.0) {
+            return Err(error("animation", format!("positive seconds per frame for enemy {}", id)));
+        }
+        if matches!(enemy.shooting_cooldown, Some(seconds) if seconds <= 0.0) {
+            return Err(error("shooting_cooldown", format!("positive seconds between shots for enemy {}", id)));
+        }
+        if enemy.subtypes.iter().any(|subtype| subtype.image >= enemy.images.len() || subtype.frame >= enemy.frames) {
+            return Err(error("subtypes", format!("image and frame of enemy {} in its sprite sheets", id)));
+        }
+        if let Some(spawn) = enemy.spawns.iter().find(|spawn| defs.enemy(**spawn).is_none()) {
+            return Err(error("spawns", format!("defined enemy types for enemy {}, found {}", id, spawn)));
+        }
+        if enemy.spawner() && enemy.spawn_limit == 0 {
+            return Err(error("spawn_limit", format!("at least one spawned enemy for enemy {}", id)));
+        }
+        match enemy.face_player {
+            Some(FacePlayer::Frame) if enemy.frames < 2 => return Err(error("face_player", format!("two frames for enemy {}", id))),
+            Some(FacePlayer::Image) if enemy.images.len() < 2 => return Err(error("face_player", format!("two images for enemy {}", id))),
+            _ => {}
+        }
+        if let Some(shooting) = &enemy.shooting {
+            if !ENEMY_SHOT_TYPES.contains(&shooting.shot) {
+                return Err(error("shot", format!("one of {:?} for enemy {}, found {}", ENEMY_SHOT_TYPES, id, shooting.shot)));
+            }
+            if shooting.speed <= 0.0 || shooting.range <= 0.0 {
+                return Err(error("speed, range", format!("positive shot speed and range for enemy {}", id)));
+            }
+        }
+    }
+
+    Ok(defs)
+}
+
+// legacy format: one file per room for walls, enemies and items,
+// named <prefix><row><col>.txt, every value on its own line and records separated by ';'
+
+struct LegacyReader {
+    file_name: String,
+    reader: BufReader<File>,
+    line: String,
+    line_no: usize,
+}
+
+impl LegacyReader {
+    fn open(file_name: &str) -> Result<Self, MazeLoadError> {
+        let file = File::open(file_name)
+            .map_err(|e| MazeLoadError::new(file_name, 0, "file", &format!("readable file ({})", e)))?;
+        Ok(LegacyReader { file_name: String::from(file_name), reader: BufReader::new(file), line: String::new(), line_no: 0 })
+    }
+
+    fn error(&self, field: &str, expected: &str) -> MazeLoadError {
+        MazeLoadError::new(&self.file_name, self.line_no, field, expected)
+    }
+
+    fn next_line(&mut self, field: &str) -> Result<&str, MazeLoadError> {
+        self.line.clear();
+        self.line_no += 1;
+        match self.reader.read_line(&mut self.line) {
+            Ok(0) => Err(self.error(field, "a line, found end of file")),
+            Ok(_) => Ok(self.line.trim()),
+            Err(e) => Err(self.error(field, &format!("readable line ({})", e))),
+        }
+    }
+
+    fn next_f32(&mut self, field: &str) -> Result<f32, MazeLoadError> {
+        let value = self.next_line(field)?.parse::<f32>().ok();
+        value.ok_or_else(|| self.error(field, "a number"))
+    }
+
+    fn next_usize(&mut self, field: &str, max: usize) -> Result<usize, MazeLoadError> {
+        let value = self.next_line(field)?.parse::<usize>().ok().filter(|v| *v <= max);
+        value.ok_or_else(|| self.error(field, &format!("an integer 0-{}", max)))
+    }
+
+    fn separator(&mut self) -> Result<(), MazeLoadError> {
+        if self.next_line(";")? != ";" {
+            return Err(self.error(";", "record separator ';'"));
+        }
+        Ok(())
+    }
+}
+
+fn read_legacy_walls(file_name: &str) -> Result<Vec<WallData>, MazeLoadError> {
+    let mut reader = LegacyReader::open(file_name)?;
+    let mut walls = Vec::new();
+
+    let count = reader.next_usize("count", usize::MAX)?;
+    reader.separator()?;
+
+    for _ in 0..count {
+        let id = reader.next_usize("id", WALL_COUNT - 1)?;
+        let x = reader.next_f32("x")?;
+        let y = reader.next_f32("y")?;
+        reader.separator()?;
+        walls.push(WallData { id, x, y });
+    }
+
+    Ok(walls)
+}
+
+fn read_legacy_enemies(file_name: &str) -> Result<Vec<EnemyData>, MazeLoadError> {
+    let mut reader = LegacyReader::open(file_name)?;
+    let mut enemies = Vec::new();
+
+    let count = reader.next_usize("count", usize::MAX)?;
+    reader.separator()?;
+
+    for _ in 0..count {
+        let x = reader.next_f32("x")?;
+        let y = reader.next_f32("y")?;
+        let enemy_type = reader.next_usize("enemy_type", ENEMY_TYPE_MAX)?;
+        let subtype = reader.next_usize("subtype", usize::MAX)?;
+        reader.separator()?;
+
+        // carrier is followed by its fellow block
+        let mut fellow = None;
+        if enemy_type == CARRIER_TYPE {
+            let fellow_type = reader.next_usize("fellow_type", 1)?;
+            let fx = reader.next_f32("x")?;
+            let fy = reader.next_f32("y")?;
+            if fellow_type == 0 {
+                let id = reader.next_usize("enemy_type", ENEMY_TYPE_MAX)?;
+                let subid = reader.next_usize("subtype", usize::MAX)?;
+                fellow = Some(FellowData::Enemy { x: fx, y: fy, enemy_type: id, subtype: subid });
+            }
+            else {
+                let id = reader.next_usize("item_type", ITEM_TYPE_MAX)?;
+                reader.next_usize("subtype", usize::MAX)?;
+                fellow = Some(FellowData::Item { x: fx, y: fy, item_type: id });
+            }
+            reader.separator()?;
+        }
+
+        enemies.push(EnemyData { x, y, enemy_type, subtype, fellow });
+    }
+
+    Ok(enemies)
+}
+
+fn read_legacy_items(file_name: &str) -> Result<Vec<ItemData>, MazeLoadError> {
+    let mut reader = LegacyReader::open(file_name)?;
+    let mut items = Vec::new();
+
+    let count = reader.next_usize("count", usize::MAX)?;
+    reader.separator()?;
+
+    for _ in 0..count {
+        let x = reader.next_f32("x")?;
+        let y = reader.next_f32("y")?;
+        let item_type = reader.next_usize("item_type", ITEM_TYPE_MAX)?;
+        reader.separator()?;
+        items.push(ItemData { x, y, item_type });
+    }
+
+    Ok(items)
+}
+
+pub fn read_legacy_maze(dir: &str, rows: usize, cols: usize) -> Result<MazeData, MazeLoadError> {
+    let mut rooms = Vec::new();
+
+    for row in 0..rows {
+        for col in 0..cols {
+            rooms.push(RoomData {
+                row,
+                col,
+                name: None,
+                walls: read_legacy_walls(format!("{}/rooms/room{}{}.txt", dir, row, col).as_str())?,
+                enemies: read_legacy_enemies(format!("{}/enemies/enemy{}{}.txt", dir, row, col).as_str())?,
+                items: read_legacy_items(format!("{}/items/item{}{}.txt", dir, row, col).as_str())?,
+            });
+        }
+    }
+
+    Ok(MazeData { rows, cols, edges: EdgePolicy::Solid, rooms })
+}
+
+// image size from the PNG header (IHDR chunk is always first)
+pub fn read_png_size(file_name: &str) -> Option<(f32, f32)> {
+    let data = std::fs::read(file_name).ok()?;
+    if data.len() < 24 || &data[1..4] != b"PNG" {
+        return None;
+    }
+    let w = u32::from_be_bytes([data[16], data[17], data[18], data[19]]);
+    let h = u32::from_be_bytes([data[20], data[21], data[22], data[23]]);
+    Some((w as f32, h as f32))
+}
+
+// overlap rule of collision_check, boxes are given by their centre and size
+// (same comparisons as bevy's collide_aabb, which the game used before)
+pub fn boxes_overlap(a: (f32, f32), a_size: (f32, f32), b: (f32, f32), b_size: (f32, f32)) -> bool {
+    let a_min = (a.0 - a_size.0 / 2.0, a.1 - a_size.1 / 2.0);
+    let a_max = (a.0 + a_size.0 / 2.0, a.1 + a_size.1 / 2.0);
+    let b_min = (b.0 - b_size.0 / 2.0, b.1 - b_size.1 / 2.0);
+    let b_max = (b.0 + b_size.0 / 2.0, b.1 + b_size.1 / 2.0);
+    a_min.0 < b_max.0 && a_max.0 > b_min.0 && a_min.1 < b_max.1 && a_max.1 > b_min.1
+}
+
+// centre and size of the WallComponent of a wall at x, y, in game coordinates
+pub fn wall_box(x: f32, y: f32, collision: &CollisionRect) -> ((f32, f32), (f32, f32)) {
+    (
+        (x + collision.x + collision.w / 2.0, PLAY_AREA_H - y - collision.y - collision.h / 2.0),
+        (collision.w - 1.0, collision.h - 1.0),
+    )
+}
+
+// centre of an enemy placed at x, y in the maze file, in game coordinates
+pub fn enemy_start_centre(x: f32, y: f32) -> (f32, f32) {
+    (x + 25.0, PLAY_AREA_H - y - 25.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(edges: EdgePolicy) -> MazeGrid {
+        MazeGrid { rows: 2, cols: 3, edges, exit_room: (1, 1) }
+    }
+
+    fn room(row: usize, col: usize, name: Option<&str>) -> RoomData {
+        RoomData { row, col, name: name.map(String::from), walls: Vec::new(), enemies: Vec::new(), items: Vec::new() }
+    }
+
+    // directory of its own for every test, the tests run in parallel
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("jetstory-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn room_index_inside_and_outside() {
+        let grid = grid(EdgePolicy::Solid);
+        assert_eq!(grid.room_index(0, 0), Some(0));
+        assert_eq!(grid.room_index(1, 2), Some(5));
+        assert_eq!(grid.room_index(2, 0), None);
+        assert_eq!(grid.room_index(0, 3), None);
+    }
+
+    #[test]
+    fn next_room_inside_the_maze() {
+        for edges in [EdgePolicy::Solid, EdgePolicy::Wrap, EdgePolicy::Exit(String::from("exit"))] {
+            let grid = grid(edges);
+            assert_eq!(grid.next_room(0, 0, 0, 1), Some((0, 1)));
+            assert_eq!(grid.next_room(0, 1, 1, 0), Some((1, 1)));
+            assert_eq!(grid.next_room(1, 2, 0, -1), Some((1, 1)));
+            assert_eq!(grid.next_room(1, 0, -1, 0), Some((0, 0)));
+        }
+    }
+
+    #[test]
+    fn next_room_solid_edge() {
+        let grid = grid(EdgePolicy::Solid);
+        assert_eq!(grid.next_room(0, 0, 0, -1), None);
+        assert_eq!(grid.next_room(0, 0, -1, 0), None);
+        assert_eq!(grid.next_room(1, 2, 0, 1), None);
+        assert_eq!(grid.next_room(1, 2, 1, 0), None);
+    }
+
+    #[test]
+    fn next_room_wrap_edge() {
+        let grid = grid(EdgePolicy::Wrap);
+        assert_eq!(grid.next_room(0, 0, 0, -1), Some((0, 2)));
+        assert_eq!(grid.next_room(0, 0, -1, 0), Some((1, 0)));
+        assert_eq!(grid.next_room(1, 2, 0, 1), Some((1, 0)));
+        assert_eq!(grid.next_room(1, 2, 1, 0), Some((0, 2)));
+    }
+
+    #[test]
+    fn next_room_exit_edge() {
+        let grid = grid(EdgePolicy::Exit(String::from("exit")));
+        assert_eq!(grid.next_room(0, 0, 0, -1), Some((1, 1)));
+        assert_eq!(grid.next_room(1, 2, 1, 0), Some((1, 1)));
+    }
+
+    #[test]
+    fn grid_finds_the_exit_room_by_name() {
+        let maze = MazeData { rows: 2, cols: 3, edges: EdgePolicy::Exit(String::from("exit")),
+            rooms: vec![room(0, 0, None), room(1, 2, Some("exit"))] };
+        assert_eq!(maze.grid("maze.ron").unwrap().exit_room, (1, 2));
+
+        let maze = MazeData { edges: EdgePolicy::Exit(String::from("missing")), ..maze };
+        let error = maze.grid("maze.ron").unwrap_err();
+        assert_eq!(error.field, "edges");
+    }
+
+    #[test]
+    fn ron_error_reports_line_and_field() {
+        let text = "(\n    rows: 1,\n    cols: 1,\n    rooms: [\n        (row: 0, col: 0, walls: [(id: 3, x: \"a\", y: 0)]),\n    ],\n)\n";
+        let error = parse_ron::<MazeData>("maze.ron", text).unwrap_err();
+        assert_eq!(error.file, "maze.ron");
+        assert_eq!(error.line, 5);
+        assert_eq!(error.field, "x");
+    }
+
+    #[test]
+    fn ron_error_reports_invalid_enemy_type() {
+        let text = "(\n    rows: 1,\n    cols: 1,\n    rooms: [\n        (row: 0, col: 0, enemies: [\n            (x: 0, y: 0, enemy_type: 21),\n        ]),\n    ],\n)\n";
+        let error = parse_ron::<MazeData>("maze.ron", text).unwrap_err();
+        assert_eq!(error.line, 6);
+        assert_eq!(error.field, "enemy_type");
+        assert_eq!(error.expected, "an enemy type 0-20");
+    }
+
+    #[test]
+    fn ron_error_reports_missing_field() {
+        let text = "(\n    rows: 1,\n    rooms: [],\n)\n";
+        let error = parse_ron::<MazeData>("maze.ron", text).unwrap_err();
+        assert_eq!(error.field, "cols");
+        assert_eq!(error.expected, "field to be present");
+    }
+
+    #[test]
+    fn legacy_room_converts() {
+        let dir = temp_dir("legacy");
+        for sub in ["rooms", "enemies", "items"] {
+            std::fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        std::fs::write(dir.join("rooms/room00.txt"), "1\n;\n5\n100\n200\n;\n").unwrap();
+        std::fs::write(dir.join("enemies/enemy00.txt"),
+            "2\n;\n100\n50\n4\n0\n;\n300\n200\n20\n1\n;\n0\n300\n150\n1\n0\n;\n").unwrap();
+        std::fs::write(dir.join("items/item00.txt"), "1\n;\n400\n300\n2\n;\n").unwrap();
+
+        let maze = read_legacy_maze(dir.to_str().unwrap(), 1, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let maze = maze.unwrap();
+
+        assert_eq!((maze.rows, maze.cols, maze.edges.clone()), (1, 1, EdgePolicy::Solid));
+        assert_eq!(maze.rooms, vec![RoomData {
+            row: 0,
+            col: 0,
+            name: None,
+            walls: vec![WallData { id: 5, x: 100.0, y: 200.0 }],
+            enemies: vec![
+                EnemyData { x: 100.0, y: 50.0, enemy_type: 4, subtype: 0, fellow: None },
+                EnemyData { x: 300.0, y: 200.0, enemy_type: CARRIER_TYPE, subtype: 1,
+                    fellow: Some(FellowData::Enemy { x: 300.0, y: 150.0, enemy_type: 1, subtype: 0 }) },
+            ],
+            items: vec![ItemData { x: 400.0, y: 300.0, item_type: 2 }],
+        }]);
+
+        // converted maze reads back the same
+        let text = ron::ser::to_string_pretty(&maze, ron::ser::PrettyConfig::new().depth_limit(4)).unwrap();
+        assert_eq!(parse_ron::<MazeData>("maze.ron", &text).unwrap(), maze);
+    }
+
+    #[test]
+    fn legacy_error_reports_line() {
+        let dir = temp_dir("legacy-error");
+        let file = dir.join("room00.txt");
+        std::fs::write(&file, "1\n;\n5\n100\nabc\n;\n").unwrap();
+
+        let error = read_legacy_walls(file.to_str().unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+        let error = error.unwrap_err();
+        assert_eq!((error.line, error.field.as_str()), (5, "y"));
+    }
+
+    #[test]
+    fn boxes_overlap_not_when_touching() {
+        assert!(boxes_overlap((0.0, 0.0), (10.0, 10.0), (9.0, 9.0), (10.0, 10.0)));
+        assert!(!boxes_overlap((0.0, 0.0), (10.0, 10.0), (10.0, 0.0), (10.0, 10.0)));
+        assert!(!boxes_overlap((0.0, 0.0), (10.0, 10.0), (0.0, -10.0), (10.0, 10.0)));
+    }
+}
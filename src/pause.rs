@@ -1,19 +1,42 @@
 use bevy::prelude::*;
-use bevy::text::Text2dBounds;
+use bevy::app::AppExit;
 use bevy_kira_audio::{AudioControl, AudioChannel};
 
-use crate::{GameState, WINDOW_W, WINDOW_H, WINDOW_W2, WINDOW_H2, mainmenu::UiAssets};
+use crate::{GameState, WINDOW_W, WINDOW_H, WINDOW_W2, WINDOW_H2};
 use crate::audio::{DamageChannel, Shooting01Channel, Shooting05Channel, Shooting06Channel, Shooting08Channel, Shooting09Channel};
+use crate::actions::{ActionInput, GameAction};
+use crate::mainmenu::{UiAssets, MenuItem, MenuFocus, spawn_button, menu_navigation};
+use crate::maze::Maze;
+use crate::player::Player;
+use crate::savegame::Checkpoint;
+use crate::settings::Settings;
+use crate::random::GameRng;
+
+pub const VOLUME_STEP: f64 = 0.1;
 
 pub struct PausePlugin;
 
 impl Plugin for PausePlugin {
     fn build(&self, app: &mut App) {
         app
+        .insert_resource(PauseMenu { page: PausePage::Main, rebuild: false })
         .add_system_set(SystemSet::on_enter(GameState::Pause)
             .with_system(spawn_pause)
             .with_system(pause_all_sounds)
         )
+        .add_system_set(SystemSet::on_update(GameState::Pause)
+            .with_system(menu_navigation.label("MenuNavigationSystem"))
+            .with_system(handle_pause_buttons.after("MenuNavigationSystem"))
+            .with_system(pause_keyboard_input.after("MenuNavigationSystem"))
+            .with_system(rebuild_pause_menu)
+        )
+        // key bindings are redefined on top of the pause, the overlay stays
+        .add_system_set(SystemSet::on_pause(GameState::Pause)
+            .with_system(despawn_pause_menu)
+        )
+        .add_system_set(SystemSet::on_resume(GameState::Pause)
+            .with_system(respawn_pause_menu)
+        )
         .add_system_set(SystemSet::on_exit(GameState::Pause)
             .with_system(despawn_pause)
             .with_system(resume_all_sounds)
@@ -21,20 +44,120 @@ impl Plugin for PausePlugin {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum PausePage {
+    Main,
+    Options,
+}
+
+pub struct PauseMenu {
+    page: PausePage,
+    rebuild: bool,
+}
+
 #[derive(Component)]
 struct PauseOverlay;
 
 #[derive(Component)]
-struct PauseText;
-
-fn spawn_pause(mut commands: Commands, ui_assets: Res<UiAssets>) {
-    // text style
-    let text_style = TextStyle {
-        font: ui_assets.font.clone(),
-        font_size: 40.0,
-        color: Color::RED,
-    };
+struct PauseMenuPanel;
+
+fn volume_text(name: &str, volume: f64) -> String {
+    format!("{} {:.0}%", name, volume * 100.0)
+}
+
+// steps of VOLUME_STEP, rounded so that repeated steps do not drift
+fn step_volume(volume: f64, steps: f64) -> f64 {
+    ((volume / VOLUME_STEP).round() + steps).clamp(0.0, 1.0 / VOLUME_STEP) * VOLUME_STEP
+}
+
+fn spawn_pause_menu(commands: &mut Commands, ui_assets: &Res<UiAssets>, menu: &PauseMenu, checkpoint: &Checkpoint, settings: &Settings, rng: &GameRng) {
+    let panel = commands.spawn_bundle(NodeBundle{
+        style: Style { 
+            size: Size::new(Val::Percent(100.0), Val::Percent(70.0)),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_content: AlignContent::Center,
+            justify_content: JustifyContent::Center,
+            align_self: AlignSelf::Center,
+            ..Default::default()
+        },
+        color: UiColor(Color::NONE),
+        ..Default::default()
+    })
+    .insert(PauseMenuPanel)
+    .id();
+
+    let title = if menu.page == PausePage::Main { "PAUSE" } else { "OPTIONS" };
+    let mut items = vec![commands.spawn_bundle(TextBundle {
+        text: Text::from_section(
+            title, 
+            TextStyle { 
+                font: ui_assets.font.clone(), 
+                font_size: 40.0, 
+                color: Color::RED 
+            }
+        ),
+        style: Style {
+            margin: UiRect::all(Val::Auto),
+            ..Default::default()
+        },
+        ..Default::default()
+    }).id()];
+
+    let mut buttons = Vec::new();
+    match menu.page {
+        PausePage::Main => {
+            buttons.push(spawn_button(commands, ui_assets, "PauseResumeButton", "Resume"));
+            if checkpoint.exists() {
+                buttons.push(spawn_button(commands, ui_assets, "PauseRestartButton", "Restart Room"));
+            }
+            buttons.push(spawn_button(commands, ui_assets, "PauseOptionsButton", "Options"));
+            buttons.push(spawn_button(commands, ui_assets, "PauseMenuButton", "Quit to Menu"));
+            buttons.push(spawn_button(commands, ui_assets, "PauseExitButton", "Quit to Desktop"));
+        },
+        PausePage::Options => {
+            buttons.push(spawn_button(commands, ui_assets, "MasterVolumeButton", &volume_text("Volume", settings.master_volume)));
+            buttons.push(spawn_button(commands, ui_assets, "EffectsVolumeButton", &volume_text("Effects", settings.effects_volume)));
+            buttons.push(spawn_button(commands, ui_assets, "KeyBindingsButton", "Redefine Keys"));
+            buttons.push(spawn_button(commands, ui_assets, "OptionsBackButton", "Back"));
+        },
+    }
 
+    for (index, button) in buttons.iter().enumerate() {
+        commands.entity(*button).insert(MenuItem(index));
+    }
+    items.extend_from_slice(&buttons);
+
+    // the run can be played again with --seed
+    if menu.page == PausePage::Main {
+        items.push(commands.spawn_bundle(TextBundle {
+            text: Text::from_section(
+                format!("SEED {}", rng.seed()),
+                TextStyle {
+                    font: ui_assets.font.clone(),
+                    font_size: 20.0,
+                    color: Color::GRAY
+                }
+            ),
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            ..Default::default()
+        }).id());
+    }
+
+    commands.entity(panel).push_children(&items);
+}
+
+fn spawn_pause(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    mut menu: ResMut<PauseMenu>,
+    mut focus: ResMut<MenuFocus>,
+    checkpoint: Res<Checkpoint>,
+    settings: Res<Settings>,
+    rng: Res<GameRng>)
+{
     // overlay
     commands.spawn_bundle(SpriteBundle {
         sprite: Sprite { 
@@ -51,41 +174,172 @@ fn spawn_pause(mut commands: Commands, ui_assets: Res<UiAssets>) {
     })
     .insert(PauseOverlay);
 
-    // text box
-    let box_size = Vec2::new(200.0, 60.0);
-    let box_position = Vec2::new(WINDOW_W2, WINDOW_H2);
-    commands.spawn_bundle(SpriteBundle {
-        sprite: Sprite {
-            color: Color::BLACK,
-            custom_size: Some(Vec2::new(box_size.x, box_size.y)),
-            ..Default::default()
-        },
-        transform: Transform::from_translation(box_position.extend(900.0)),
-        ..Default::default()
-    })
-    .insert(PauseText);
+    menu.page = PausePage::Main;
+    menu.rebuild = false;
+    focus.0 = 0;
+    spawn_pause_menu(&mut commands, &ui_assets, &menu, &checkpoint, &settings, &rng);
+}
 
-    // text
-    commands.spawn_bundle(Text2dBundle {
-        text: Text::from_section("PAUSE", text_style.clone()).with_alignment(TextAlignment::CENTER),
-        text_2d_bounds: Text2dBounds {
-            size: box_size,
-        },
-        transform: Transform::from_xyz(box_position.x, box_position.y, 999.0),
-        ..Default::default()
-    })
-    .insert(PauseText);
+fn despawn_pause_menu(mut commands: Commands, panel_query: Query<Entity, With<PauseMenuPanel>>) {
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn respawn_pause_menu(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    menu: Res<PauseMenu>,
+    checkpoint: Res<Checkpoint>,
+    settings: Res<Settings>,
+    rng: Res<GameRng>)
+{
+    spawn_pause_menu(&mut commands, &ui_assets, &menu, &checkpoint, &settings, &rng);
 }
 
-fn despawn_pause(mut commands: Commands, overlay_query: Query<Entity, With<PauseOverlay>>, text_query: Query<Entity, With<PauseText>>) {
+// page or volume changed, the buttons are spawned again
+fn rebuild_pause_menu(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    mut menu: ResMut<PauseMenu>,
+    checkpoint: Res<Checkpoint>,
+    settings: Res<Settings>,
+    rng: Res<GameRng>,
+    panel_query: Query<Entity, With<PauseMenuPanel>>)
+{
+    if menu.rebuild {
+        menu.rebuild = false;
+        for entity in panel_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_pause_menu(&mut commands, &ui_assets, &menu, &checkpoint, &settings, &rng);
+    }
+}
+
+fn despawn_pause(
+    mut commands: Commands,
+    overlay_query: Query<Entity, With<PauseOverlay>>,
+    panel_query: Query<Entity, With<PauseMenuPanel>>)
+{
     for entity in overlay_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
-    for entity in text_query.iter() {
+    for entity in panel_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
 }
 
+fn handle_pause_buttons(
+    interaction_query: Query<(&Name, &Interaction), Changed<Interaction>>,
+    mut state: ResMut<State<GameState>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    mut maze: ResMut<Maze>,
+    mut player: ResMut<Player>,
+    checkpoint: Res<Checkpoint>,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<PauseMenu>,
+    mut focus: ResMut<MenuFocus>,
+    mut exit: EventWriter<AppExit>)
+{
+    for (name, interaction) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        println!("Button clicked: {}", name.as_str());
+        mouse.clear();
+        match name.as_str() {
+            "PauseResumeButton" => {
+                state.pop().expect("PAUSE: Failed to pop state!");
+            },
+            "PauseRestartButton" => {
+                // replace, the game below on the stack is left and entered again with the room as it was on entry
+                match checkpoint.restore(&mut maze, &mut player) {
+                    Ok(()) => {
+                        state.replace(GameState::Game).expect("PAUSE: Failed to change state!");
+                    },
+                    Err(error) => {
+                        println!("Failed to restore checkpoint: {}", error);
+                        maze.loaded = false;
+                        maze.load_error = Some(error);
+                        state.replace(GameState::MazeError).expect("PAUSE: Failed to change state!");
+                    }
+                }
+            },
+            "PauseOptionsButton" => {
+                menu.page = PausePage::Options;
+                menu.rebuild = true;
+                focus.0 = 0;
+            },
+            "PauseMenuButton" => {
+                state.replace(GameState::Menu).expect("PAUSE: Failed to change state!");
+            },
+            "PauseExitButton" => {
+                exit.send(AppExit);
+            },
+            // a click goes up one step, back to zero after the full volume
+            "MasterVolumeButton" => {
+                settings.master_volume = if settings.master_volume >= 1.0 { 0.0 } else { step_volume(settings.master_volume, 1.0) };
+                menu.rebuild = true;
+            },
+            "EffectsVolumeButton" => {
+                settings.effects_volume = if settings.effects_volume >= 1.0 { 0.0 } else { step_volume(settings.effects_volume, 1.0) };
+                menu.rebuild = true;
+            },
+            "KeyBindingsButton" => {
+                state.push(GameState::RedefineKeys).expect("PAUSE: Failed to push state!");
+            },
+            "OptionsBackButton" => {
+                menu.page = PausePage::Main;
+                menu.rebuild = true;
+                focus.0 = 0;
+            },
+            _ => panic!("PAUSE: Unexpected button!")
+        }
+        // one click per frame, the buttons may be spawned again
+        break;
+    }
+}
+
+// left / right change the selected volume, Escape goes back a page or resumes
+fn pause_keyboard_input(
+    mut actions: ResMut<ActionInput>,
+    focus: Res<MenuFocus>,
+    item_query: Query<(&MenuItem, &Name)>,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<PauseMenu>,
+    mut state: ResMut<State<GameState>>)
+{
+    if actions.just_pressed(GameAction::Back) {
+        actions.clear();
+        if menu.page == PausePage::Options {
+            menu.page = PausePage::Main;
+            menu.rebuild = true;
+        }
+        else {
+            state.pop().expect("PAUSE: Failed to pop state!");
+        }
+        return;
+    }
+
+    let steps = if actions.just_pressed(GameAction::ThrustLeft) {
+        -1.0
+    }
+    else if actions.just_pressed(GameAction::ThrustRight) {
+        1.0
+    }
+    else {
+        return;
+    };
+
+    let focused = item_query.iter().find(|(item, _)| item.0 == focus.0).map(|(_, name)| name.as_str());
+    match focused {
+        Some("MasterVolumeButton") => settings.master_volume = step_volume(settings.master_volume, steps),
+        Some("EffectsVolumeButton") => settings.effects_volume = step_volume(settings.effects_volume, steps),
+        _ => return,
+    }
+    menu.rebuild = true;
+}
+
 fn pause_all_sounds(
     sfx_dmg: Res<AudioChannel<DamageChannel>>, 
     sfx01: Res<AudioChannel<Shooting01Channel>>, 
@@ -1,29 +1,25 @@
 use bevy::prelude::*;
 use bevy_kira_audio::{AudioControl, AudioChannel};
+use serde::{Deserialize, Serialize};
 
-use crate::infobar::{SpecialAmmoChange, SpecialChange, ScoreChange, BaseCountChange};
-use crate::items::{ITEM_W, ITEM_H};
-use crate::{WINDOW_H, WINDOW_W, INFO_BAR_H, GRAVITY, GameState, GameDirection, collision_check, GameKeys};
-use crate::enemies::{ENEMY_NN_SIZE, ENEMY_07_SIZE, EnemyComponent};
-use crate::maze::{Maze, WallComponent, RoomChangeEvent};
-use crate::explosions::ExplosionsImages;
+use crate::infobar::{SpecialAmmoChange, SpecialChange, LivesChange};
+use crate::{WINDOW_W, GRAVITY, GameState, GameDirection, collision_check};
+use crate::enemies::{EnemyDefs, EnemyComponent};
+use crate::maze::{Maze, WallComponent, RoomChangeEvent, START_ROOM};
+use crate::damage::{DamageEvent, HitPart, INSTANT_KILL, enemy_hits};
 use crate::audio::{Sounds, SfxChannel, DamageChannel};
 use crate::special::{SpecialType, SpecialImages};
+use crate::loading::LoadingAssets;
+use crate::settings::Settings;
+use crate::actions::{ActionInput, GameAction};
+use crate::gamestep::{GameStep, GAME_STEP, TIME_STEP};
+use crate::random::GameRng;
 
-pub const PLAYER_W: f32 = 99.0;
-pub const PLAYER_H: f32 = 48.0;
+pub use crate::mazefile::{PLAYER_W, PLAYER_H, PLAYER_START_X, PLAYER_START_Y};
 pub const PLAYER_W2: f32 = PLAYER_W / 2.0;
 pub const PLAYER_H2: f32 = PLAYER_H / 2.0;
 
-pub const PLAYER_START_X: f32 = 210.0;
-pub const PLAYER_START_Y: f32 = 300.0;
-// pub const PLAYER_START_X: f32 = 650.0;  // room 1,14
-// pub const PLAYER_START_Y: f32 = 200.0;  // room 1,14
-
-pub const LEFT_EDGE: f32 = PLAYER_W2;
-pub const RIGHT_EDGE: f32 = WINDOW_W - PLAYER_W2;
-pub const TOP_EDGE: f32 = WINDOW_H - INFO_BAR_H - PLAYER_H2;
-pub const BOTTOM_EDGE: f32 = PLAYER_H2;
+pub use crate::mazefile::{LEFT_EDGE, RIGHT_EDGE, TOP_EDGE, BOTTOM_EDGE};
 
 pub const FLAME_BACK_W: f32 = 43.0;
 pub const FLAME_BACK_H: f32 = 35.0;
@@ -55,10 +51,25 @@ pub const HEALTH_MAX: f32 = 1000.0;
 pub const FUEL_MAX: f32 = 1000.0;
 pub const AMMO_MAX: u16 = 1000;
 
-pub const FUEL_SUB: f32 = 0.005;
+// fuel used per second by each thrust
+pub const FUEL_BURN: f32 = 5.0;
+// health lost per second while out of fuel
+pub const NO_FUEL_DAMAGE: f32 = 500.0;
 
 pub const CANNON_DAMAGE: i16 = 10;
-pub const DAMAGE_DELAY: u8 = 50;
+// enemy health taken when it touches the ship
+pub const CONTACT_DAMAGE: i16 = 10;
+// seconds between two contacts with enemies
+pub const DAMAGE_DELAY: f32 = 0.05;
+// seconds per frame of the flames
+pub const FLAME_FRAME: f32 = 0.05;
+
+// defaults of the gameplay settings
+pub const PLAYER_LIVES: u8 = 3;
+pub const LIVES_MAX: u8 = 9;
+// seconds without damage after respawn
+pub const RESPAWN_INVULNERABILITY: f32 = 3.0;
+pub const INVULNERABILITY_BLINK: f32 = 0.1;
 
 pub struct PlayerPlugin;
 
@@ -70,11 +81,14 @@ impl Plugin for PlayerPlugin {
         .add_system_set(SystemSet::on_enter(GameState::Game)
             .with_system(spawn_player)
         )
-        .add_system_set(SystemSet::on_update(GameState::Game)
+        .add_system_set_to_stage(GAME_STEP, SystemSet::new()
             .with_system(player_movement).label("PlayerMovementSystem")
-            .with_system(cannon_shot_movement)
-            .with_system(player_vs_enemy)
-            .with_system(check_player_status)
+            .with_system(cannon_shot_movement.label("DamageSourceSystem"))
+            .with_system(player_vs_enemy.label("DamageSourceSystem"))
+            .with_system(out_of_fuel)
+            .with_system(player_invulnerability)
+            // in the steps: the room change event of a respawn is read by change_room in the same step
+            .with_system(check_player_status.label("PlayerStatusSystem").after("ApplyDamageSystem"))
         )
         .add_system_set(SystemSet::on_exit(GameState::Game)
             .with_system(despawn_player)
@@ -82,6 +96,7 @@ impl Plugin for PlayerPlugin {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Player {
     pub posx: f32,
     pub posy: f32,
@@ -96,13 +111,20 @@ pub struct Player {
     pub shooting_special: bool,
     current_room: (usize, usize),
     pub changing_room: bool,
-    damage_delay: u8,
+    damage_delay: f32,
     pub is_dead: bool,
     pub color_index: usize,
+    pub lives: u8,
+    // position and direction the ship entered the current room with, respawn puts it back there
+    entry_posx: f32,
+    entry_posy: f32,
+    entry_direction: GameDirection,
+    // seconds left
+    pub invulnerable: f32,
 }
 
 impl Player {
-    pub fn clear(&mut self) {
+    pub fn clear(&mut self, lives: u8) {
         self.posx = PLAYER_START_X;
         self.posy = PLAYER_START_Y;
         self.health = HEALTH_MAX;
@@ -114,11 +136,47 @@ impl Player {
         self.velocity = Vec2::splat(0.0);
         self.shooting_cannon = false;
         self.shooting_special = false;
-        self.current_room = (0, 0);
+        self.current_room = START_ROOM;
         self.changing_room = false;
         self.damage_delay = DAMAGE_DELAY;
         self.is_dead = false;
         self.color_index = 0;
+        self.lives = lives;
+        self.entry_posx = PLAYER_START_X;
+        self.entry_posy = PLAYER_START_Y;
+        self.entry_direction = GameDirection::Right;
+        self.invulnerable = 0.0;
+    }
+
+    pub fn current_room(&self) -> (usize, usize) {
+        self.current_room
+    }
+
+    pub fn mark_room_entry(&mut self) {
+        self.entry_posx = self.posx;
+        self.entry_posy = self.posy;
+        self.entry_direction = self.direction;
+    }
+
+    pub fn add_life(&mut self) {
+        if self.lives < LIVES_MAX {
+            self.lives += 1;
+        }
+    }
+
+    // one life less, back at the room entry with full shield and fuel, ammo and special are kept
+    fn respawn(&mut self, invulnerability: f32) {
+        self.lives -= 1;
+        self.posx = self.entry_posx;
+        self.posy = self.entry_posy;
+        self.direction = self.entry_direction;
+        self.velocity = Vec2::splat(0.0);
+        self.health = HEALTH_MAX;
+        self.fuel = FUEL_MAX;
+        self.damage_delay = DAMAGE_DELAY;
+        self.is_dead = false;
+        self.color_index = 0;
+        self.invulnerable = invulnerability;
     }
 }
 
@@ -128,19 +186,19 @@ pub struct PlayerComponent;
 #[derive(Component)]
 pub struct PlayerFlameBack {
     frame: u8,
-    cooldown: u8,
+    cooldown: f32,
 }
 
 #[derive(Component)]
 pub struct PlayerFlameDownBig {
     frame: u8,
-    cooldown: u8,
+    cooldown: f32,
 }
 
 #[derive(Component)]
 pub struct PlayerFlameDownSmall {
     frame: u8,
-    cooldown: u8,
+    cooldown: f32,
 }
 
 #[derive(Component)]
@@ -159,16 +217,16 @@ pub struct PlayerImages {
     pub cannon_shot: Handle<Image>,
 }
 
-pub fn load_player_resources(mut commands: Commands, assets: Res<AssetServer>) {
+pub fn load_player_resources(mut commands: Commands, assets: Res<AssetServer>, mut loading: ResMut<LoadingAssets>) {
     commands.insert_resource(PlayerImages {
-        ship: assets.load("images/ship/ship.png"),
-        flame_back1: assets.load("images/ship/flame_back1.png"),
-        flame_back2: assets.load("images/ship/flame_back2.png"),
-        flame_big_down1: assets.load("images/ship/flame_big_down1.png"),
-        flame_big_down2: assets.load("images/ship/flame_big_down2.png"),
-        flame_small_down1: assets.load("images/ship/flame_small_down1.png"),
-        flame_small_down2: assets.load("images/ship/flame_small_down2.png"),
-        cannon_shot: assets.load("images/ship/cannon.png"),
+        ship: loading.track(assets.load("images/ship/ship.png")),
+        flame_back1: loading.track(assets.load("images/ship/flame_back1.png")),
+        flame_back2: loading.track(assets.load("images/ship/flame_back2.png")),
+        flame_big_down1: loading.track(assets.load("images/ship/flame_big_down1.png")),
+        flame_big_down2: loading.track(assets.load("images/ship/flame_big_down2.png")),
+        flame_small_down1: loading.track(assets.load("images/ship/flame_small_down1.png")),
+        flame_small_down2: loading.track(assets.load("images/ship/flame_small_down2.png")),
+        cannon_shot: loading.track(assets.load("images/ship/cannon.png")),
     });
 }
 
@@ -185,11 +243,16 @@ fn create_player_resource(mut commands: Commands) {
         velocity: Vec2::splat(0.0),
         shooting_cannon: false,
         shooting_special: false,
-        current_room: (0, 0),
+        current_room: START_ROOM,
         changing_room: false,
         damage_delay: DAMAGE_DELAY,
         is_dead: false,
         color_index: 0,
+        lives: PLAYER_LIVES,
+        entry_posx: PLAYER_START_X,
+        entry_posy: PLAYER_START_Y,
+        entry_direction: GameDirection::Right,
+        invulnerable: 0.0,
     };
     commands.insert_resource(player);
 }
@@ -235,7 +298,7 @@ fn spawn_player(mut commands: Commands, player_images: Res<PlayerImages>, mut pl
     .insert(Name::new("PlayerFlameBack"))
     .insert(PlayerFlameBack {
         frame: 0,
-        cooldown: 0,
+        cooldown: 0.0,
     });
 
     // down big flame sprite
@@ -256,7 +319,7 @@ fn spawn_player(mut commands: Commands, player_images: Res<PlayerImages>, mut pl
     .insert(Name::new("PlayerFlameDownBig"))
     .insert(PlayerFlameDownBig {
         frame: 0,
-        cooldown: 0,
+        cooldown: 0.0,
     });
 
     // down small flame sprite
@@ -277,7 +340,7 @@ fn spawn_player(mut commands: Commands, player_images: Res<PlayerImages>, mut pl
     .insert(Name::new("PlayerFlameDownSmall"))
     .insert(PlayerFlameDownSmall {
         frame: 0,
-        cooldown: 0,
+        cooldown: 0.0,
     });
 
     // NOTE: 
@@ -363,11 +426,11 @@ fn player_movement(
     mut change_room_event: EventWriter<RoomChangeEvent>,
     mut change_special_ammo_event: EventWriter<SpecialAmmoChange>,
     images: Res<PlayerImages>,
-    keyboard: Res<Input<KeyCode>>,
-    sfx: Res<AudioChannel<SfxChannel>>, sounds: Res<Sounds>,
+    actions: Res<ActionInput>,
+    (sfx, sounds): (Res<AudioChannel<SfxChannel>>, Res<Sounds>),
     special_images: Res<SpecialImages>,
-    time: Res<Time>,
-    game_input: Res<GameKeys>) 
+    maze: Res<Maze>,
+    mut rng: ResMut<GameRng>) 
 {
     if player.changing_room {
         // println!("player changing room...");
@@ -397,15 +460,15 @@ fn player_movement(
     let mut force = Vec2::splat(0.0);
 
     // gravity
-    force += GRAVITY * time.delta_seconds();
+    force += GRAVITY * TIME_STEP;
 
     // friction
 
     if player.velocity.x > 0.0 {
-        force += Vec2::new(-100.0, 0.0) * time.delta_seconds();
+        force += Vec2::new(-100.0, 0.0) * TIME_STEP;
     }
     if player.velocity.x < 0.0 {
-        force += Vec2::new(100.0, 0.0) * time.delta_seconds();
+        force += Vec2::new(100.0, 0.0) * TIME_STEP;
     }
 
     let mut horiz_key = false;
@@ -413,33 +476,33 @@ fn player_movement(
 
     // apply force from directional keys (left / right / up)
 
-    if keyboard.pressed(game_input.left) && player.fuel > 0.0 {
+    if actions.pressed(GameAction::ThrustLeft) && player.fuel > 0.0 {
         // println!("LEFT");
         player.direction = GameDirection::Left;
-        player.fuel -= FUEL_SUB;
+        player.fuel -= FUEL_BURN * TIME_STEP;
         player_sprite.flip_x = true;
-        force += Vec2::new(-200.0, 0.0) * time.delta_seconds();
+        force += Vec2::new(-200.0, 0.0) * TIME_STEP;
         horiz_key = true;
     }
 
-    if keyboard.pressed(game_input.right) && player.fuel > 0.0 {
+    if actions.pressed(GameAction::ThrustRight) && player.fuel > 0.0 {
         // println!("RIGHT");
         player.direction = GameDirection::Right;
-        player.fuel -= FUEL_SUB;
+        player.fuel -= FUEL_BURN * TIME_STEP;
         player_sprite.flip_x = false;
-        force += Vec2::new(200.0, 0.0) * time.delta_seconds();
+        force += Vec2::new(200.0, 0.0) * TIME_STEP;
         horiz_key = true;
     }
 
-    if keyboard.pressed(game_input.up) && player.fuel > 0.0 {
+    if actions.pressed(GameAction::ThrustUp) && player.fuel > 0.0 {
         // println!("UP");
-        player.fuel -= FUEL_SUB;
-        force += Vec2::new(0.0, 200.0) * time.delta_seconds();
+        player.fuel -= FUEL_BURN * TIME_STEP;
+        force += Vec2::new(0.0, 200.0) * TIME_STEP;
         up_key = true;
     }
 
     // special shooting
-    if keyboard.just_pressed(game_input.down) && !player.shooting_special && player.ammo_special > 0 {
+    if actions.step_just_pressed(GameAction::FireSpecial) && !player.shooting_special && player.ammo_special > 0 {
         sfx.play(sounds.special_launch.clone());
         player.ammo_special -= 1;
         player.shooting_special = true;
@@ -450,14 +513,14 @@ fn player_movement(
                 player_transform.translation.x, player_transform.translation.y - 10.0),
             SpecialType::MissileSide => crate::special::spawn_special_missile_side(&mut commands, &special_images, 
                 player_transform.translation.x, player_transform.translation.y - 10.0, player.direction),
-            SpecialType::Star => crate::special::spawn_special_star(&mut commands, &special_images, 
+            SpecialType::Star => crate::special::spawn_special_star(&mut commands, &special_images, &mut rng.game,
                 player_transform.translation.x, player_transform.translation.y),
         }
         change_special_ammo_event.send(SpecialAmmoChange(player.ammo_special));
     }
 
     // cannon shooting
-    if keyboard.pressed(game_input.fire) && !player.shooting_cannon && player.ammo > 0 {
+    if actions.pressed(GameAction::FireCannon) && !player.shooting_cannon && player.ammo > 0 {
         sfx.play(sounds.cannon_shot.clone());
         player.shooting_cannon = true;
         player.ammo -= 1;
@@ -489,7 +552,7 @@ fn player_movement(
 
     let mut position = Vec2::splat(0.0);
     position += player.velocity;
-    position *= time.delta_seconds();
+    position *= TIME_STEP;
 
     let target = player_transform.translation + Vec3::new(position.x, 0.0, 0.0);
     if !walls_query.iter().any(|&wall| collision_check(
@@ -516,40 +579,68 @@ fn player_movement(
     }
     // println!("position: {:?}", transform.translation);
 
+    // at the outer edge of the maze the maze edge policy decides where the ship goes,
+    // if there is no room the edge works as a wall
+    let (row, col) = player.current_room;
+
     // check movement LEFT
     if player.velocity.x < 0.0 && player_transform.translation.x < LEFT_EDGE && !player.changing_room {
         // println!("Exit room to LEFT");
-        player_transform.translation.x = RIGHT_EDGE - 1.0;
-        player.current_room.1 -= 1;
-        player.changing_room = true;
-        change_room_event.send(RoomChangeEvent { row: player.current_room.0, col: player.current_room.1});
+        if let Some(next_room) = maze.grid.next_room(row, col, 0, -1) {
+            player_transform.translation.x = RIGHT_EDGE - 1.0;
+            player.current_room = next_room;
+            player.changing_room = true;
+            change_room_event.send(RoomChangeEvent { row: next_room.0, col: next_room.1 });
+        }
+        else {
+            player_transform.translation.x = LEFT_EDGE;
+            player.velocity.x *= -0.25;
+        }
     }
     else
     // check movement RIGHT
     if player.velocity.x > 0.0 && player_transform.translation.x > RIGHT_EDGE && !player.changing_room {
         // println!("Exit room to RIGHT");
-        player_transform.translation.x = LEFT_EDGE + 1.0;
-        player.current_room.1 += 1;
-        player.changing_room = true;
-        change_room_event.send(RoomChangeEvent { row: player.current_room.0, col: player.current_room.1});
+        if let Some(next_room) = maze.grid.next_room(row, col, 0, 1) {
+            player_transform.translation.x = LEFT_EDGE + 1.0;
+            player.current_room = next_room;
+            player.changing_room = true;
+            change_room_event.send(RoomChangeEvent { row: next_room.0, col: next_room.1 });
+        }
+        else {
+            player_transform.translation.x = RIGHT_EDGE;
+            player.velocity.x *= -0.25;
+        }
     }
     else
     // check movemnt UP
     if player.velocity.y > 0.0 && player_transform.translation.y > TOP_EDGE && !player.changing_room {
         // println!("Exit room to UP");
-        player_transform.translation.y = BOTTOM_EDGE - 1.0;
-        player.current_room.0 -= 1;
-        player.changing_room = true;
-        change_room_event.send(RoomChangeEvent { row: player.current_room.0, col: player.current_room.1});
+        if let Some(next_room) = maze.grid.next_room(row, col, -1, 0) {
+            player_transform.translation.y = BOTTOM_EDGE - 1.0;
+            player.current_room = next_room;
+            player.changing_room = true;
+            change_room_event.send(RoomChangeEvent { row: next_room.0, col: next_room.1 });
+        }
+        else {
+            player_transform.translation.y = TOP_EDGE;
+            player.velocity.y *= -0.25;
+        }
     }
     else
     // check movement DOWN
     if player.velocity.y < 0.0 && player_transform.translation.y < BOTTOM_EDGE && !player.changing_room {
         // println!("Exit room to DOWN");
-        player_transform.translation.y = TOP_EDGE + 1.0;
-        player.current_room.0 += 1;
-        player.changing_room = true;
-        change_room_event.send(RoomChangeEvent { row: player.current_room.0, col: player.current_room.1});
+        if let Some(next_room) = maze.grid.next_room(row, col, 1, 0) {
+            player_transform.translation.y = TOP_EDGE + 1.0;
+            player.current_room = next_room;
+            player.changing_room = true;
+            change_room_event.send(RoomChangeEvent { row: next_room.0, col: next_room.1 });
+        }
+        else {
+            player_transform.translation.y = BOTTOM_EDGE;
+            player.velocity.y *= -0.25;
+        }
     }
 
     // println!("Player direction: {:?}", player.direction);
@@ -591,8 +682,8 @@ fn player_movement(
     // else, set back flame invisible
     if horiz_key {
         flame_back_visibility.is_visible = true;
-        flame_back.cooldown += 1;
-        if flame_back.cooldown == 50 {
+        flame_back.cooldown += TIME_STEP;
+        if flame_back.cooldown >= FLAME_FRAME {
             if flame_back.frame == 0 {
                 flame_back.frame = 1;
                 *flame_back_texture = images.flame_back1.clone();
@@ -601,7 +692,7 @@ fn player_movement(
                 flame_back.frame = 0;
                 *flame_back_texture = images.flame_back2.clone();
             }
-            flame_back.cooldown = 0;
+            flame_back.cooldown = 0.0;
         }
     }
     else {
@@ -614,10 +705,10 @@ fn player_movement(
         flame_down_big_visibility.is_visible = true;
         flame_down_small_visibility.is_visible = true;
 
-        flame_down_big.cooldown += 1;
-        flame_down_small.cooldown += 1;
+        flame_down_big.cooldown += TIME_STEP;
+        flame_down_small.cooldown += TIME_STEP;
         
-        if flame_down_big.cooldown == 50 {
+        if flame_down_big.cooldown >= FLAME_FRAME {
             if flame_down_big.frame == 0 {
                 flame_down_big.frame = 1;
                 *flame_down_big_texture = images.flame_big_down1.clone();
@@ -626,10 +717,10 @@ fn player_movement(
                 flame_down_big.frame = 0;
                 *flame_down_big_texture = images.flame_big_down2.clone();
             }
-            flame_down_big.cooldown = 0;
+            flame_down_big.cooldown = 0.0;
         }
 
-        if flame_down_small.cooldown == 50 {
+        if flame_down_small.cooldown >= FLAME_FRAME {
             if flame_down_small.frame == 0 {
                 flame_down_small.frame = 1;
                 *flame_down_small_texture = images.flame_small_down1.clone();
@@ -638,7 +729,7 @@ fn player_movement(
                 flame_down_small.frame = 0;
                 *flame_down_small_texture = images.flame_small_down2.clone();
             }
-            flame_down_small.cooldown = 0;
+            flame_down_small.cooldown = 0.0;
         }
     }
     else {
@@ -654,154 +745,26 @@ fn cannon_shot_movement(
     mut player: ResMut<Player>,
     walls_query: Query<&WallComponent>,
     enemies_query: Query<(Entity, &EnemyComponent, &Transform), With<EnemyComponent>>,
-    mut maze: ResMut<Maze>,
-    time: Res<Time>,
-    explosions_images: Res<ExplosionsImages>,
-    sfx: Res<AudioChannel<SfxChannel>>, sounds: Res<Sounds>,
-    mut change_score_event: EventWriter<ScoreChange>,
-    mut change_base_count_event: EventWriter<BaseCountChange>) 
+    maze: Res<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    mut damage_events: EventWriter<DamageEvent>) 
 {
     for (eopt, copt, topt) in cannon_query.iter_mut() {
         if let Some(cannon) = copt {
             if let Some(mut cannon_transform) = topt {
 
-                let target_pos = cannon_transform.translation + Vec3::new(cannon.velocity.x * time.delta_seconds(), 0.0, 0.0);
+                let target_pos = cannon_transform.translation + Vec3::new(cannon.velocity.x * TIME_STEP, 0.0, 0.0);
                 let target_size = Vec2::new(SHOT_CANNON_W, SHOT_CANNON_H);
                 let mut collided = false;
 
-                // check collision with enemies
+                // check collision with enemies, the fellow shields its carrier
                 for (enemy_entity, enemy_component, enemy_transform) in enemies_query.iter() {
-                    // from 10
-                    if enemy_component.is_from_10 {
-                        let room = &mut maze.rooms[enemy_component.room_seq];
-                        
-                        if collision_check(target_pos, target_size, enemy_transform.translation, ENEMY_NN_SIZE) {
-                            collided = true;
-                            for (idx, mut enemy) in room.enemies_from_10.iter_mut().enumerate() {
-                                if enemy.enemy_seq == enemy_component.enemy_seq {
-                                    enemy.health -= CANNON_DAMAGE;
-                                    if enemy.health <= 0 {
-                                        crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                                        sfx.play(sounds.boom.clone());
-                                        commands.entity(enemy_entity).despawn_recursive();
-                                        room.enemies_from_10.remove(idx);
-                                        maze.score += 100;
-                                        break;
-                                    }
-                                    else {
-                                        sfx.play(sounds.enemy_damage.clone());
-                                    }
-                                }
-                            }
-                        }
+                    let hits = enemy_hits(target_pos, target_size, enemy_component, enemy_transform.translation, &maze, &enemy_defs);
+                    if let Some(part) = hits.into_iter().find(|part| *part != HitPart::FellowItem) {
+                        collided = true;
+                        damage_events.send(DamageEvent { entity: enemy_entity, part, amount: CANNON_DAMAGE, hit_sound: true });
                     }
-                    else {
-                        let mut tmp_score: u16 = 0;
-                        {
-                            let enemy = &mut maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
-                            // 20 + fellow
-                            if enemy.enemy_type == 20 {
-                                let mut fellow_died = false;
-                                let mut carrier_died = false;
-
-                                if enemy.fellow_enemy.is_some() {
-                                    // println!("Check cannon collision with fellow");
-                                    let fellow = enemy.fellow_enemy.as_mut().unwrap();
-
-                                    // prepare fellow size and position
-                                    let mut fellow_size = ENEMY_NN_SIZE;
-                                    if fellow.enemy_type == 7 {
-                                        fellow_size = ENEMY_07_SIZE;
-                                    }
-                                    let fellow_pos = Vec3::new(
-                                        enemy_transform.translation.x, 
-                                        enemy_transform.translation.y + (ENEMY_NN_SIZE.y / 2.0) + (fellow_size.y / 2.0),
-                                        0.0);
-                                    
-                                    // check collision with fellow
-                                    if collision_check(target_pos, target_size, fellow_pos, fellow_size) {
-                                        collided = true;
-                                        fellow.health -= CANNON_DAMAGE;
-                                        if fellow.health <= 0 {
-                                            crate::explosions::spawn_boom(&mut commands, 
-                                                Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                            sfx.play(sounds.boom.clone());
-                                            commands.entity(enemy_entity).despawn_recursive();
-                                            tmp_score += 100;
-                                            fellow_died = true;
-                                        }
-                                        else {
-                                            sfx.play(sounds.enemy_damage.clone());
-                                        }
-                                    }
-                                };
-        
-                                if !collided {
-                                    // check also carrier
-                                    if collision_check(target_pos, target_size, enemy_transform.translation, ENEMY_NN_SIZE) {
-                                        collided = true;
-                                        enemy.health -= CANNON_DAMAGE;
-                                        if enemy.health <= 0 {
-                                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                                            sfx.play(sounds.boom.clone());
-                                            commands.entity(enemy_entity).despawn_recursive();
-                                            tmp_score += 100;
-                                            carrier_died = true;
-                                        }
-                                        else {
-                                            sfx.play(sounds.enemy_damage.clone());
-                                        }
-                                    }
-                                }
-
-                                if fellow_died {
-                                    // destroy also carrier
-                                    enemy.health = 0;
-                                    crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                                    sfx.play(sounds.boom.clone());
-                                    commands.entity(enemy_entity).despawn_recursive();
-                                }
-
-                                if carrier_died {
-                                    // destroy also fellow
-                                    if enemy.fellow_enemy.is_some() {
-                                        let fellow = enemy.fellow_enemy.as_mut().unwrap();
-                                        fellow.health = 0;
-                                        crate::explosions::spawn_boom(&mut commands, 
-                                            Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                        sfx.play(sounds.boom.clone());
-                                        commands.entity(enemy_entity).despawn_recursive();
-                                    }
-                                }
-                            }
-                            else {
-                                // others
-                                if collision_check(target_pos, target_size, enemy_transform.translation, ENEMY_NN_SIZE) {
-                                    collided = true;
-                                    enemy.health -= CANNON_DAMAGE;
-                                    if enemy.health <= 0 {
-                                        crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                                        if enemy.enemy_type == 0 {
-                                            crate::explosions::spawn_flash(&mut commands);
-                                            sfx.play(sounds.boom_base.clone());
-                                            maze.score += 1000;
-                                            maze.bases -= 1;
-                                        }
-                                        else {
-                                            sfx.play(sounds.boom.clone());
-                                            maze.score += 100;
-                                        }
-                                        commands.entity(enemy_entity).despawn_recursive();
-                                    }
-                                    else {
-                                        sfx.play(sounds.enemy_damage.clone());
-                                    }
-                                }
-                            }
-                        }
-                        maze.score += tmp_score;
-                    }
-                } // end for
+                }
 
                 if collided {
                     // cannon collided with some enemy
@@ -810,8 +773,6 @@ fn cannon_shot_movement(
                         commands.entity(entity).despawn_recursive();
                     }
                     player.shooting_cannon = false;
-                    change_score_event.send(ScoreChange(maze.score));
-                    change_base_count_event.send(BaseCountChange(maze.bases));
                 }
                 else {
                     // check collision with walls
@@ -846,230 +807,98 @@ fn cannon_shot_movement(
 }
 
 fn player_vs_enemy(
-    mut commands: Commands, 
     mut player: ResMut<Player>,
     mut player_query: Query<&Transform, With<PlayerComponent>>,
-    mut enemies_query: Query<(Entity, &EnemyComponent, &Transform), Without<PlayerComponent>>,
+    enemies_query: Query<(Entity, &EnemyComponent, &Transform), Without<PlayerComponent>>,
     mut maze: ResMut<Maze>,
-    explosions_images: Res<ExplosionsImages>,
+    enemy_defs: Res<EnemyDefs>,
     sfx: Res<AudioChannel<SfxChannel>>, 
     sfx_dmg: Res<AudioChannel<DamageChannel>>, 
     sounds: Res<Sounds>,
     mut change_special_event: EventWriter<SpecialChange>,
     mut change_special_ammo_event: EventWriter<SpecialAmmoChange>,
-    mut change_score_event: EventWriter<ScoreChange>,
-    mut change_base_count_event: EventWriter<BaseCountChange>)
+    mut change_lives_event: EventWriter<LivesChange>,
+    mut damage_events: EventWriter<DamageEvent>)
 {
-    if player.damage_delay > 0 {
-        player.damage_delay -= 1;
+    if player.damage_delay > 0.0 {
+        player.damage_delay -= TIME_STEP;
+        return;
+    }
+
+    // just respawned, enemies pass through the ship
+    if player.invulnerable > 0.0 {
         return;
     }
 
     let player_transform = player_query.single_mut();
     let mut taking_damage = false;
-    let mut dmg_sound_started = false;
-    let mut something_died = false;
 
-    for (enemy_entity, enemy_component, enemy_transform) in enemies_query.iter_mut() 
+    for (enemy_entity, enemy_component, enemy_transform) in enemies_query.iter() 
     {
-        // from 10
-        if enemy_component.is_from_10 {
-            let room = &mut maze.rooms[enemy_component.room_seq];
-                        
-            if collision_check(player_transform.translation, Vec2::new(PLAYER_W, PLAYER_H), 
-                enemy_transform.translation, ENEMY_NN_SIZE) 
-            {
-                for (idx, mut enemy) in room.enemies_from_10.iter_mut().enumerate() {
-                    if enemy.enemy_seq == enemy_component.enemy_seq {
-                        taking_damage = true;
-                        player.health -= 10.0;
-                        enemy.health -= 10;
-                        if enemy.health <= 0 {
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            room.enemies_from_10.remove(idx);
-                            something_died = true;
-                            maze.score += 100;
-                            break;
-                        }
-                        else if !dmg_sound_started && !sfx_dmg.is_playing_sound() {
-                            println!("DAMAGE: from 10: {}", enemy.enemy_type);
-                            sfx_dmg.play(sounds.damage.clone()).looped();
-                            dmg_sound_started = true;
-                        }
-                    }
-                }
-            }
-        }
-        else {
-            let mut tmp_score: u16 = 0;
-            {
-                let enemy = &mut maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
-
-                // 20 + fellow
-                if enemy.enemy_type == 20 {
-                    let mut fellow_died = false;
-                    let mut carrier_died = false;
-
-                    if enemy.fellow_enemy.is_some() {
-                        let fellow = enemy.fellow_enemy.as_mut().unwrap();
-
-                        // prepare fellow size and position
-                        let mut fellow_size = ENEMY_NN_SIZE;
-                        if fellow.enemy_type == 7 {
-                            fellow_size = ENEMY_07_SIZE;
-                        }
-                        let fellow_pos = Vec3::new(
-                            enemy_transform.translation.x, 
-                            enemy_transform.translation.y + (ENEMY_NN_SIZE.y / 2.0) + (fellow_size.y / 2.0),
-                            0.0);
-                        
-                        // check collision with fellow
-                        if collision_check(player_transform.translation, Vec2::new(PLAYER_W, PLAYER_H), fellow_pos, fellow_size) 
-                        {
-                            taking_damage = true;
-                            player.health -= 10.0;
-                            fellow.health -= 10;
-                            if fellow.health <= 0 {
-                                crate::explosions::spawn_boom(&mut commands, 
-                                    Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                                fellow_died = true;
-                                something_died = true;
-                                tmp_score += 100;
-                            }
-                            else if !dmg_sound_started && !sfx_dmg.is_playing_sound() {
-                                println!("DAMAGE: fellow: {}", enemy.enemy_type);
-                                sfx_dmg.play(sounds.damage.clone()).looped();
-                                dmg_sound_started = true;
-                            }
-                        }
-                    }
-                    else if enemy.fellow_item.is_some() {
-                        let fellow = enemy.fellow_item.as_mut().unwrap();
-
-                        // check collision with fellow item
-                        if collision_check(player_transform.translation, Vec2::new(PLAYER_W, PLAYER_H), 
-                            Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), 
-                            Vec2::new(ITEM_W, ITEM_H)) 
-                        {
-                            sfx.play(sounds.get_item.clone());
-
-                            match fellow.item_type {
-                                0 => player.ammo = AMMO_MAX,
-                                1 => {
-                                    player.special_type = SpecialType::Ball;
-                                    player.ammo_special = 10;
-                                },
-                                2 => player.fuel = FUEL_MAX,
-                                3 => {
-                                    player.special_type = SpecialType::MissileDown;
-                                    player.ammo_special = 50;
-                                },
-                                4 => {
-                                    player.special_type = SpecialType::MissileSide;
-                                    player.ammo_special = 50;
-                                },
-                                5 => player.health = HEALTH_MAX,
-                                6 => { }, // star
-                                7 => { }, // random
-                                _ => panic!("Unexpected special type!")
-                            }
+        let hits = enemy_hits(player_transform.translation, Vec2::new(PLAYER_W, PLAYER_H), 
+            enemy_component, enemy_transform.translation, &maze, &enemy_defs);
 
-                            change_special_event.send(SpecialChange(player.special_type));
-                            change_special_ammo_event.send(SpecialAmmoChange(player.ammo_special));
-
-                            fellow.collected = true;
-                            fellow_died = true;
-                        }
+        for part in hits {
+            let room = &mut maze.rooms[enemy_component.room_seq];
+            match part {
+                HitPart::Enemy => {
+                    player.health -= enemy_defs.def(enemy_component.enemy_type).damage;
+                },
+                HitPart::FellowEnemy => {
+                    if let Some(fellow) = &room.enemies[enemy_component.enemy_seq].fellow_enemy {
+                        player.health -= enemy_defs.def(fellow.enemy_type).damage;
                     }
-
-                    // check carrier
-                    if collision_check(player_transform.translation, Vec2::new(PLAYER_W, PLAYER_H), 
-                        enemy_transform.translation, ENEMY_NN_SIZE) 
-                    {
-                        taking_damage = true;
-                        player.health -= 10.0;
-                        enemy.health -= 10;
-                        if enemy.health <= 0 {
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            carrier_died = true;
-                            something_died = true;
-                            tmp_score += 100;
+                },
+                HitPart::FellowItem => {
+                    if let Some(fellow) = room.enemies[enemy_component.enemy_seq].fellow_item.as_mut() {
+                        sfx.play(sounds.get_item.clone());
+
+                        match fellow.item_type {
+                            0 => player.ammo = AMMO_MAX,
+                            1 => {
+                                player.special_type = SpecialType::Ball;
+                                player.ammo_special = 10;
+                            },
+                            2 => player.fuel = FUEL_MAX,
+                            3 => {
+                                player.special_type = SpecialType::MissileDown;
+                                player.ammo_special = 50;
+                            },
+                            4 => {
+                                player.special_type = SpecialType::MissileSide;
+                                player.ammo_special = 50;
+                            },
+                            5 => player.health = HEALTH_MAX,
+                            6 => { }, // star
+                            7 => { }, // random
+                            8 => {
+                                player.add_life();
+                                change_lives_event.send(LivesChange(player.lives));
+                            },
+                            _ => panic!("Unexpected special type!")
                         }
-                        else if !dmg_sound_started && !sfx_dmg.is_playing_sound() {
-                            println!("DAMAGE: carrier");
-                            sfx_dmg.play(sounds.damage.clone()).looped();
-                            dmg_sound_started = true;
-                        }
-                    }
 
-                    if fellow_died {
-                        // destroy also carrier
-                        enemy.health = 0;
-                        crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                        sfx.play(sounds.boom.clone());
-                        commands.entity(enemy_entity).despawn_recursive();
-                    }
+                        change_special_event.send(SpecialChange(player.special_type));
+                        change_special_ammo_event.send(SpecialAmmoChange(player.ammo_special));
 
-                    if carrier_died {
-                        // destroy also fellow
-                        if enemy.fellow_enemy.is_some() {
-                            let fellow = enemy.fellow_enemy.as_mut().unwrap();
-                            fellow.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, 
-                                Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
+                        fellow.collected = true;
                     }
-                }
-                // others
-                else {
-                    if collision_check(
-                        player_transform.translation, Vec2::new(PLAYER_W, PLAYER_H), 
-                        enemy_transform.translation, ENEMY_NN_SIZE) 
-                    {
-                        taking_damage = true;
-                        player.health -= 10.0;
-                        enemy.health -= 10;
-                        if enemy.health <= 0 {
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            if enemy.enemy_type == 0 {
-                                crate::explosions::spawn_flash(&mut commands);
-                                sfx.play(sounds.boom_base.clone());
-                                maze.score += 1000;
-                                maze.bases -= 1;
-                            }
-                            else {
-                                sfx.play(sounds.boom.clone());
-                                maze.score += 100;
-                            }
-                            something_died = true;
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-                        else if !dmg_sound_started && !sfx_dmg.is_playing_sound() {
-                            println!("DAMAGE: {}", enemy.enemy_type);
-                            sfx_dmg.play(sounds.damage.clone()).looped();
-                            dmg_sound_started = true;
-                        }
-                    }
-                }
+                    // carrier goes down with the item
+                    damage_events.send(DamageEvent { entity: enemy_entity, part, amount: INSTANT_KILL, hit_sound: false });
+                    continue;
+                },
             }
-            maze.score += tmp_score;
+            taking_damage = true;
+            damage_events.send(DamageEvent { entity: enemy_entity, part, amount: CONTACT_DAMAGE, hit_sound: false });
         }
     }
 
-    if something_died {
-        change_score_event.send(ScoreChange(maze.score));
-        change_base_count_event.send(BaseCountChange(maze.bases));
+    if taking_damage {
+        if !sfx_dmg.is_playing_sound() {
+            sfx_dmg.play(sounds.damage.clone()).looped();
+        }
     }
-
-    if !taking_damage {
+    else {
         sfx_dmg.stop();
     }
 
@@ -1077,18 +906,64 @@ fn player_vs_enemy(
 
 }
 
-pub fn check_player_status(mut player: ResMut<Player>, mut state: ResMut<State<GameState>>) {
+pub fn check_player_status(
+    mut player: ResMut<Player>,
+    mut player_query: Query<(&mut Transform, &mut Sprite), With<PlayerComponent>>,
+    mut change_room_event: EventWriter<RoomChangeEvent>,
+    mut change_lives_event: EventWriter<LivesChange>,
+    settings: Res<Settings>,
+    mut step: ResMut<GameStep>,
+    mut state: ResMut<State<GameState>>)
+{
     if !player.is_dead {
-        if player.fuel < 0.0 {
-            player.health -= 0.5;
-        }
         if player.health < 0.0 {
             state.push(GameState::Death).expect("PLAYER: Failed to push Death state!");
+            step.stop();
             return;
         }
     }
+    else if player.lives > 0 {
+        player.respawn(settings.respawn_invulnerability);
+        println!("Respawn, lives left: {}", player.lives);
+        change_lives_event.send(LivesChange(player.lives));
+
+        for (mut transform, mut sprite) in player_query.iter_mut() {
+            transform.translation.x = player.posx;
+            transform.translation.y = player.posy;
+            sprite.color = Color::WHITE;
+        }
+
+        // the room is entered again: killed enemies stay dead, the rest stay where they are,
+        // enemy shots, explosions and shooting cooldowns are reset
+        let (row, col) = player.current_room;
+        player.changing_room = true;
+        change_room_event.send(RoomChangeEvent { row, col });
+    }
     else {
         // state.set(GameState::Menu).expect("PLAYER: Failed to change state!");
         state.set(GameState::GameOver).expect("PLAYER: Failed to change state!");
+        step.stop();
+    }
+}
+
+fn out_of_fuel(mut player: ResMut<Player>) {
+    if !player.is_dead && player.fuel < 0.0 {
+        player.health -= NO_FUEL_DAMAGE * TIME_STEP;
+    }
+}
+
+// ship blinks while it can not be damaged
+fn player_invulnerability(
+    mut player: ResMut<Player>,
+    mut player_query: Query<&mut Visibility, With<PlayerComponent>>)
+{
+    if player.invulnerable <= 0.0 {
+        return;
+    }
+
+    player.invulnerable -= TIME_STEP;
+    let visible = player.invulnerable <= 0.0 || (player.invulnerable / INVULNERABILITY_BLINK) as u32 % 2 == 0;
+    for mut visibility in player_query.iter_mut() {
+        visibility.is_visible = visible;
     }
 }
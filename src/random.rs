@@ -0,0 +1,62 @@
+// One seeded random generator for the whole game, so that a run can be repeated.
+// The seed is picked at every new game, or given on the command line: --seed <number>.
+// The gameplay draws from its own stream in the fixed steps, the effects (explosion fragments,
+// colours of the info bar) from another one, so the frame rate does not change the game.
+
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+pub const SEED_ARG: &str = "--seed";
+
+pub struct RandomPlugin;
+
+impl Plugin for RandomPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::new(seed_from_args()));
+    }
+}
+
+pub struct GameRng {
+    seed: u64,
+    // seed from the command line, every new game replays it
+    fixed: bool,
+    // systems drawing from it in the same step are ordered, see EnemiesPlugin
+    pub game: StdRng,
+    pub effects: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: Option<u64>) -> Self {
+        let fixed = seed.is_some();
+        let seed = seed.unwrap_or_else(rand::random);
+        GameRng {
+            seed,
+            fixed,
+            game: StdRng::seed_from_u64(seed),
+            effects: StdRng::seed_from_u64(seed.wrapping_add(1)),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // both streams start again, from a new seed unless it was given on the command line
+    pub fn new_game(&mut self) {
+        *self = GameRng::new(if self.fixed { Some(self.seed) } else { None });
+        println!("Random seed: {}", self.seed);
+    }
+}
+
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args().skip_while(|arg| arg != SEED_ARG).skip(1);
+    let value = args.next()?;
+    match value.parse() {
+        Ok(seed) => Some(seed),
+        Err(_) => {
+            println!("Invalid seed: {}, a random one is used", value);
+            None
+        }
+    }
+}
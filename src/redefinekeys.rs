@@ -1,140 +1,279 @@
 use bevy::prelude::*;
 
 use crate::{GameState, WINDOW_W2, mainmenu::UiAssets, WINDOW_H, GameKeys};
+use crate::settings::{key_code, pad_button};
+use crate::gamepad::PadInput;
+use crate::actions::{ActionInput, ActionBindings, GameAction, PhysicalInput};
 
 pub struct RedefineKeysPlugin;
 
 impl Plugin for RedefineKeysPlugin {
     fn build(&self, app: &mut App) {
         app
+        .insert_resource(RedefineKeys { keys: GameKeys::default(), selected: 0, waiting: false, message: String::new(), redraw: false })
         .add_system_set(SystemSet::on_enter(GameState::RedefineKeys).with_system(spawn_redefine_keys))
-        .add_system_set(SystemSet::on_update(GameState::RedefineKeys).with_system(victory_keyboard_input))
+        .add_system_set(SystemSet::on_update(GameState::RedefineKeys)
+            .with_system(redefine_keyboard_input).label("RedefineInputSystem")
+            .with_system(redraw_redefine_keys).after("RedefineInputSystem"))
         .add_system_set(SystemSet::on_exit(GameState::RedefineKeys).with_system(despawn_redefine_keys))
         ;
     }
 }
 
-#[derive(Component)]
-pub struct RedefineKeysText(u8);
+// keys and gamepad buttons are edited on a copy, GameKeys is changed only when the list is saved
+pub struct RedefineKeys {
+    keys: GameKeys,
+    selected: usize,
+    // next key or gamepad button pressed is bound to the selected action
+    waiting: bool,
+    message: String,
+    redraw: bool,
+}
 
 #[derive(Component)]
-pub struct RedefineSelectedKeyText;
+pub struct RedefineKeysText;
 
 pub const REDEFINE_FONT_SIZE: f32 = 24.0;
 pub const Y_POS: f32 = WINDOW_H - 80.0;
+pub const LINE_H: f32 = REDEFINE_FONT_SIZE * 1.6;
+
+const ACTIONS: [&str; 6] = ["UP", "DOWN", "LEFT", "RIGHT", "FIRE", "PAUSE"];
+const ROW_DEFAULTS: usize = ACTIONS.len();
+const ROW_SAVE: usize = ACTIONS.len() + 1;
+const ROW_CANCEL: usize = ACTIONS.len() + 2;
+const ROW_COUNT: usize = ACTIONS.len() + 3;
+
+const ACTION_PAUSE: usize = 5;
+// fixed inputs of the menus, see ActionBindings: PAUSE works in the pause menu too, so it can not share them
+const MENU_ACTIONS: [GameAction; 3] = [GameAction::Confirm, GameAction::MenuUp, GameAction::MenuDown];
+
+// the inputs of GameAction::Back always go back, no action can have them
+fn is_back(bindings: &ActionBindings, input: PhysicalInput) -> bool {
+    bindings.inputs(GameAction::Back).contains(&input)
+}
+
+fn is_reserved(bindings: &ActionBindings, action: usize, input: PhysicalInput) -> bool {
+    action == ACTION_PAUSE && MENU_ACTIONS.iter().any(|menu_action| bindings.inputs(*menu_action).contains(&input))
+}
 
-fn spawn_redefine_text(commands: &mut Commands, ui_assets: &Res<UiAssets>, index: u8, text: String, voffset: f32, alignment: TextAlignment) {
+fn action_key(keys: &GameKeys, action: usize) -> KeyCode {
+    match action {
+        0 => keys.up,
+        1 => keys.down,
+        2 => keys.left,
+        3 => keys.right,
+        4 => keys.fire,
+        5 => keys.pause,
+        _ => panic!("REDEFINE: Unexpected action!")
+    }
+}
+
+fn set_action_key(keys: &mut GameKeys, action: usize, key: KeyCode) {
+    match action {
+        0 => keys.up = key,
+        1 => keys.down = key,
+        2 => keys.left = key,
+        3 => keys.right = key,
+        4 => keys.fire = key,
+        5 => keys.pause = key,
+        _ => panic!("REDEFINE: Unexpected action!")
+    }
+}
+
+fn action_button(keys: &GameKeys, action: usize) -> GamepadButtonType {
+    match action {
+        0 => keys.pad_up,
+        1 => keys.pad_down,
+        2 => keys.pad_left,
+        3 => keys.pad_right,
+        4 => keys.pad_fire,
+        5 => keys.pad_pause,
+        _ => panic!("REDEFINE: Unexpected action!")
+    }
+}
+
+fn set_action_button(keys: &mut GameKeys, action: usize, button: GamepadButtonType) {
+    match action {
+        0 => keys.pad_up = button,
+        1 => keys.pad_down = button,
+        2 => keys.pad_left = button,
+        3 => keys.pad_right = button,
+        4 => keys.pad_fire = button,
+        5 => keys.pad_pause = button,
+        _ => panic!("REDEFINE: Unexpected action!")
+    }
+}
+
+fn spawn_redefine_text(commands: &mut Commands, ui_assets: &Res<UiAssets>, text: String, x: f32, y: f32, color: Color, alignment: TextAlignment) {
     commands.spawn_bundle(Text2dBundle {
         text: Text::from_section(
-            text, 
-            TextStyle { font: ui_assets.font.clone(), font_size: REDEFINE_FONT_SIZE, color: Color::rgb(0.9, 0.9, 0.9)  }
+            text,
+            TextStyle { font: ui_assets.font.clone(), font_size: REDEFINE_FONT_SIZE, color }
         ).with_alignment(alignment),
-        transform: Transform::from_xyz(WINDOW_W2, Y_POS + voffset, 0.0),
+        // above the pause overlay, the keys can be redefined from the pause menu
+        transform: Transform::from_xyz(x, y, 950.0),
         ..Default::default()
-    }).insert(RedefineKeysText(index));
+    }).insert(RedefineKeysText);
 }
 
-fn spawn_redefine_selected_text(commands: &mut Commands, ui_assets: &Res<UiAssets>, text: String, voffset: f32) {
-    commands.spawn_bundle(Text2dBundle {
-        text: Text::from_section(
-            text, 
-            TextStyle { font: ui_assets.font.clone(), font_size: REDEFINE_FONT_SIZE, color: Color::CYAN }
-        ).with_alignment(TextAlignment::CENTER_LEFT),
-        transform: Transform::from_xyz(WINDOW_W2, Y_POS + voffset, 0.0),
-        ..Default::default()
-    }).insert(RedefineSelectedKeyText);
+fn spawn_redefine_list(commands: &mut Commands, ui_assets: &Res<UiAssets>, redefine: &RedefineKeys) {
+    let normal = Color::rgb(0.9, 0.9, 0.9);
+
+    for row in 0..ROW_COUNT {
+        let y = Y_POS - row as f32 * LINE_H - if row >= ROW_DEFAULTS { LINE_H } else { 0.0 };
+        let selected = row == redefine.selected;
+        let color = if selected { Color::CYAN } else { normal };
+
+        if row < ACTIONS.len() {
+            spawn_redefine_text(commands, ui_assets, format!("{}: ", ACTIONS[row]), WINDOW_W2, y, color, TextAlignment::CENTER_RIGHT);
+            let key = if selected && redefine.waiting {
+                String::from("PRESS A KEY OR BUTTON...")
+            }
+            else {
+                format!("{} / PAD {}",
+                    key_code::name(action_key(&redefine.keys, row)).to_uppercase(),
+                    pad_button::name(action_button(&redefine.keys, row)).to_uppercase())
+            };
+            spawn_redefine_text(commands, ui_assets, key, WINDOW_W2, y, color, TextAlignment::CENTER_LEFT);
+        }
+        else {
+            let text = match row {
+                ROW_DEFAULTS => "Reset to defaults",
+                ROW_SAVE => "Save and return",
+                _ => "Cancel",
+            };
+            spawn_redefine_text(commands, ui_assets, String::from(text), WINDOW_W2, y, color, TextAlignment::CENTER);
+        }
+    }
+
+    let help = if redefine.waiting { "ESC / PAD SELECT - keep the current key" } else { "UP/DOWN - select, ENTER / PAD SOUTH - change, ESC / PAD SELECT - cancel" };
+    let y = Y_POS - (ROW_COUNT + 2) as f32 * LINE_H;
+    spawn_redefine_text(commands, ui_assets, String::from(help), WINDOW_W2, y, normal, TextAlignment::CENTER);
+    spawn_redefine_text(commands, ui_assets, redefine.message.clone(), WINDOW_W2, y - LINE_H, Color::RED, TextAlignment::CENTER);
 }
 
-fn spawn_redefine_keys(mut commands: Commands, ui_assets: Res<UiAssets>) {
-    spawn_redefine_text(&mut commands, &ui_assets, 1, String::from("Press key for UP: "), 0.0, TextAlignment::CENTER_RIGHT);
+fn spawn_redefine_keys(mut commands: Commands, ui_assets: Res<UiAssets>, game_input: Res<GameKeys>, mut redefine: ResMut<RedefineKeys>) {
+    redefine.keys = *game_input;
+    redefine.selected = 0;
+    redefine.waiting = false;
+    redefine.message.clear();
+    redefine.redraw = false;
+    spawn_redefine_list(&mut commands, &ui_assets, &redefine);
 }
 
-fn despawn_redefine_keys(
-    mut commands: Commands, 
-    text_query: Query<Entity, With<RedefineKeysText>>,
-    selection_query: Query<Entity, With<RedefineSelectedKeyText>>) 
-{
+fn despawn_redefine_keys(mut commands: Commands, text_query: Query<Entity, With<RedefineKeysText>>) {
     for entity in text_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
-    for entity in selection_query.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
 }
 
-fn victory_keyboard_input(
-    mut commands: Commands, 
-    mut keyboard: ResMut<Input<KeyCode>>, 
-    text_query: Query<&RedefineKeysText>, 
+fn redraw_redefine_keys(
+    mut commands: Commands,
     ui_assets: Res<UiAssets>,
+    text_query: Query<Entity, With<RedefineKeysText>>,
+    mut redefine: ResMut<RedefineKeys>)
+{
+    if redefine.redraw {
+        redefine.redraw = false;
+        for entity in text_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_redefine_list(&mut commands, &ui_assets, &redefine);
+    }
+}
+
+fn redefine_keyboard_input(
+    mut keyboard: ResMut<Input<KeyCode>>,
+    pad: Res<PadInput>,
+    mut actions: ResMut<ActionInput>,
+    mut redefine: ResMut<RedefineKeys>,
     mut game_input: ResMut<GameKeys>,
-    mut state: ResMut<State<GameState>>) 
+    bindings: Res<ActionBindings>,
+    mut state: ResMut<State<GameState>>)
 {
-    if keyboard.get_just_pressed().len() == 1 {
-        let key_opt = keyboard.get_just_pressed().next();
-        if key_opt.is_some() {
-            let key = key_opt.unwrap().clone();
-            let key_name = format!("{:#?}", key);
-            
-            keyboard.clear();
-
-            let cnt = text_query.iter().count();
-            // println!("text_query cnt = {}", cnt);
-
-            if cnt == 1 {
-                game_input.up = key;
-                for text_component in text_query.iter() {
-                    if text_component.0 == 1 {
-                        spawn_redefine_selected_text(&mut commands, &ui_assets, key_name.to_uppercase(), 0.0);
-                    }
-                }
-                spawn_redefine_text(&mut commands, &ui_assets, 2, String::from("Press key for DOWN: "), 
-                    -(REDEFINE_FONT_SIZE*2.0), TextAlignment::CENTER_RIGHT);
+    let key_opt = keyboard.get_just_pressed().next().copied();
+    let confirm = actions.just_pressed(GameAction::Confirm);
+    let back = actions.just_pressed(GameAction::Back);
+    let menu_up = actions.just_pressed(GameAction::MenuUp);
+    let menu_down = actions.just_pressed(GameAction::MenuDown);
+    // the stick moves through the list, but it can not be bound
+    let pad_used = pad.any_button.is_some() || menu_up || menu_down;
+    if key_opt.is_none() && (!pad_used || (redefine.waiting && pad.any_button.is_none())) {
+        return;
+    }
+    keyboard.clear();
+    actions.clear();
+    redefine.redraw = true;
+    redefine.message.clear();
+
+    if redefine.waiting {
+        redefine.waiting = false;
+        let action = redefine.selected;
+
+        // a Back input keeps the current binding
+        if let Some(key) = key_opt {
+            if is_back(&bindings, PhysicalInput::Key(key)) {
+                return;
             }
-            else if cnt == 2 {
-                game_input.down = key;
-                for text_component in text_query.iter() {
-                    if text_component.0 == 2 {
-                        spawn_redefine_selected_text(&mut commands, &ui_assets, key_name.to_uppercase(), -(REDEFINE_FONT_SIZE*2.0));
-                    }
-                }
-                spawn_redefine_text(&mut commands, &ui_assets, 3, String::from("Press key for LEFT: "), 
-                    -(REDEFINE_FONT_SIZE*4.0), TextAlignment::CENTER_RIGHT);
+            if is_reserved(&bindings, action, PhysicalInput::Key(key)) {
+                redefine.message = format!("{} is used in the menus, {} can not have it", key_code::name(key).to_uppercase(), ACTIONS[action]);
+                return;
             }
-            else if cnt == 3 {
-                game_input.left = key;
-                for text_component in text_query.iter() {
-                    if text_component.0 == 3 {
-                        spawn_redefine_selected_text(&mut commands, &ui_assets, key_name.to_uppercase(), -(REDEFINE_FONT_SIZE*4.0));
-                    }
-                }
-                spawn_redefine_text(&mut commands, &ui_assets, 4, String::from("Press key for RIGHT: "), 
-                    -(REDEFINE_FONT_SIZE*6.0), TextAlignment::CENTER_RIGHT);
+            let conflict = (0..ACTIONS.len()).find(|other| *other != action && action_key(&redefine.keys, *other) == key);
+            if let Some(other) = conflict {
+                redefine.message = format!("{} is already used for {}", key_code::name(key).to_uppercase(), ACTIONS[other]);
+                return;
             }
-            else if cnt == 4 {
-                game_input.right = key;
-                for text_component in text_query.iter() {
-                    if text_component.0 == 4 {
-                        spawn_redefine_selected_text(&mut commands, &ui_assets, key_name.to_uppercase(), -(REDEFINE_FONT_SIZE*6.0));
-                    }
-                }
-                spawn_redefine_text(&mut commands, &ui_assets, 5, String::from("Press key for FIRE: "), 
-                    -(REDEFINE_FONT_SIZE*8.0), TextAlignment::CENTER_RIGHT);
+            set_action_key(&mut redefine.keys, action, key);
+        }
+        else if let Some(button) = pad.any_button {
+            if is_back(&bindings, PhysicalInput::PadButton(button)) {
+                return;
             }
-            else if cnt == 5 {
-                game_input.fire = key;
-                for text_component in text_query.iter() {
-                    if text_component.0 == 5 {
-                        spawn_redefine_selected_text(&mut commands, &ui_assets, key_name.to_uppercase(), -(REDEFINE_FONT_SIZE*8.0));
-                    }
-                }
-                spawn_redefine_text(&mut commands, &ui_assets, 6, String::from("Press any key to return to main menu..."), 
-                    -(REDEFINE_FONT_SIZE*12.0), TextAlignment::CENTER);
+            if is_reserved(&bindings, action, PhysicalInput::PadButton(button)) {
+                redefine.message = format!("PAD {} is used in the menus, {} can not have it", pad_button::name(button).to_uppercase(), ACTIONS[action]);
+                return;
             }
-            else {
-                // state.set(GameState::Menu).expect("REDEFINE: Failed to change state!");
-                state.pop().expect("REDEFINE: Failed to pop state!");
+            let conflict = (0..ACTIONS.len()).find(|other| *other != action && action_button(&redefine.keys, *other) == button);
+            if let Some(other) = conflict {
+                redefine.message = format!("PAD {} is already used for {}", pad_button::name(button).to_uppercase(), ACTIONS[other]);
+                return;
             }
+            set_action_button(&mut redefine.keys, action, button);
+        }
+        // next action, so that all keys can be redefined in a row
+        if redefine.selected + 1 < ACTIONS.len() {
+            redefine.selected += 1;
         }
+        return;
+    }
+
+    if back {
+        // edited keys are dropped, GameKeys were not touched
+        redefine.redraw = false;
+        state.pop().expect("REDEFINE: Failed to pop state!");
+    }
+    else if confirm {
+        match redefine.selected {
+            ROW_DEFAULTS => redefine.keys = GameKeys::default(),
+            ROW_SAVE => {
+                if *game_input != redefine.keys {
+                    *game_input = redefine.keys;
+                }
+                redefine.redraw = false;
+                state.pop().expect("REDEFINE: Failed to pop state!");
+            },
+            ROW_CANCEL => {
+                redefine.redraw = false;
+                state.pop().expect("REDEFINE: Failed to pop state!");
+            },
+            _ => redefine.waiting = true,
+        }
+    }
+    else if menu_up {
+        redefine.selected = (redefine.selected + ROW_COUNT - 1) % ROW_COUNT;
+    }
+    else if menu_down {
+        redefine.selected = (redefine.selected + 1) % ROW_COUNT;
     }
 }
@@ -0,0 +1,234 @@
+// Saved games: the whole maze state (enemies, items, score, bases) and the player, one RON file per slot.
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::maze::Maze;
+use crate::enemies::EnemyDefs;
+use crate::mazefile::{MazeLoadError, EdgePolicy, read_ron, ITEM_TYPE_MAX};
+use crate::player::Player;
+
+pub const SAVE_DIR: &str = "saves";
+pub const SAVE_SLOTS: usize = 4;
+// increase when Maze or Player change so that older saves can not be read
+pub const SAVE_VERSION: u32 = 4;
+// file name of the load errors of the checkpoint, it is not written to a file
+pub const CHECKPOINT: &str = "checkpoint";
+
+// shown in the slot list without loading the whole game
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveInfo {
+    pub room: (usize, usize),
+    pub score: u16,
+    pub bases: u8,
+    pub bases_total: u8,
+    pub playtime: f32,
+    // seconds since 1970-01-01 UTC
+    pub saved_at: u64,
+}
+
+#[derive(Serialize)]
+struct SaveGameRef<'a> {
+    version: u32,
+    info: SaveInfo,
+    maze: &'a Maze,
+    player: &'a Player,
+}
+
+#[derive(Deserialize)]
+struct SaveGame {
+    maze: Maze,
+    player: Player,
+}
+
+// read first, the rest of the file may not match the current format
+#[derive(Deserialize)]
+struct SaveVersion {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct SaveHeader {
+    info: SaveInfo,
+}
+
+// slots are numbered from 1
+pub fn slot_file(slot: usize) -> String {
+    format!("{}/slot_{}.ron", SAVE_DIR, slot)
+}
+
+pub fn save_exists(file_name: &str) -> bool {
+    std::path::Path::new(file_name).is_file()
+}
+
+pub fn any_save_exists() -> bool {
+    (1..=SAVE_SLOTS).any(|slot| save_exists(&slot_file(slot)))
+}
+
+fn check_version(file_name: &str) -> Result<(), MazeLoadError> {
+    let version: SaveVersion = read_ron(file_name, "save")?;
+    if version.version != SAVE_VERSION {
+        return Err(MazeLoadError::new(file_name, 0, "version",
+            format!("save version {}, found {}", SAVE_VERSION, version.version).as_str()));
+    }
+    Ok(())
+}
+
+pub fn read_save_info(file_name: &str) -> Result<SaveInfo, MazeLoadError> {
+    check_version(file_name)?;
+    let header: SaveHeader = read_ron(file_name, "save")?;
+    Ok(header.info)
+}
+
+pub fn save_game(file_name: &str, maze: &Maze, player: &Player) -> std::io::Result<()> {
+    if let Some(dir) = std::path::Path::new(file_name).parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let save = SaveGameRef { version: SAVE_VERSION, info: save_info(maze), maze, player };
+    let config = ron::ser::PrettyConfig::new().depth_limit(3);
+    let text = ron::ser::to_string_pretty(&save, config).expect("Failed to serialize saved game!");
+    std::fs::write(file_name, text)
+}
+
+fn save_info(maze: &Maze) -> SaveInfo {
+    let cols = maze.grid.cols.max(1);
+    SaveInfo {
+        room: (maze.current_room / cols, maze.current_room % cols),
+        score: maze.score,
+        bases: maze.bases,
+        bases_total: maze.bases_total,
+        playtime: maze.playtime,
+        saved_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+    }
+}
+
+pub fn delete_save(file_name: &str) -> std::io::Result<()> {
+    std::fs::remove_file(file_name)
+}
+
+pub fn load_game(file_name: &str, maze: &mut Maze, player: &mut Player, enemy_defs: &EnemyDefs) -> Result<(), MazeLoadError> {
+    check_version(file_name)?;
+
+    let save: SaveGame = read_ron(file_name, "save")?;
+    check_save(file_name, &save, enemy_defs)?;
+
+    restore_game(save, maze, player);
+
+    println!("Game loaded: {}", file_name);
+    Ok(())
+}
+
+// a file edited by hand or cut short must not make the game index outside the maze
+fn check_save(file_name: &str, save: &SaveGame, enemy_defs: &EnemyDefs) -> Result<(), MazeLoadError> {
+    let maze = &save.maze;
+    let grid = &maze.grid;
+    if grid.rows.checked_mul(grid.cols) != Some(maze.rooms.len()) {
+        return Err(MazeLoadError::new(file_name, 0, "grid",
+            format!("rows x cols = {} rooms", maze.rooms.len()).as_str()));
+    }
+    if grid.edges == EdgePolicy::Exit && grid.room_index(grid.exit_room.0, grid.exit_room.1).is_none() {
+        return Err(MazeLoadError::new(file_name, 0, "exit_room", "room inside the maze"));
+    }
+    if maze.current_room >= maze.rooms.len() {
+        return Err(MazeLoadError::new(file_name, 0, "current_room", "room inside the maze"));
+    }
+    let (row, col) = save.player.current_room();
+    if grid.room_index(row, col) != Some(maze.current_room) {
+        return Err(MazeLoadError::new(file_name, 0, "player current_room", "same room as the maze current_room"));
+    }
+
+    for (room_seq, room) in maze.rooms.iter().enumerate() {
+        let enemies_ok = room.enemies.iter().enumerate()
+            .all(|(enemy_seq, enemy)| enemy.room_seq == room_seq && enemy.enemy_seq == enemy_seq && !enemy.is_from_10);
+        let spawned_ok = room.enemies_from_10.iter()
+            .all(|enemy| enemy.room_seq == room_seq && enemy.enemy_seq < room.from_10_seq && enemy.is_from_10);
+        let items_ok = room.items.iter().enumerate()
+            .all(|(item_seq, item)| item.room_seq == room_seq && item.item_seq == item_seq);
+        if !enemies_ok || !spawned_ok || !items_ok {
+            return Err(MazeLoadError::new(file_name, 0, "rooms",
+                format!("enemies and items of room {} numbered in their room", room_seq).as_str()));
+        }
+
+        let known_types = room.enemies.iter().chain(room.enemies_from_10.iter()).all(|enemy| {
+            enemy_defs.get(enemy.enemy_type).is_some()
+                && enemy.fellow_enemy.as_ref().is_none_or(|fellow| enemy_defs.get(fellow.enemy_type).is_some())
+                && enemy.fellow_item.as_ref().is_none_or(|fellow| fellow.item_type <= ITEM_TYPE_MAX)
+        }) && room.items.iter().all(|item| item.item_type <= ITEM_TYPE_MAX);
+        if !known_types {
+            return Err(MazeLoadError::new(file_name, 0, "rooms",
+                format!("known enemy and item types in room {}", room_seq).as_str()));
+        }
+    }
+    Ok(())
+}
+
+fn restore_game(save: SaveGame, maze: &mut Maze, player: &mut Player) {
+    *maze = save.maze;
+    maze.loaded = true;
+    maze.load_error = None;
+
+    *player = save.player;
+    // shots and room change in progress are not saved
+    player.shooting_cannon = false;
+    player.shooting_special = false;
+    player.changing_room = false;
+}
+
+// state at the last room entry, kept in memory only, a death can restart from here
+pub struct Checkpoint {
+    snapshot: Option<String>,
+}
+
+impl Checkpoint {
+    pub fn new() -> Self {
+        Checkpoint { snapshot: None }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.snapshot.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshot = None;
+    }
+
+    pub fn take(&mut self, maze: &Maze, player: &Player) {
+        let save = SaveGameRef { version: SAVE_VERSION, info: save_info(maze), maze, player };
+        self.snapshot = Some(ron::ser::to_string(&save).expect("Failed to serialize checkpoint!"));
+    }
+
+    pub fn restore(&self, maze: &mut Maze, player: &mut Player) -> Result<(), MazeLoadError> {
+        let text = self.snapshot.as_ref()
+            .ok_or_else(|| MazeLoadError::new(CHECKPOINT, 0, "checkpoint", "checkpoint taken at a room entry"))?;
+        let save: SaveGame = ron::from_str(text)
+            .map_err(|e| MazeLoadError::new(CHECKPOINT, 0, "checkpoint", &format!("readable checkpoint ({})", e)))?;
+        restore_game(save, maze, player);
+
+        println!("Checkpoint restored");
+        Ok(())
+    }
+}
+
+// h:mm:ss
+pub fn format_playtime(seconds: f32) -> String {
+    let seconds = seconds as u64;
+    format!("{}:{:0>2}:{:0>2}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+}
+
+// yyyy-mm-dd hh:mm UTC
+pub fn format_timestamp(seconds: u64) -> String {
+    // days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = seconds / 86400;
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{}-{:0>2}-{:0>2} {:0>2}:{:0>2} UTC", year, month, day, (seconds / 3600) % 24, (seconds / 60) % 60)
+}
@@ -0,0 +1,323 @@
+// User settings: key bindings, sound volumes, window placement and gameplay options.
+// Read in main() before the window is created, written back whenever they change.
+
+use bevy::prelude::*;
+use bevy::window::WindowMoved;
+use bevy_kira_audio::{AudioChannel, AudioControl};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::GameKeys;
+use crate::audio::{SfxChannel, DamageChannel, Shooting01Channel, Shooting05Channel, Shooting06Channel, Shooting08Channel, Shooting09Channel, DeathSoundChannel};
+use crate::mazefile::read_ron;
+use crate::player::{PLAYER_LIVES, LIVES_MAX, RESPAWN_INVULNERABILITY};
+
+pub const SETTINGS_DIR: &str = "rth-jet-story";
+pub const SETTINGS_FILE: &str = "settings.ron";
+
+pub const WINDOW_X: i32 = 150;
+pub const WINDOW_Y: i32 = 50;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system(track_window_position.label("UpdateSettingsSystem"))
+        .add_system(sync_keys.label("UpdateSettingsSystem"))
+        .add_system(apply_volumes)
+        .add_system(save_settings.after("UpdateSettingsSystem"));
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub keys: GameKeys,
+    // 0.0 - 1.0, effects are played at master * effects
+    pub master_volume: f64,
+    pub effects_volume: f64,
+    pub window_x: i32,
+    pub window_y: i32,
+    pub lives: u8,
+    // seconds
+    pub respawn_invulnerability: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            keys: GameKeys::default(),
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            window_x: WINDOW_X,
+            window_y: WINDOW_Y,
+            lives: PLAYER_LIVES,
+            respawn_invulnerability: RESPAWN_INVULNERABILITY,
+        }
+    }
+}
+
+impl Settings {
+    // values edited by hand may be out of range
+    fn sanitize(&mut self) {
+        self.master_volume = self.master_volume.clamp(0.0, 1.0);
+        self.effects_volume = self.effects_volume.clamp(0.0, 1.0);
+        self.lives = self.lives.clamp(1, LIVES_MAX);
+        self.respawn_invulnerability = self.respawn_invulnerability.max(0.0);
+    }
+}
+
+// platform config directory, the current directory if it is not known
+fn config_dir() -> Option<PathBuf> {
+    let env_dir = |name: &str| std::env::var_os(name).filter(|dir| !dir.is_empty()).map(PathBuf::from);
+
+    if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    }
+    else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library").join("Application Support"))
+    }
+    else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+    }
+}
+
+pub fn settings_file() -> PathBuf {
+    match config_dir() {
+        Some(dir) => dir.join(SETTINGS_DIR).join(SETTINGS_FILE),
+        None => PathBuf::from(SETTINGS_FILE),
+    }
+}
+
+// missing or broken file gives the defaults, a missing one is created
+pub fn load_settings() -> Settings {
+    let file = settings_file();
+    let file_name = file.to_string_lossy();
+
+    if !file.is_file() {
+        let settings = Settings::default();
+        if let Err(e) = write_settings(&settings) {
+            println!("Failed to write settings: {}: {}", file_name, e);
+        }
+        return settings;
+    }
+
+    match read_ron::<Settings>(&file_name, "settings") {
+        Ok(mut settings) => {
+            println!("Settings loaded: {}", file_name);
+            settings.sanitize();
+            settings
+        },
+        Err(error) => {
+            println!("Settings load error, using defaults: {}", error);
+            Settings::default()
+        }
+    }
+}
+
+pub fn write_settings(settings: &Settings) -> std::io::Result<()> {
+    let file = settings_file();
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let text = ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::new()).expect("Failed to serialize settings!");
+    std::fs::write(file, text)
+}
+
+// defaults for everything, the window is moved back too
+pub fn reset_settings(settings: &mut Settings, game_keys: &mut GameKeys, windows: &mut Windows) {
+    *settings = Settings::default();
+    *game_keys = settings.keys;
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_position(IVec2::new(settings.window_x, settings.window_y));
+    }
+    println!("Settings reset to defaults");
+}
+
+fn track_window_position(mut moved_events: EventReader<WindowMoved>, mut settings: ResMut<Settings>) {
+    if let Some(moved) = moved_events.iter().last() {
+        if settings.window_x != moved.position.x || settings.window_y != moved.position.y {
+            settings.window_x = moved.position.x;
+            settings.window_y = moved.position.y;
+        }
+    }
+}
+
+// keys are redefined through GameKeys, the settings keep a copy
+fn sync_keys(game_keys: Res<GameKeys>, mut settings: ResMut<Settings>) {
+    if game_keys.is_changed() && settings.keys != *game_keys {
+        settings.keys = *game_keys;
+    }
+}
+
+fn apply_volumes(
+    settings: Res<Settings>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+    sfx_dmg: Res<AudioChannel<DamageChannel>>,
+    sfx01: Res<AudioChannel<Shooting01Channel>>,
+    sfx05: Res<AudioChannel<Shooting05Channel>>,
+    sfx06: Res<AudioChannel<Shooting06Channel>>,
+    sfx08: Res<AudioChannel<Shooting08Channel>>,
+    sfx09: Res<AudioChannel<Shooting09Channel>>,
+    sfx_death: Res<AudioChannel<DeathSoundChannel>>)
+{
+    if settings.is_changed() {
+        let volume = settings.master_volume * settings.effects_volume;
+        sfx.set_volume(volume);
+        sfx_dmg.set_volume(volume);
+        sfx01.set_volume(volume);
+        sfx05.set_volume(volume);
+        sfx06.set_volume(volume);
+        sfx08.set_volume(volume);
+        sfx09.set_volume(volume);
+        sfx_death.set_volume(volume);
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    // added means it was just read from the file
+    if settings.is_changed() && !settings.is_added() {
+        match write_settings(&settings) {
+            Ok(()) => println!("Settings saved: {}", settings_file().display()),
+            Err(e) => println!("Failed to write settings: {}: {}", settings_file().display(), e),
+        }
+    }
+}
+
+// bindings as read from the file, a missing or unknown name falls back to the default of its own binding
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct GameKeysNames {
+    up: String,
+    down: String,
+    left: String,
+    right: String,
+    fire: String,
+    pause: String,
+    pad_up: String,
+    pad_down: String,
+    pad_left: String,
+    pad_right: String,
+    pad_fire: String,
+    pad_pause: String,
+}
+
+fn key_or(name: String, default: KeyCode) -> KeyCode {
+    if name.is_empty() {
+        return default;
+    }
+    key_code::from_name(&name).unwrap_or_else(|| {
+        println!("Settings: unknown key {}, {} is used", name, key_code::name(default));
+        default
+    })
+}
+
+fn button_or(name: String, default: GamepadButtonType) -> GamepadButtonType {
+    if name.is_empty() {
+        return default;
+    }
+    pad_button::from_name(&name).unwrap_or_else(|| {
+        println!("Settings: unknown gamepad button {}, {} is used", name, pad_button::name(default));
+        default
+    })
+}
+
+impl From<GameKeysNames> for GameKeys {
+    fn from(names: GameKeysNames) -> Self {
+        let defaults = GameKeys::default();
+        GameKeys {
+            up: key_or(names.up, defaults.up),
+            down: key_or(names.down, defaults.down),
+            left: key_or(names.left, defaults.left),
+            right: key_or(names.right, defaults.right),
+            fire: key_or(names.fire, defaults.fire),
+            pause: key_or(names.pause, defaults.pause),
+            pad_up: button_or(names.pad_up, defaults.pad_up),
+            pad_down: button_or(names.pad_down, defaults.pad_down),
+            pad_left: button_or(names.pad_left, defaults.pad_left),
+            pad_right: button_or(names.pad_right, defaults.pad_right),
+            pad_fire: button_or(names.pad_fire, defaults.pad_fire),
+            pad_pause: button_or(names.pad_pause, defaults.pad_pause),
+        }
+    }
+}
+
+// KeyCode has no serde support without the bevy "serialize" feature, keys are stored by name
+pub mod key_code {
+    use bevy::prelude::KeyCode;
+    use serde::Serializer;
+
+    macro_rules! key_names {
+        ($($key:ident),* $(,)?) => {
+            const KEY_NAMES: &[(KeyCode, &str)] = &[$((KeyCode::$key, stringify!($key))),*];
+        };
+    }
+
+    // every KeyCode of bevy 0.8, any key the redefine screen accepts can be read back
+    key_names!(
+        Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+        Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+        Snapshot, Scroll, Pause, Insert, Home, Delete, End, PageDown, PageUp,
+        Left, Up, Right, Down, Back, Return, Space, Compose, Caret,
+        Numlock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+        NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide, NumpadDecimal, NumpadComma, NumpadEnter, NumpadEquals,
+        AbntC1, AbntC2, Apostrophe, Apps, Asterisk, Plus, At, Ax, Backslash, Calculator, Capital, Colon, Comma, Convert,
+        Equals, Grave, Kana, Kanji, Minus, Oem102, Period, Semicolon, Slash, Underline, Unlabeled, Yen,
+        LBracket, RBracket, LAlt, RAlt, LControl, RControl, LShift, RShift, LWin, RWin, Tab,
+        Mail, MediaSelect, MediaStop, Mute, MyComputer, NavigateForward, NavigateBackward, NextTrack, NoConvert,
+        PlayPause, Power, PrevTrack, Sleep, Stop, Sysrq, VolumeDown, VolumeUp, Wake,
+        WebBack, WebFavorites, WebForward, WebHome, WebRefresh, WebSearch, WebStop, Copy, Paste, Cut,
+    );
+
+    pub fn name(key: KeyCode) -> String {
+        match KEY_NAMES.iter().find(|(code, _)| *code == key) {
+            Some((_, name)) => String::from(*name),
+            None => format!("{:?}", key),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<KeyCode> {
+        KEY_NAMES.iter().find(|(_, key_name)| *key_name == name).map(|(code, _)| *code)
+    }
+
+    pub fn serialize<S: Serializer>(key: &KeyCode, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&name(*key))
+    }
+}
+
+// GamepadButtonType, same as KeyCode
+pub mod pad_button {
+    use bevy::prelude::GamepadButtonType;
+    use serde::Serializer;
+
+    macro_rules! button_names {
+        ($($button:ident),* $(,)?) => {
+            const BUTTON_NAMES: &[(GamepadButtonType, &str)] = &[$((GamepadButtonType::$button, stringify!($button))),*];
+        };
+    }
+
+    button_names!(
+        South, East, North, West, C, Z,
+        LeftTrigger, LeftTrigger2, RightTrigger, RightTrigger2,
+        Select, Start, Mode, LeftThumb, RightThumb,
+        DPadUp, DPadDown, DPadLeft, DPadRight,
+    );
+
+    pub fn name(button: GamepadButtonType) -> String {
+        match BUTTON_NAMES.iter().find(|(code, _)| *code == button) {
+            Some((_, name)) => String::from(*name),
+            None => format!("{:?}", button),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<GamepadButtonType> {
+        BUTTON_NAMES.iter().find(|(_, button_name)| *button_name == name).map(|(code, _)| *code)
+    }
+
+    pub fn serialize<S: Serializer>(button: &GamepadButtonType, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&name(*button))
+    }
+}
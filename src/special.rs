@@ -1,19 +1,22 @@
 use bevy::prelude::*;
 use bevy_kira_audio::{AudioControl, AudioChannel};
 use rand::Rng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 
-use crate::explosions::ExplosionsImages;
-use crate::infobar::{ScoreChange, BaseCountChange};
+use crate::damage::{DamageEvent, HitPart, INSTANT_KILL, enemy_hits};
 use crate::items::ITEM_COLORS;
 use crate::player::{Player, PlayerComponent};
 use crate::{WINDOW_W, WINDOW_H, INFO_BAR_H, GameState, collision_check, GameDirection};
-use crate::enemies::{EnemyComponent, ENEMY_NN_SIZE, ENEMY_07_SIZE};
+use crate::enemies::{EnemyDefs, EnemyComponent};
 use crate::maze::{WallComponent, Maze};
 use crate::audio::{Sounds, SfxChannel};
+use crate::loading::LoadingAssets;
+use crate::gamestep::{GAME_STEP, TIME_STEP};
 
 pub struct SpecialPlugin;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum SpecialType {
     Ball,
     MissileSide,
@@ -25,11 +28,11 @@ impl Plugin for SpecialPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_startup_system_to_stage(StartupStage::PreStartup, load_special_resources)
-        .add_system_set(SystemSet::on_update(GameState::Game)
-            .with_system(special_ball_movement)
-            .with_system(special_missile_side_movement)
-            .with_system(special_missile_down_movement)
-            .with_system(special_star_movement)
+        .add_system_set_to_stage(GAME_STEP, SystemSet::new()
+            .with_system(special_ball_movement.label("DamageSourceSystem"))
+            .with_system(special_missile_side_movement.label("DamageSourceSystem"))
+            .with_system(special_missile_down_movement.label("DamageSourceSystem"))
+            .with_system(special_star_movement.label("DamageSourceSystem"))
         )
         .add_system_set(SystemSet::on_exit(GameState::Game)
             .with_system(despawn_specials)
@@ -44,19 +47,20 @@ pub struct SpecialImages {
     pub star: Handle<Image>,
 }
 
-pub fn load_special_resources(mut commands: Commands, assets: Res<AssetServer>) {
+pub fn load_special_resources(mut commands: Commands, assets: Res<AssetServer>, mut loading: ResMut<LoadingAssets>) {
     commands.insert_resource(SpecialImages {
-        ball: assets.load("images/ship/ball.png"),
-        missile_side: assets.load("images/ship/missile_side.png"),
-        missile_down: assets.load("images/ship/missile_down.png"),
-        star: assets.load("images/ship/star.png"),
+        ball: loading.track(assets.load("images/ship/ball.png")),
+        missile_side: loading.track(assets.load("images/ship/missile_side.png")),
+        missile_down: loading.track(assets.load("images/ship/missile_down.png")),
+        star: loading.track(assets.load("images/ship/star.png")),
     });
 }
 
 #[derive(Component)]
 pub struct SpecialBall {
     pub velocity: Vec2,
-    pub duration: u16,
+    // seconds left
+    pub duration: f32,
     pub color_index: usize,
 }
 
@@ -74,9 +78,10 @@ pub struct SpecialMissileDown {
 #[derive(Component)]
 pub struct SpecialStar {
     pub velocity: Vec2,
-    pub duration: u16,
+    // seconds left
+    pub duration: f32,
     pub color_index: usize,
-    pub sound_delay: u8,
+    pub sound_delay: f32,
 }
 
 pub const BALL_SIZE: Vec2 = Vec2::new(22.0, 22.0);
@@ -94,6 +99,16 @@ pub const STAR_H2: f32 = STAR_SIZE.y / 2.0;
 pub const STAR_VELOCITY: f32 = 100.0;
 pub const STAR_FORCE: f32 = 400.0;
 
+// seconds
+pub const BALL_DURATION: f32 = 15.0;
+pub const STAR_DURATION: f32 = 18.0;
+// last seconds of the ball and the star, they blink and the ball slows down
+pub const SPECIAL_FADING: f32 = 6.0;
+// part of the ball velocity lost per second when fading
+pub const BALL_SLOWDOWN: f32 = 0.1;
+// seconds between two bounce sounds of the star
+pub const STAR_SOUND_DELAY: f32 = 0.1;
+
 pub fn spawn_special_ball(commands: &mut Commands, special_images: &Res<SpecialImages>, posx: f32, posy: f32, direction: GameDirection) {
     commands.spawn_bundle(SpriteBundle {
         texture: special_images.ball.clone(),
@@ -115,7 +130,7 @@ pub fn spawn_special_ball(commands: &mut Commands, special_images: &Res<SpecialI
             GameDirection::Right => Vec2::new(250.0, -300.0),
             GameDirection::None => panic!("Unexpected special direction!")
         },
-        duration: 15000,
+        duration: BALL_DURATION,
         color_index: 0,
     });
 }
@@ -169,9 +184,7 @@ pub fn spawn_special_missile_down(commands: &mut Commands, special_images: &Res<
     });
 }
 
-pub fn spawn_special_star(commands: &mut Commands, special_images: &Res<SpecialImages>, posx: f32, posy: f32) {
-    let mut rng = rand::thread_rng();
-
+pub fn spawn_special_star(commands: &mut Commands, special_images: &Res<SpecialImages>, rng: &mut StdRng, posx: f32, posy: f32) {
     let dir = rng.gen_range(0..=3);
 
     commands.spawn_bundle(SpriteBundle {
@@ -196,9 +209,9 @@ pub fn spawn_special_star(commands: &mut Commands, special_images: &Res<SpecialI
             3 => Vec2::new(STAR_VELOCITY, -STAR_VELOCITY),
             _ => panic!("Unexpected star direction!")
         },
-        duration: 18000,
+        duration: STAR_DURATION,
         color_index: 0,
-        sound_delay: 0,
+        sound_delay: 0.0,
     });
 }
 
@@ -225,23 +238,21 @@ pub fn despawn_specials(
 
 pub fn special_ball_movement(
     mut commands: Commands, 
-    time: Res<Time>, 
     mut special_query: Query<(Entity, &mut Sprite, &mut Transform, &mut SpecialBall)>,
     walls_query: Query<&WallComponent>,
-    mut enemies_query: Query<(Entity, &mut Transform, &mut EnemyComponent), Without<SpecialBall>>,
+    enemies_query: Query<(Entity, &Transform, &EnemyComponent), Without<SpecialBall>>,
     mut player: ResMut<Player>,
     sfx: Res<AudioChannel<SfxChannel>>,
     sounds: Res<Sounds>,
-    mut maze: ResMut<Maze>,
-    explosions_images: Res<ExplosionsImages>,
-    mut change_score_event: EventWriter<ScoreChange>,
-    mut change_base_count_event: EventWriter<BaseCountChange>) 
+    maze: Res<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    mut damage_events: EventWriter<DamageEvent>) 
 {
     for (special_entity, mut special_sprite, mut special_transform, mut ball) in special_query.iter_mut() {
         // apply force
         let mut position = Vec2::splat(0.0);
         position += ball.velocity;
-        position *= time.delta_seconds();
+        position *= TIME_STEP;
 
         // check collision with walls
 
@@ -297,145 +308,28 @@ pub fn special_ball_movement(
 
         // check collision with enemies
 
-        let mut something_died = false;
-        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter_mut() 
+        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter() 
         {
-            // from 10
-            if enemy_component.is_from_10 {
-                let room = &mut maze.rooms[enemy_component.room_seq];
-                            
-                if collision_check(special_transform.translation, BALL_SIZE, 
-                    enemy_transform.translation, ENEMY_NN_SIZE) 
-                {
-                    for (idx, mut enemy) in room.enemies_from_10.iter_mut().enumerate() {
-                        if enemy.enemy_seq == enemy_component.enemy_seq {
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            room.enemies_from_10.remove(idx);
-                            something_died = true;
-                            maze.score += 100;
-                            break;
-                        }
-                    }
-                }
-            }
-            else {
-                let mut tmp_score: u16 = 0;
-                {
-                    let enemy = &mut maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
-
-                    // 20 + fellow
-                    if enemy.enemy_type == 20 {
-                        let mut fellow_died = false;
-                        let mut carrier_died = false;
-
-                        if enemy.fellow_enemy.is_some() {
-                            let fellow = enemy.fellow_enemy.as_mut().unwrap();
-
-                            // prepare fellow size and position
-                            let mut fellow_size = ENEMY_NN_SIZE;
-                            if fellow.enemy_type == 7 {
-                                fellow_size = ENEMY_07_SIZE;
-                            }
-                            let fellow_pos = Vec3::new(
-                                enemy_transform.translation.x, 
-                                enemy_transform.translation.y + (ENEMY_NN_SIZE.y / 2.0) + (fellow_size.y / 2.0),
-                                0.0);
-                            
-                            // check collision with fellow
-                            if collision_check(special_transform.translation, BALL_SIZE, fellow_pos, fellow_size) 
-                            {
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                        Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                                fellow_died = true;
-                                something_died = true;
-                                tmp_score += 100;
-                            }
-                        };
-
-                        // check carrier
-                        if collision_check(special_transform.translation, BALL_SIZE, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            carrier_died = true;
-                            something_died = true;
-                            tmp_score += 100;
-                        }
-
-                        if fellow_died {
-                            // destroy also carrier
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-
-                        if carrier_died {
-                            // destroy also fellow
-                            if enemy.fellow_enemy.is_some() {
-                                let fellow = enemy.fellow_enemy.as_mut().unwrap();
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                    Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                            }
-                        }
-                    }
-                    // others
-                    else {
-                        if collision_check(
-                            special_transform.translation, BALL_SIZE, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            if enemy.enemy_type == 0 {
-                                crate::explosions::spawn_flash(&mut commands);
-                                sfx.play(sounds.boom_base.clone());
-                                maze.score += 1000;
-                                maze.bases -= 1;
-                            }
-                            else {
-                                sfx.play(sounds.boom.clone());
-                                maze.score += 100;
-                            }
-                            something_died = true;
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-                    }
-                }
-                maze.score += tmp_score;
+            let hits = enemy_hits(special_transform.translation, BALL_SIZE, enemy_component, enemy_transform.translation, &maze, &enemy_defs);
+            for part in hits.into_iter().filter(|part| *part != HitPart::FellowItem) {
+                damage_events.send(DamageEvent { entity: enemy_entity, part, amount: INSTANT_KILL, hit_sound: false });
             }
         }
 
-        if something_died {
-            change_score_event.send(ScoreChange(maze.score));
-            change_base_count_event.send(BaseCountChange(maze.bases));
-        }
-
         // move ball
-        ball.duration -= 1;
-        if ball.duration == 0 {
+        ball.duration -= TIME_STEP;
+        if ball.duration <= 0.0 {
             commands.entity(special_entity).despawn_recursive();
             player.shooting_special = false;
         }
 
-        if ball.duration <= 6000 {
+        if ball.duration <= SPECIAL_FADING {
             special_sprite.color = ITEM_COLORS[ball.color_index];
             ball.color_index += 1;
             if ball.color_index >= ITEM_COLORS.len() {
                 ball.color_index = 0;
             }
-            ball.velocity.x -= ball.velocity.x * 0.0001;
-            ball.velocity.y -= ball.velocity.y * 0.0001;
+            ball.velocity -= ball.velocity * BALL_SLOWDOWN * TIME_STEP;
         }
         // println!("Ball velocity = {:?}", ball.velocity);
     }
@@ -443,17 +337,13 @@ pub fn special_ball_movement(
 
 pub fn special_missile_side_movement(
     mut commands: Commands, 
-    time: Res<Time>, 
     mut special_query: Query<(Entity, &mut Transform, &mut SpecialMissileSide)>,
     walls_query: Query<&WallComponent>,
-    mut enemies_query: Query<(Entity, &mut Transform, &mut EnemyComponent), Without<SpecialMissileSide>>,
+    enemies_query: Query<(Entity, &Transform, &EnemyComponent), Without<SpecialMissileSide>>,
     mut player: ResMut<Player>,
-    sfx: Res<AudioChannel<SfxChannel>>,
-    sounds: Res<Sounds>,
-    mut maze: ResMut<Maze>,
-    explosions_images: Res<ExplosionsImages>,
-    mut change_score_event: EventWriter<ScoreChange>,
-    mut change_base_count_event: EventWriter<BaseCountChange>) 
+    maze: Res<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    mut damage_events: EventWriter<DamageEvent>) 
 {
     for (special_entity, mut special_transform, mut missile_side) in special_query.iter_mut() {
         let mut force = Vec2::splat(0.0);
@@ -468,7 +358,7 @@ pub fn special_missile_side_movement(
             GameDirection::None => {}
         }
 
-        force *= time.delta_seconds();
+        force *= TIME_STEP;
         missile_side.velocity += force;
         // missile_side.velocity.x = missile_side.velocity.x.clamp(-400.0, 400.0);
         // missile_side.velocity.y = missile_side.velocity.y.clamp(-400.0, 400.0);
@@ -476,7 +366,7 @@ pub fn special_missile_side_movement(
         // calculate new position
         let mut position = Vec2::splat(0.0);
         position += missile_side.velocity;
-        position *= time.delta_seconds();
+        position *= TIME_STEP;
 
         // check collision with walls
         let target = special_transform.translation + Vec3::new(position.x, position.y, 0.0);
@@ -505,164 +395,41 @@ pub fn special_missile_side_movement(
         // move missile
         special_transform.translation = target;
 
-        // check collision with enemies
+        // check collision with enemies, the missile explodes on the first one
 
-        let mut something_died = false;
-        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter_mut() 
+        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter() 
         {
+            let hits = enemy_hits(special_transform.translation, MISSILE_SIDE_SIZE, enemy_component, enemy_transform.translation, &maze, &enemy_defs);
             let mut collided = false;
-
-            // from 10
-            if enemy_component.is_from_10 {
-                let room = &mut maze.rooms[enemy_component.room_seq];
-                            
-                if collision_check(special_transform.translation, MISSILE_SIDE_SIZE, 
-                    enemy_transform.translation, ENEMY_NN_SIZE) 
-                {
-                    for (idx, mut enemy) in room.enemies_from_10.iter_mut().enumerate() {
-                        if enemy.enemy_seq == enemy_component.enemy_seq {
-                            collided = true;
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            room.enemies_from_10.remove(idx);
-                            something_died = true;
-                            maze.score += 100;
-                            break;
-                        }
-                    }
-                }
-            }
-            else {
-                let mut tmp_score: u16 = 0;
-                {
-                    let enemy = &mut maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
-
-                    // 20 + fellow
-                    if enemy.enemy_type == 20 {
-                        let mut fellow_died = false;
-                        let mut carrier_died = false;
-
-                        if enemy.fellow_enemy.is_some() {
-                            let fellow = enemy.fellow_enemy.as_mut().unwrap();
-
-                            // prepare fellow size and position
-                            let mut fellow_size = ENEMY_NN_SIZE;
-                            if fellow.enemy_type == 7 {
-                                fellow_size = ENEMY_07_SIZE;
-                            }
-                            let fellow_pos = Vec3::new(
-                                enemy_transform.translation.x, 
-                                enemy_transform.translation.y + (ENEMY_NN_SIZE.y / 2.0) + (fellow_size.y / 2.0),
-                                0.0);
-                            
-                            // check collision with fellow
-                            if collision_check(special_transform.translation, MISSILE_SIDE_SIZE, fellow_pos, fellow_size) 
-                            {
-                                collided = true;
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                        Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                                fellow_died = true;
-                                something_died = true;
-                                tmp_score += 100;
-                            }
-                        };
-
-                        // check carrier
-                        if collision_check(special_transform.translation, MISSILE_SIDE_SIZE, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            collided = true;
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            carrier_died = true;
-                            something_died = true;
-                            tmp_score += 100;
-                        }
-
-                        if fellow_died {
-                            // destroy also carrier
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-
-                        if carrier_died {
-                            // destroy also fellow
-                            if enemy.fellow_enemy.is_some() {
-                                let fellow = enemy.fellow_enemy.as_mut().unwrap();
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                    Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                            }
-                        }
-                    }
-                    // others
-                    else {
-                        if collision_check(
-                            special_transform.translation, MISSILE_SIDE_SIZE, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            collided = true;
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            if enemy.enemy_type == 0 {
-                                crate::explosions::spawn_flash(&mut commands);
-                                sfx.play(sounds.boom_base.clone());
-                                maze.score += 1000;
-                                maze.bases -= 1;
-                            }
-                            else {
-                                sfx.play(sounds.boom.clone());
-                                maze.score += 100;
-                            }
-                            something_died = true;
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-                    }
-                }
-                maze.score += tmp_score;
+            for part in hits.into_iter().filter(|part| *part != HitPart::FellowItem) {
+                collided = true;
+                damage_events.send(DamageEvent { entity: enemy_entity, part, amount: INSTANT_KILL, hit_sound: false });
             }
 
             if collided {
                 commands.entity(special_entity).despawn_recursive();
                 player.shooting_special = false;
+                break;
             }
-        } // end for enemies
-
-        if something_died {
-            change_score_event.send(ScoreChange(maze.score));
-            change_base_count_event.send(BaseCountChange(maze.bases));
         }
     }
 }
 
 pub fn special_missile_down_movement(
     mut commands: Commands, 
-    time: Res<Time>, 
     mut special_query: Query<(Entity, &mut Transform, &mut SpecialMissileDown)>,
     walls_query: Query<&WallComponent>,
-    mut enemies_query: Query<(Entity, &mut Transform, &mut EnemyComponent), Without<SpecialMissileDown>>,
+    enemies_query: Query<(Entity, &Transform, &EnemyComponent), Without<SpecialMissileDown>>,
     mut player: ResMut<Player>,
-    sfx: Res<AudioChannel<SfxChannel>>,
-    sounds: Res<Sounds>,
-    mut maze: ResMut<Maze>,
-    explosions_images: Res<ExplosionsImages>,
-    mut change_score_event: EventWriter<ScoreChange>,
-    mut change_base_count_event: EventWriter<BaseCountChange>) 
+    maze: Res<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    mut damage_events: EventWriter<DamageEvent>) 
 {
     for (special_entity, mut special_transform, mut missile_side) in special_query.iter_mut() {
         let mut force = Vec2::splat(0.0);
         force += Vec2::new(0.0, -100.0);
 
-        force *= time.delta_seconds();
+        force *= TIME_STEP;
         missile_side.velocity += force;
         // missile_side.velocity.x = missile_side.velocity.x.clamp(-400.0, 400.0);
         // missile_side.velocity.y = missile_side.velocity.y.clamp(-400.0, 400.0);
@@ -670,7 +437,7 @@ pub fn special_missile_down_movement(
         // calculate new position
         let mut position = Vec2::splat(0.0);
         position += missile_side.velocity;
-        position *= time.delta_seconds();
+        position *= TIME_STEP;
 
         // check collision with walls
         let target = special_transform.translation + Vec3::new(position.x, position.y, 0.0);
@@ -699,166 +466,45 @@ pub fn special_missile_down_movement(
         // move missile
         special_transform.translation = target;
 
-        // check collision with enemies
+        // check collision with enemies, the missile explodes on the first one
 
-        let mut something_died = false;
-        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter_mut() 
+        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter() 
         {
+            let hits = enemy_hits(special_transform.translation, MISSILE_SIDE_DOWN, enemy_component, enemy_transform.translation, &maze, &enemy_defs);
             let mut collided = false;
-
-            // from 10
-            if enemy_component.is_from_10 {
-                let room = &mut maze.rooms[enemy_component.room_seq];
-                            
-                if collision_check(special_transform.translation, MISSILE_SIDE_DOWN, 
-                    enemy_transform.translation, ENEMY_NN_SIZE) 
-                {
-                    for (idx, mut enemy) in room.enemies_from_10.iter_mut().enumerate() {
-                        if enemy.enemy_seq == enemy_component.enemy_seq {
-                            collided = true;
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            room.enemies_from_10.remove(idx);
-                            something_died = true;
-                            maze.score += 100;
-                            break;
-                        }
-                    }
-                }
-            }
-            else {
-                let mut tmp_score: u16 = 0;
-                {
-                    let enemy = &mut maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
-
-                    // 20 + fellow
-                    if enemy.enemy_type == 20 {
-                        let mut fellow_died = false;
-                        let mut carrier_died = false;
-
-                        if enemy.fellow_enemy.is_some() {
-                            let fellow = enemy.fellow_enemy.as_mut().unwrap();
-
-                            // prepare fellow size and position
-                            let mut fellow_size = ENEMY_NN_SIZE;
-                            if fellow.enemy_type == 7 {
-                                fellow_size = ENEMY_07_SIZE;
-                            }
-                            let fellow_pos = Vec3::new(
-                                enemy_transform.translation.x, 
-                                enemy_transform.translation.y + (ENEMY_NN_SIZE.y / 2.0) + (fellow_size.y / 2.0),
-                                0.0);
-                            
-                            // check collision with fellow
-                            if collision_check(special_transform.translation, MISSILE_SIDE_DOWN, fellow_pos, fellow_size) 
-                            {
-                                collided = true;
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                        Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                                fellow_died = true;
-                                something_died = true;
-                                tmp_score += 100;
-                            }
-                        };
-
-                        // check carrier
-                        if collision_check(special_transform.translation, MISSILE_SIDE_DOWN, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            collided = true;
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            carrier_died = true;
-                            something_died = true;
-                            tmp_score += 100;
-                        }
-
-                        if fellow_died {
-                            // destroy also carrier
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-
-                        if carrier_died {
-                            // destroy also fellow
-                            if enemy.fellow_enemy.is_some() {
-                                let fellow = enemy.fellow_enemy.as_mut().unwrap();
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                    Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                            }
-                        }
-                    }
-                    // others
-                    else {
-                        if collision_check(
-                            special_transform.translation, MISSILE_SIDE_DOWN, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            collided = true;
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            if enemy.enemy_type == 0 {
-                                crate::explosions::spawn_flash(&mut commands);
-                                sfx.play(sounds.boom_base.clone());
-                                maze.score += 1000;
-                                maze.bases -= 1;
-                            }
-                            else {
-                                sfx.play(sounds.boom.clone());
-                                maze.score += 100;
-                            }
-                            commands.entity(enemy_entity).despawn_recursive();
-                            something_died = true;
-                        }
-                    }
-                }
-                maze.score += tmp_score;
+            for part in hits.into_iter().filter(|part| *part != HitPart::FellowItem) {
+                collided = true;
+                damage_events.send(DamageEvent { entity: enemy_entity, part, amount: INSTANT_KILL, hit_sound: false });
             }
 
             if collided {
                 commands.entity(special_entity).despawn_recursive();
                 player.shooting_special = false;
+                break;
             }
-        } // end for enemies
-
-        if something_died {
-            change_score_event.send(ScoreChange(maze.score));
-            change_base_count_event.send(BaseCountChange(maze.bases));
         }
     }
 }
 
 pub fn special_star_movement(
     mut commands: Commands, 
-    time: Res<Time>, 
     mut special_query: Query<(Entity, &mut Sprite, &mut Transform, &mut SpecialStar)>,
     walls_query: Query<&WallComponent>,
-    mut enemies_query: Query<(Entity, &mut Transform, &mut EnemyComponent), (Without<SpecialStar>, Without<PlayerComponent>)>,
+    enemies_query: Query<(Entity, &Transform, &EnemyComponent), (Without<SpecialStar>, Without<PlayerComponent>)>,
     mut player: ResMut<Player>,
     player_query: Query<&Transform, (With<PlayerComponent>, Without<SpecialStar>, Without<EnemyComponent>)>,
     sfx: Res<AudioChannel<SfxChannel>>,
     sounds: Res<Sounds>,
-    mut maze: ResMut<Maze>,
-    explosions_images: Res<ExplosionsImages>,
-    mut change_score_event: EventWriter<ScoreChange>,
-    mut change_base_count_event: EventWriter<BaseCountChange>) 
+    maze: Res<Maze>,
+    enemy_defs: Res<EnemyDefs>,
+    mut damage_events: EventWriter<DamageEvent>) 
 {
     let player_transform = player_query.single();
 
     for (special_entity, mut special_sprite, mut special_transform, mut star) in special_query.iter_mut() 
     {
-        if star.sound_delay > 0 {
-            star.sound_delay -= 1;
+        if star.sound_delay > 0.0 {
+            star.sound_delay -= TIME_STEP;
         }
 
         let mut force = Vec2::new(
@@ -867,7 +513,7 @@ pub fn special_star_movement(
 
         if force.length() > 0.0 {
             force = force.normalize() * STAR_FORCE;
-            force *= time.delta_seconds();
+            force *= TIME_STEP;
         }
         // println!("Star force = {:?}", force);
 
@@ -879,7 +525,7 @@ pub fn special_star_movement(
         // calculate new position
         let mut position = Vec2::splat(0.0);
         position += star.velocity;
-        position *= time.delta_seconds();
+        position *= TIME_STEP;
 
         // check collision with walls
 
@@ -891,9 +537,9 @@ pub fn special_star_movement(
             special_transform.translation = target;
         }
         else {
-            if star.sound_delay == 0 {
+            if star.sound_delay <= 0.0 {
                 sfx.play(sounds.ball_bounce.clone());
-                star.sound_delay = 100;
+                star.sound_delay = STAR_SOUND_DELAY;
             }
             star.velocity.x *= -0.5;
         }
@@ -906,9 +552,9 @@ pub fn special_star_movement(
             special_transform.translation = target;
         }
         else {
-            if star.sound_delay == 0 {
+            if star.sound_delay <= 0.0 {
                 sfx.play(sounds.ball_bounce.clone());
-                star.sound_delay = 100;
+                star.sound_delay = STAR_SOUND_DELAY;
             }
             star.velocity.y *= -0.5;
         }
@@ -916,36 +562,36 @@ pub fn special_star_movement(
         // check screen edges
 
         if special_transform.translation.x < STAR_W2 {
-            if star.sound_delay == 0 {
+            if star.sound_delay <= 0.0 {
                 sfx.play(sounds.ball_bounce.clone());
-                star.sound_delay = 100;
+                star.sound_delay = STAR_SOUND_DELAY;
             }
             special_transform.translation.x = STAR_W2;
             star.velocity.x *= -0.5;
         }
 
         if special_transform.translation.x > WINDOW_W - STAR_W2 {
-            if star.sound_delay == 0 {
+            if star.sound_delay <= 0.0 {
                 sfx.play(sounds.ball_bounce.clone());
-                star.sound_delay = 100;
+                star.sound_delay = STAR_SOUND_DELAY;
             }
             special_transform.translation.x = WINDOW_W - STAR_W2;
             star.velocity.x *= -0.5;
         }
 
         if special_transform.translation.y > WINDOW_H - INFO_BAR_H - STAR_H2 {
-            if star.sound_delay == 0 {
+            if star.sound_delay <= 0.0 {
                 sfx.play(sounds.ball_bounce.clone());
-                star.sound_delay = 100;
+                star.sound_delay = STAR_SOUND_DELAY;
             }
             special_transform.translation.y = WINDOW_H - INFO_BAR_H - STAR_H2;
             star.velocity.y *= -0.5;
         }
 
         if special_transform.translation.y < STAR_H2 {
-            if star.sound_delay == 0 {
+            if star.sound_delay <= 0.0 {
                 sfx.play(sounds.ball_bounce.clone());
-                star.sound_delay = 100;
+                star.sound_delay = STAR_SOUND_DELAY;
             }
             special_transform.translation.y = STAR_H2;
             star.velocity.y *= -0.5;
@@ -953,136 +599,20 @@ pub fn special_star_movement(
 
         // check collision with enemies
 
-        let mut something_died = false;
-        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter_mut() 
+        for (enemy_entity, enemy_transform, enemy_component) in enemies_query.iter() 
         {
-            // from 10
-            if enemy_component.is_from_10 {
-                let room = &mut maze.rooms[enemy_component.room_seq];
-                            
-                if collision_check(special_transform.translation, STAR_SIZE, 
-                    enemy_transform.translation, ENEMY_NN_SIZE) 
-                {
-                    for (idx, mut enemy) in room.enemies_from_10.iter_mut().enumerate() {
-                        if enemy.enemy_seq == enemy_component.enemy_seq {
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            room.enemies_from_10.remove(idx);
-                            something_died = true;
-                            maze.score += 100;
-                            break;
-                        }
-                    }
-                }
+            let hits = enemy_hits(special_transform.translation, STAR_SIZE, enemy_component, enemy_transform.translation, &maze, &enemy_defs);
+            for part in hits.into_iter().filter(|part| *part != HitPart::FellowItem) {
+                damage_events.send(DamageEvent { entity: enemy_entity, part, amount: INSTANT_KILL, hit_sound: false });
             }
-            else {
-                let mut tmp_score: u16 = 0;
-                {
-                    let enemy = &mut maze.rooms[enemy_component.room_seq].enemies[enemy_component.enemy_seq];
-
-                    // 20 + fellow
-                    if enemy.enemy_type == 20 {
-                        let mut fellow_died = false;
-                        let mut carrier_died = false;
-
-                        if enemy.fellow_enemy.is_some() {
-                            let fellow = enemy.fellow_enemy.as_mut().unwrap();
-
-                            // prepare fellow size and position
-                            let mut fellow_size = ENEMY_NN_SIZE;
-                            if fellow.enemy_type == 7 {
-                                fellow_size = ENEMY_07_SIZE;
-                            }
-                            let fellow_pos = Vec3::new(
-                                enemy_transform.translation.x, 
-                                enemy_transform.translation.y + (ENEMY_NN_SIZE.y / 2.0) + (fellow_size.y / 2.0),
-                                0.0);
-                            
-                            // check collision with fellow
-                            if collision_check(special_transform.translation, STAR_SIZE, fellow_pos, fellow_size) 
-                            {
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                        Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                                fellow_died = true;
-                                something_died = true;
-                                tmp_score += 100;
-                            }
-                        };
-
-                        // check carrier
-                        if collision_check(special_transform.translation, STAR_SIZE, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                            carrier_died = true;
-                            something_died = true;
-                            tmp_score += 100;
-                        }
-
-                        if fellow_died {
-                            // destroy also carrier
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            sfx.play(sounds.boom.clone());
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-
-                        if carrier_died {
-                            // destroy also fellow
-                            if enemy.fellow_enemy.is_some() {
-                                let fellow = enemy.fellow_enemy.as_mut().unwrap();
-                                fellow.health = 0;
-                                crate::explosions::spawn_boom(&mut commands, 
-                                    Vec3::new(enemy_transform.translation.x, enemy_transform.translation.y + ENEMY_NN_SIZE.y, 0.0), &explosions_images);
-                                sfx.play(sounds.boom.clone());
-                                commands.entity(enemy_entity).despawn_recursive();
-                            }
-                        }
-                    }
-                    // others
-                    else {
-                        if collision_check(
-                            special_transform.translation, STAR_SIZE, enemy_transform.translation, ENEMY_NN_SIZE) 
-                        {
-                            enemy.health = 0;
-                            crate::explosions::spawn_boom(&mut commands, enemy_transform.translation, &explosions_images);
-                            if enemy.enemy_type == 0 {
-                                crate::explosions::spawn_flash(&mut commands);
-                                sfx.play(sounds.boom_base.clone());
-                                maze.score += 1000;
-                                maze.bases -= 1;
-                            }
-                            else {
-                                sfx.play(sounds.boom.clone());
-                                maze.score += 100;
-                            }
-                            something_died = true;
-                            commands.entity(enemy_entity).despawn_recursive();
-                        }
-                    }
-                }
-                maze.score += tmp_score;
-            }
-        } // end for enemies
-
-        if something_died {
-            change_score_event.send(ScoreChange(maze.score));
-            change_base_count_event.send(BaseCountChange(maze.bases));
         }
 
-        star.duration -= 1;
-        if star.duration == 0 {
+        star.duration -= TIME_STEP;
+        if star.duration <= 0.0 {
             commands.entity(special_entity).despawn_recursive();
             player.shooting_special = false;
         }
-        else if star.duration <= 6000 {
+        else if star.duration <= SPECIAL_FADING {
             special_sprite.color = ITEM_COLORS[star.color_index];
             star.color_index += 1;
             if star.color_index >= ITEM_COLORS.len() {
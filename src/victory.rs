@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
 use crate::{GameState, WINDOW_W2, WINDOW_H2, mainmenu::UiAssets, maze::Maze};
+use crate::actions::ActionInput;
 
 pub struct VictoryPlugin;
 
@@ -55,9 +56,9 @@ fn despawn_victory(mut commands: Commands, text_query: Query<Entity, With<Victor
     }
 }
 
-fn victory_keyboard_input(mut keyboard: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
-    if keyboard.get_just_pressed().len() != 0 {
-        keyboard.clear();
+fn victory_keyboard_input(mut actions: ResMut<ActionInput>, mut state: ResMut<State<GameState>>) {
+    if actions.any_just_pressed() {
+        actions.clear();
         state.set(GameState::Menu).expect("Victory: Failed to change state!");
     }
 }